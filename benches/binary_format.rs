@@ -0,0 +1,56 @@
+/* Size and parse time of the binary board format against json, on a board of freehand strokes. */
+use geometry::point::point_2d::Point2D;
+use standard_tool_plugin::tool::{Interaction, PointingDevice};
+use std::time::{Duration, Instant};
+use whiteboard_rs::document::Document;
+use whiteboard_rs::element::ElementType;
+use whiteboard_rs::Whiteboard;
+
+const STROKES: usize = 200;
+const POINTS: usize = 250;
+const RUNS: u32 = 20;
+
+fn main() {
+    let mut whiteboard: Whiteboard = Whiteboard::new("bench");
+    whiteboard.activate_move_draw(ElementType::FreeHand).expect("Can't activate free hand");
+
+    /* Wavy strokes sampled like pointer events, in whole and half pixels. */
+    for stroke in 0..STROKES {
+        let top: f64 = (stroke * 12) as f64;
+        let point = |index: usize| Point2D::new(
+            (index as f64 * 2.5).round() / 2.0,
+            top + ((index as f64 / 8.0 + stroke as f64).sin() * 24.0).round() / 2.0,
+        );
+
+        whiteboard.interact(Interaction::PointerDown(point(0), PointingDevice::Mouse));
+        for index in 1..POINTS {
+            whiteboard.interact(Interaction::PointerMove(point(index), PointingDevice::Mouse));
+        }
+        whiteboard.interact(Interaction::PointerUp(point(POINTS - 1), PointingDevice::Mouse));
+    }
+    whiteboard.run_pending();
+
+    let json: String = whiteboard.to_json();
+    let bytes: Vec<u8> = whiteboard.to_bytes();
+
+    let time = |parse: &dyn Fn()| -> Duration {
+        let start: Instant = Instant::now();
+        for _ in 0..RUNS {
+            parse();
+        }
+
+        start.elapsed() / RUNS
+    };
+
+    let json_time: Duration = time(&|| {
+        Document::from_json(&json).expect("Can't parse json");
+    });
+    let binary_time: Duration = time(&|| {
+        Document::from_bytes(&bytes).expect("Can't parse bytes");
+    });
+
+    println!("{STROKES} strokes of {POINTS} points");
+    println!("json:   {:>10} bytes, parsed in {json_time:?}", json.len());
+    println!("binary: {:>10} bytes, parsed in {binary_time:?}", bytes.len());
+    println!("binary is {:.1}% of the json size", bytes.len() as f64 / json.len() as f64 * 100.0);
+}
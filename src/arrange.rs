@@ -0,0 +1,120 @@
+use crate::bounding_box::BoundingBox;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[derive(Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub enum AlignEdge {
+    Left,
+    Center,
+    Right,
+    Top,
+    Middle,
+    Bottom,
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub enum Dimension {
+    Width,
+    Height,
+    Both,
+}
+
+/*
+ * Target boxes for the operations below, in the order of the input boxes.
+ * Boxes are only moved, except for match_size which only resizes.
+ */
+
+/* Line boxes up with the edge of the box enclosing all of them. */
+pub fn align(boxes: &[BoundingBox], edge: AlignEdge) -> Vec<BoundingBox> {
+    let Some(bounds) = boxes.iter().copied().reduce(|bounds, bounding_box| bounds.union(&bounding_box)) else {
+        return vec![];
+    };
+    let (center_x, center_y) = bounds.center();
+
+    boxes
+        .iter()
+        .map(|bounding_box| {
+            let (x, y) = match edge {
+                AlignEdge::Left => (bounds.left, bounding_box.top),
+                AlignEdge::Center => (center_x - bounding_box.width() / 2.0, bounding_box.top),
+                AlignEdge::Right => (bounds.right - bounding_box.width(), bounding_box.top),
+                AlignEdge::Top => (bounding_box.left, bounds.top),
+                AlignEdge::Middle => (bounding_box.left, center_y - bounding_box.height() / 2.0),
+                AlignEdge::Bottom => (bounding_box.left, bounds.bottom - bounding_box.height()),
+            };
+
+            BoundingBox::new(x, y, bounding_box.width(), bounding_box.height())
+        })
+        .collect()
+}
+
+/* Equal gaps between neighbours along the axis. The first and the last boxes stay in place. */
+pub fn distribute(boxes: &[BoundingBox], axis: Axis) -> Vec<BoundingBox> {
+    let start = |bounding_box: &BoundingBox| match axis {
+        Axis::Horizontal => bounding_box.left,
+        Axis::Vertical => bounding_box.top,
+    };
+    let length = |bounding_box: &BoundingBox| match axis {
+        Axis::Horizontal => bounding_box.width(),
+        Axis::Vertical => bounding_box.height(),
+    };
+
+    let mut order: Vec<usize> = (0..boxes.len()).collect();
+    order.sort_by(|a, b| start(&boxes[*a]).total_cmp(&start(&boxes[*b])));
+
+    let mut result: Vec<BoundingBox> = boxes.to_vec();
+
+    let (Some(first), Some(last)) = (order.first(), order.last()) else {
+        return result;
+    };
+    if boxes.len() < 3 {
+        return result;
+    }
+
+    let span: f64 = start(&boxes[*last]) + length(&boxes[*last]) - start(&boxes[*first]);
+    let occupied: f64 = boxes.iter().map(length).sum();
+    let gap: f64 = (span - occupied) / (boxes.len() - 1) as f64;
+
+    let mut position: f64 = start(&boxes[*first]);
+    for index in order {
+        let bounding_box: &BoundingBox = &boxes[index];
+
+        result[index] = match axis {
+            Axis::Horizontal => BoundingBox::new(position, bounding_box.top, bounding_box.width(), bounding_box.height()),
+            Axis::Vertical => BoundingBox::new(bounding_box.left, position, bounding_box.width(), bounding_box.height()),
+        };
+
+        position += length(bounding_box) + gap;
+    }
+
+    result
+}
+
+/* Resize boxes to the largest size among them, keeping their top left corners. */
+pub fn match_size(boxes: &[BoundingBox], dimension: Dimension) -> Vec<BoundingBox> {
+    let width: f64 = boxes.iter().map(BoundingBox::width).fold(0.0, f64::max);
+    let height: f64 = boxes.iter().map(BoundingBox::height).fold(0.0, f64::max);
+
+    boxes
+        .iter()
+        .map(|bounding_box| {
+            let (new_width, new_height) = match dimension {
+                Dimension::Width => (width, bounding_box.height()),
+                Dimension::Height => (bounding_box.width(), height),
+                Dimension::Both => (width, height),
+            };
+
+            BoundingBox::new(bounding_box.left, bounding_box.top, new_width, new_height)
+        })
+        .collect()
+}
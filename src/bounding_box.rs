@@ -0,0 +1,84 @@
+use geometry::point::point_2d::Point2D;
+
+/* Axis aligned bounding box in board coordinates. */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoundingBox {
+    pub left: f64,
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+}
+
+impl BoundingBox {
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self {
+            left: x.min(x + width),
+            top: y.min(y + height),
+            right: x.max(x + width),
+            bottom: y.max(y + height),
+        }
+    }
+
+    pub fn from_point(x: f64, y: f64) -> Self {
+        Self { left: x, top: y, right: x, bottom: y }
+    }
+
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Point2D>) -> Option<Self> {
+        points
+            .into_iter()
+            .map(|point| Self::from_point(point.x, point.y))
+            .reduce(|bounding_box, point| bounding_box.union(&point))
+    }
+
+    pub fn width(&self) -> f64 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> f64 {
+        self.bottom - self.top
+    }
+
+    pub fn center(&self) -> (f64, f64) {
+        ((self.left + self.right) / 2.0, (self.top + self.bottom) / 2.0)
+    }
+
+    pub fn union(&self, other: &BoundingBox) -> Self {
+        Self {
+            left: self.left.min(other.left),
+            top: self.top.min(other.top),
+            right: self.right.max(other.right),
+            bottom: self.bottom.max(other.bottom),
+        }
+    }
+
+    pub fn inflate(&self, amount: f64) -> Self {
+        Self {
+            left: self.left - amount,
+            top: self.top - amount,
+            right: self.right + amount,
+            bottom: self.bottom + amount,
+        }
+    }
+
+    /* Edges and size are finite numbers. Models with huge or broken coordinates can have boxes that aren't. */
+    pub fn is_finite(&self) -> bool {
+        [self.left, self.top, self.right, self.bottom, self.width(), self.height()]
+            .iter()
+            .all(|value| value.is_finite())
+    }
+
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.left <= other.right && other.left <= self.right &&
+        self.top <= other.bottom && other.top <= self.bottom
+    }
+
+    pub fn contains(&self, other: &BoundingBox) -> bool {
+        self.left <= other.left && other.right <= self.right &&
+        self.top <= other.top && other.bottom <= self.bottom
+    }
+
+    pub fn contains_point(&self, x: f64, y: f64) -> bool {
+        self.left <= x && x <= self.right &&
+        self.top <= y && y <= self.bottom
+    }
+}
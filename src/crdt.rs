@@ -0,0 +1,450 @@
+pub mod rga;
+
+use crate::crdt::rga::Rga;
+use crate::document::binary;
+use crate::document::DocumentError;
+use crate::element::id::Id;
+use crate::element::json_entity::JsonEntity;
+use crate::element::sticky_note_model::StickyNoteModel;
+use crate::element::text_box_model::TextBoxModel;
+use crate::element::ElementType;
+use crate::mode::BoardMode;
+use crate::schema;
+use crate::style::Style;
+use crate::text::measure::TextMeasure;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+const STYLE_FIELD: &str = "style";
+const MODEL_FIELD_PREFIX: &str = "model.";
+const TEXT_FIELD: &str = "text";
+
+/* Lamport timestamp of the operation with its peer as a tiebreaker. Orders all operations the same way on every peer. */
+#[derive(Clone, Serialize, Deserialize, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct OpId {
+    pub lamport: u64,
+    pub peer: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub enum Operation {
+    /* Entity put into z-order after the position. The operation id is the new position. */
+    Place { id: Id, after: Option<OpId> },
+    /* Entity at the position is taken out of z-order. */
+    Remove { position: OpId },
+    /* Last writer wins value of the model field or style. */
+    Set { id: Id, field: String, value: Value },
+    /* Char of the text inserted after the position. The operation id is the new position. */
+    Insert { id: Id, after: Option<OpId>, char: char },
+    Delete { id: Id, position: OpId },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Op {
+    pub id: OpId,
+    /* Number of the operation among operations of its peer, without gaps. Used by state vectors. */
+    pub seq: u64,
+    pub operation: Operation,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Update {
+    ops: Vec<Op>,
+}
+
+/* Operations of each peer received without gaps. */
+pub type StateVector = BTreeMap<String, u64>;
+
+#[derive(Default)]
+struct EntityState {
+    fields: BTreeMap<String, (OpId, Value)>,
+    text: Option<Rga<char>>,
+}
+
+/*
+ * Board replica for conflict free editing.
+ * Z-order and texts are sequences, other model fields and style are last writer wins registers.
+ * Local changes are recorded as operations by diffing entity json against the replica,
+ * remote operations are applied in any order and any number of times, every peer converges to the same board.
+ */
+pub struct Replica {
+    peer: String,
+    lamport: u64,
+    seq: u64,
+    /* All known operations, sent to peers missing them. */
+    log: Vec<Op>,
+    received: StateVector,
+    /* Received after a gap in the peer sequence. */
+    ahead: HashSet<(String, u64)>,
+    /* Operations whose anchors are not known yet. */
+    pending: Vec<Op>,
+    order: Rga<Id>,
+    /* Visible positions of each entity in the z-order. */
+    placed: HashMap<Id, HashSet<OpId>>,
+    entities: HashMap<Id, EntityState>,
+    outgoing: Vec<Op>,
+    /* Operations of peers their mode doesn't allow are relayed, but never merged into the board. */
+    peer_modes: HashMap<String, BoardMode>,
+}
+
+impl Replica {
+    pub fn new(peer: &str) -> Self {
+        Self {
+            peer: peer.to_string(),
+            lamport: 0,
+            seq: 0,
+            log: vec![],
+            received: StateVector::new(),
+            ahead: HashSet::new(),
+            pending: vec![],
+            order: Rga::new(),
+            placed: HashMap::new(),
+            entities: HashMap::new(),
+            outgoing: vec![],
+            peer_modes: HashMap::new(),
+        }
+    }
+
+    /*
+     * Record local change of the entity, or its creation. New entity is placed right above the entity below it
+     * on the board, so an entity put back into its place by undo gets the same place on other peers.
+     */
+    pub fn record(&mut self, json_entity: &JsonEntity, below: Option<&Id>) {
+        let id: &Id = &json_entity.id;
+
+        if !self.is_placed(id) {
+            let after: Option<OpId> = match below {
+                Some(below) => self.placed
+                    .get(below)
+                    .and_then(|positions| positions.iter().max())
+                    .or(self.order.last_id())
+                    .cloned(),
+                None => None,
+            };
+            self.local(Operation::Place { id: id.clone(), after });
+        }
+
+        let has_text: bool = Self::has_text(id);
+
+        let mut fields: Vec<(String, Value)> = match &json_entity.model {
+            Value::Object(model) => model
+                .iter()
+                .filter(|(name, _)| !(has_text && name.as_str() == TEXT_FIELD))
+                .map(|(name, value)| (format!("{MODEL_FIELD_PREFIX}{name}"), value.clone()))
+                .collect(),
+            /* Models are structs, this only keeps replica lossless. */
+            model => vec![("model".to_string(), model.clone())],
+        };
+        fields.push((STYLE_FIELD.to_string(), serde_json::to_value(&json_entity.style).unwrap_or(Value::Null)));
+
+        for (field, value) in fields {
+            let current: Option<&Value> = self.entities
+                .get(id)
+                .and_then(|state| state.fields.get(&field))
+                .map(|(_, value)| value);
+
+            if current != Some(&value) {
+                self.local(Operation::Set { id: id.clone(), field, value });
+            }
+        }
+
+        if has_text {
+            let text: &str = json_entity.model.get(TEXT_FIELD).and_then(Value::as_str).unwrap_or_default();
+            self.record_text(id, text);
+        }
+    }
+
+    pub fn record_removal(&mut self, id: &Id) {
+        let positions: Vec<OpId> = self.placed.get(id).into_iter().flatten().cloned().collect();
+
+        for position in positions {
+            self.local(Operation::Remove { position });
+        }
+    }
+
+    /*
+     * Integrate remote operations. Returns ids of the entities that may have changed.
+     * Operations their peer's mode doesn't allow are kept in the log for other peers only,
+     * they never reach the merged state, so neither the board nor later local changes carry them.
+     */
+    pub fn apply(&mut self, update: &[u8]) -> Result<HashSet<Id>, DocumentError> {
+        let update: Update = decode(update)?;
+        let mut affected: HashSet<Id> = HashSet::new();
+
+        for op in update.ops {
+            if self.is_known(&op) {
+                continue;
+            }
+
+            self.mark_received(&op);
+            self.lamport = self.lamport.max(op.id.lamport);
+
+            self.log.push(op.clone());
+
+            if self.permits(&op) {
+                self.pending.push(op);
+            }
+        }
+
+        /* Integrating an operation may provide anchors for others. */
+        loop {
+            let pending: Vec<Op> = std::mem::take(&mut self.pending);
+            let count: usize = pending.len();
+
+            for op in pending {
+                match self.integrate(&op) {
+                    Some(id) => {
+                        affected.insert(id);
+                    }
+                    None => self.pending.push(op),
+                }
+            }
+
+            if self.pending.len() == count {
+                break;
+            }
+        }
+
+        Ok(affected)
+    }
+
+    /* Entity json as merged by the replica, None if the entity is not on the board. Text is laid out again. */
+    pub fn materialize(&self, id: &Id, measure: &dyn TextMeasure) -> serde_json::Result<Option<JsonEntity>> {
+        if !self.is_placed(id) {
+            return Ok(None);
+        }
+        let Some(state) = self.entities.get(id) else {
+            return Ok(None);
+        };
+
+        let mut model: Value = Value::Object(Map::new());
+        let mut style: Option<Style> = None;
+
+        for (field, (_, value)) in &state.fields {
+            if field == STYLE_FIELD {
+                style = serde_json::from_value(value.clone())?;
+            } else if field == "model" {
+                model = value.clone();
+            } else if let (Some(name), Value::Object(model)) = (field.strip_prefix(MODEL_FIELD_PREFIX), &mut model) {
+                model.insert(name.to_string(), value.clone());
+            }
+        }
+
+        if let (Some(text), Value::Object(model)) = (&state.text, &mut model) {
+            let text: String = text.visible().map(|(_, char)| *char).collect();
+            model.insert(TEXT_FIELD.to_string(), Value::String(text));
+        }
+
+        let model: Value = match id.element_type() {
+            ElementType::Text => {
+                let mut text_box: TextBoxModel = serde_json::from_value(model)?;
+                text_box.relayout(measure);
+                serde_json::to_value(text_box)?
+            }
+            ElementType::StickyNote => {
+                let mut sticky_note: StickyNoteModel = serde_json::from_value(model)?;
+                sticky_note.fit(measure);
+                serde_json::to_value(sticky_note)?
+            }
+            _ => model,
+        };
+
+        Ok(Some(JsonEntity { id: id.clone(), model, style, version: schema::VERSION }))
+    }
+
+    /* Entities on the board in z-order. */
+    pub fn order(&self) -> Vec<Id> {
+        let mut seen: HashSet<&Id> = HashSet::new();
+
+        self.order
+            .visible()
+            .map(|(_, id)| id)
+            .filter(|id| seen.insert(*id))
+            .cloned()
+            .collect()
+    }
+
+    pub fn encode_state_vector(&self) -> serde_json::Result<Vec<u8>> {
+        encode(&self.received)
+    }
+
+    /* Operations the peer with the state vector is missing. */
+    pub fn encode_update(&self, state_vector: &StateVector) -> serde_json::Result<Vec<u8>> {
+        let ops: Vec<Op> = self.log
+            .iter()
+            .filter(|op| op.seq > state_vector.get(&op.id.peer).copied().unwrap_or(0))
+            .cloned()
+            .collect();
+
+        encode(&Update { ops })
+    }
+
+    /* Local operations since the last call, empty if there are none. */
+    pub fn take_outgoing(&mut self) -> serde_json::Result<Vec<u8>> {
+        if self.outgoing.is_empty() {
+            return Ok(vec![]);
+        }
+
+        encode(&Update { ops: std::mem::take(&mut self.outgoing) })
+    }
+
+    pub fn set_peer_mode(&mut self, peer: &str, mode: BoardMode) {
+        self.peer_modes.insert(peer.to_string(), mode);
+    }
+
+    fn permits(&self, op: &Op) -> bool {
+        let mode: BoardMode = self.peer_modes.get(&op.id.peer).copied().unwrap_or_default();
+
+        let id: Option<&Id> = match &op.operation {
+            Operation::Place { id, .. }
+            | Operation::Set { id, .. }
+            | Operation::Insert { id, .. }
+            | Operation::Delete { id, .. } => Some(id),
+            /* Removal of an unknown position is allowed only in edit mode. */
+            Operation::Remove { position } => self.order.get(position),
+        };
+
+        mode.allows(id.map(|id| *id.element_type()))
+    }
+
+    fn has_text(id: &Id) -> bool {
+        matches!(id.element_type(), ElementType::Text | ElementType::StickyNote)
+    }
+
+    fn is_placed(&self, id: &Id) -> bool {
+        self.placed.get(id).is_some_and(|positions| !positions.is_empty())
+    }
+
+    /* Replace differing middle part of the text, common prefix and suffix are kept. */
+    fn record_text(&mut self, id: &Id, text: &str) {
+        let current: Vec<(OpId, char)> = self.entities
+            .get(id)
+            .and_then(|state| state.text.as_ref())
+            .map(|text| text.visible().map(|(position, char)| (position.clone(), *char)).collect())
+            .unwrap_or_default();
+        let chars: Vec<char> = text.chars().collect();
+
+        let prefix: usize = current
+            .iter()
+            .zip(&chars)
+            .take_while(|((_, current), new)| current == *new)
+            .count();
+        let suffix: usize = current[prefix..]
+            .iter()
+            .rev()
+            .zip(chars[prefix..].iter().rev())
+            .take_while(|((_, current), new)| current == *new)
+            .count();
+
+        for (position, _) in &current[prefix..current.len() - suffix] {
+            self.local(Operation::Delete { id: id.clone(), position: position.clone() });
+        }
+
+        let mut after: Option<OpId> = prefix.checked_sub(1).map(|index| current[index].0.clone());
+        for char in &chars[prefix..chars.len() - suffix] {
+            after = Some(self.local(Operation::Insert { id: id.clone(), after, char: *char }));
+        }
+    }
+
+    fn local(&mut self, operation: Operation) -> OpId {
+        self.lamport += 1;
+        self.seq += 1;
+
+        let op: Op = Op {
+            id: OpId { lamport: self.lamport, peer: self.peer.clone() },
+            seq: self.seq,
+            operation,
+        };
+
+        self.received.insert(self.peer.clone(), self.seq);
+        self.integrate(&op);
+        self.log.push(op.clone());
+        self.outgoing.push(op.clone());
+
+        op.id
+    }
+
+    /* Returns the affected entity, None if the operation has to wait for its anchor. */
+    fn integrate(&mut self, op: &Op) -> Option<Id> {
+        match &op.operation {
+            Operation::Place { id, after } => {
+                if !self.order.insert(op.id.clone(), after.as_ref(), id.clone()) {
+                    return None;
+                }
+
+                self.placed.entry(id.clone()).or_default().insert(op.id.clone());
+
+                Some(id.clone())
+            }
+            Operation::Remove { position } => {
+                let id: Id = self.order.get(position)?.clone();
+                self.order.delete(position);
+
+                if let Some(positions) = self.placed.get_mut(&id) {
+                    positions.remove(position);
+                }
+
+                Some(id)
+            }
+            Operation::Set { id, field, value } => {
+                let fields = &mut self.entities.entry(id.clone()).or_default().fields;
+
+                if fields.get(field).is_none_or(|(stamp, _)| *stamp < op.id) {
+                    fields.insert(field.clone(), (op.id.clone(), value.clone()));
+                }
+
+                Some(id.clone())
+            }
+            Operation::Insert { id, after, char } => self.entities
+                .entry(id.clone())
+                .or_default()
+                .text
+                .get_or_insert_with(Rga::new)
+                .insert(op.id.clone(), after.as_ref(), *char)
+                .then(|| id.clone()),
+            Operation::Delete { id, position } => self.entities
+                .get_mut(id)?
+                .text
+                .as_mut()?
+                .delete(position)
+                .then(|| id.clone()),
+        }
+    }
+
+    fn is_known(&self, op: &Op) -> bool {
+        op.seq <= self.received.get(&op.id.peer).copied().unwrap_or(0)
+            || self.ahead.contains(&(op.id.peer.clone(), op.seq))
+    }
+
+    fn mark_received(&mut self, op: &Op) {
+        let peer: &String = &op.id.peer;
+        let mut received: u64 = self.received.get(peer).copied().unwrap_or(0);
+
+        if op.seq != received + 1 {
+            self.ahead.insert((peer.clone(), op.seq));
+            return;
+        }
+
+        received += 1;
+        while self.ahead.remove(&(peer.clone(), received + 1)) {
+            received += 1;
+        }
+
+        self.received.insert(peer.clone(), received);
+    }
+}
+
+/* Updates and state vectors are sent in the compact binary form of their json. */
+fn encode<T: Serialize>(value: &T) -> serde_json::Result<Vec<u8>> {
+    Ok(binary::encode(&serde_json::to_value(value)?))
+}
+
+fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, DocumentError> {
+    Ok(serde_json::from_value(binary::decode(bytes)?)?)
+}
+
+pub fn decode_state_vector(bytes: &[u8]) -> Result<StateVector, DocumentError> {
+    decode(bytes)
+}
@@ -0,0 +1,88 @@
+use crate::crdt::OpId;
+use std::collections::HashMap;
+
+struct Element<T> {
+    id: OpId,
+    value: T,
+    deleted: bool,
+}
+
+/*
+ * Replicated growable array. Each element is anchored to the element it was inserted after,
+ * concurrent inserts after the same anchor are ordered by their ids, newer first.
+ * Deleted elements are kept as tombstones, later inserts may still be anchored to them.
+ */
+pub struct Rga<T> {
+    elements: Vec<Element<T>>,
+    /* Index of every element in elements, shifted by inserts before it. */
+    indices: HashMap<OpId, usize>,
+}
+
+impl<T> Rga<T> {
+    pub fn new() -> Self {
+        Self {
+            elements: vec![],
+            indices: HashMap::new(),
+        }
+    }
+
+    /* Returns false if the anchor is not known yet, the insert has to wait for it. */
+    pub fn insert(&mut self, id: OpId, after: Option<&OpId>, value: T) -> bool {
+        if self.indices.contains_key(&id) {
+            return true;
+        }
+
+        let mut index: usize = match after {
+            None => 0,
+            Some(after) => match self.position(after) {
+                Some(position) => position + 1,
+                None => return false,
+            },
+        };
+
+        /* Skip newer inserts after the same anchor together with everything inserted after them, they all have greater ids. */
+        while index < self.elements.len() && self.elements[index].id > id {
+            index += 1;
+        }
+
+        if index < self.elements.len() {
+            self.indices.values_mut().filter(|position| **position >= index).for_each(|position| *position += 1);
+        }
+
+        self.indices.insert(id.clone(), index);
+        self.elements.insert(index, Element { id, value, deleted: false });
+
+        true
+    }
+
+    /* Returns false if the element is not known yet. */
+    pub fn delete(&mut self, id: &OpId) -> bool {
+        match self.position(id) {
+            Some(position) => {
+                self.elements[position].deleted = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get(&self, id: &OpId) -> Option<&T> {
+        self.position(id).map(|position| &self.elements[position].value)
+    }
+
+    /* Id of the last element, deleted or not. New elements are appended after it. */
+    pub fn last_id(&self) -> Option<&OpId> {
+        self.elements.last().map(|element| &element.id)
+    }
+
+    pub fn visible(&self) -> impl Iterator<Item = (&OpId, &T)> {
+        self.elements
+            .iter()
+            .filter(|element| !element.deleted)
+            .map(|element| (&element.id, &element.value))
+    }
+
+    fn position(&self, id: &OpId) -> Option<usize> {
+        self.indices.get(id).copied()
+    }
+}
@@ -0,0 +1,106 @@
+pub mod binary;
+pub mod excalidraw;
+
+use crate::document::binary::DecodeError;
+use crate::element::id::Id;
+use crate::element::json_entity::JsonEntity;
+use crate::schema;
+use crate::view_port::{LockError, ViewPort};
+use entity_model_feature::entity::Entity;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug)]
+pub enum DocumentError {
+    Lock,
+    Json(serde_json::Error),
+    Binary(DecodeError),
+}
+
+impl From<LockError<'_>> for DocumentError {
+    fn from(_: LockError) -> Self {
+        DocumentError::Lock
+    }
+}
+
+impl From<serde_json::Error> for DocumentError {
+    fn from(error: serde_json::Error) -> Self {
+        DocumentError::Json(error)
+    }
+}
+
+impl From<DecodeError> for DocumentError {
+    fn from(error: DecodeError) -> Self {
+        DocumentError::Binary(error)
+    }
+}
+
+/* Whole board in its json form. Entities are kept in z-order. */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Document {
+    /* Schema version of the board, boards saved before versioning have none. */
+    #[serde(default)]
+    pub version: u32,
+    pub entities: Vec<JsonEntity>,
+    /* Frames in presentation order. */
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub frames: Vec<Id>,
+}
+
+impl Document {
+    pub fn capture(view_port: &ViewPort) -> Result<Self, DocumentError> {
+        let mut entities: Vec<serde_json::Result<JsonEntity>> = vec![];
+        view_port.for_all_entities(|entity| entities.push(JsonEntity::try_from(entity)))?;
+
+        Ok(Self {
+            version: schema::VERSION,
+            entities: entities.into_iter().collect::<serde_json::Result<_>>()?,
+            frames: view_port.frames()?,
+        })
+    }
+
+    /* Replace all entities of the view port. Nothing is changed if an entity can't be built. */
+    pub fn restore(self, view_port: &mut ViewPort) -> Result<(), DocumentError> {
+        let entities: Vec<Entity<Id>> = self.entities
+            .into_iter()
+            .map(JsonEntity::try_into)
+            .collect::<serde_json::Result<_>>()?;
+
+        view_port.clear()?;
+
+        for entity in entities {
+            view_port.add_entity(entity)?;
+        }
+
+        view_port.set_frame_order(&self.frames)?;
+
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /* Boards saved with an earlier schema version are upgraded first. */
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_value(schema::upgrade_document(serde_json::from_str(json)?)?)
+    }
+
+    /* Same tree as the json, in the compact binary form. */
+    pub fn to_bytes(&self) -> serde_json::Result<Vec<u8>> {
+        Ok(binary::encode(&serde_json::to_value(self)?))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DocumentError> {
+        Ok(serde_json::from_value(schema::upgrade_document(binary::decode(bytes)?)?)?)
+    }
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Self {
+            version: schema::VERSION,
+            entities: vec![],
+            frames: vec![],
+        }
+    }
+}
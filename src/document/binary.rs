@@ -0,0 +1,405 @@
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+/*
+ * Compact binary form of the board json. It keeps the json value tree, so it round-trips exactly, but
+ * strings and object keys are written once and referenced afterwards, and numbers are varints.
+ * Every number is delta-encoded against the previous number under the same object key, so coordinates
+ * of consecutive points take a byte or two. Floats that are short decimals are stored as scaled integers,
+ * others as raw bits.
+ */
+
+const MAGIC: &[u8; 4] = b"WBB\x01";
+/* Floats with up to this many decimal places are stored as integers. */
+const MAX_SCALE: u8 = 6;
+
+const NULL: u8 = 0;
+const FALSE: u8 = 1;
+const TRUE: u8 = 2;
+const NEW_STRING: u8 = 3;
+const STRING_REF: u8 = 4;
+const ARRAY: u8 = 5;
+const OBJECT: u8 = 6;
+const INTEGER: u8 = 7;
+const INTEGER_DELTA: u8 = 8;
+/* Unsigned integers above i64::MAX. */
+const LARGE_INTEGER: u8 = 9;
+const FLOAT_BITS: u8 = 10;
+/* Followed by the scale in the low bits. */
+const DECIMAL: u8 = 16;
+const DECIMAL_DELTA: u8 = 32;
+
+/* Arrays and objects nested deeper are rejected, like serde_json does, so input can't overflow the stack. */
+const MAX_DEPTH: usize = 128;
+
+/* Context of numbers outside of any object. */
+const ROOT: usize = usize::MAX;
+
+#[derive(Debug, PartialEq)]
+pub enum DecodeError {
+    /* Not a board in the binary form, or written by another format version. */
+    Format,
+    UnexpectedEnd,
+    Tag(u8),
+    Varint,
+    Utf8,
+    StringIndex(usize),
+    /* Delta without a previous number of the same kind. */
+    Delta,
+    Float,
+    /* Arrays and objects nested deeper than MAX_DEPTH. */
+    Depth,
+}
+
+/* Last number written under an object key. */
+#[derive(Copy, Clone)]
+enum Previous {
+    Integer(i64),
+    Decimal { scale: u8, mantissa: i64 },
+}
+
+pub fn encode(value: &Value) -> Vec<u8> {
+    let mut encoder: Encoder = Encoder {
+        bytes: MAGIC.to_vec(),
+        strings: HashMap::new(),
+        previous: HashMap::new(),
+    };
+
+    encoder.value(value, ROOT);
+
+    encoder.bytes
+}
+
+pub fn decode(bytes: &[u8]) -> Result<Value, DecodeError> {
+    let Some(body) = bytes.strip_prefix(MAGIC.as_slice()) else {
+        return Err(DecodeError::Format);
+    };
+
+    let mut decoder: Decoder = Decoder {
+        bytes: body,
+        position: 0,
+        depth: 0,
+        strings: vec![],
+        previous: HashMap::new(),
+    };
+
+    let value: Value = decoder.value(ROOT)?;
+
+    match decoder.position == body.len() {
+        true => Ok(value),
+        false => Err(DecodeError::Format),
+    }
+}
+
+struct Encoder {
+    bytes: Vec<u8>,
+    /* Index of every string written so far. */
+    strings: HashMap<String, usize>,
+    previous: HashMap<usize, Previous>,
+}
+
+impl Encoder {
+    fn value(&mut self, value: &Value, context: usize) {
+        match value {
+            Value::Null => self.bytes.push(NULL),
+            Value::Bool(false) => self.bytes.push(FALSE),
+            Value::Bool(true) => self.bytes.push(TRUE),
+            Value::Number(number) => self.number(number, context),
+            Value::String(string) => {
+                match self.strings.get(string) {
+                    Some(index) => {
+                        let index: usize = *index;
+                        self.bytes.push(STRING_REF);
+                        self.varint(index as u64);
+                    }
+                    None => {
+                        self.bytes.push(NEW_STRING);
+                        self.new_string(string);
+                    }
+                }
+            }
+            /* Elements share the context of the array, so points of a stroke are deltas of each other. */
+            Value::Array(values) => {
+                self.bytes.push(ARRAY);
+                self.varint(values.len() as u64);
+
+                for value in values {
+                    self.value(value, context);
+                }
+            }
+            Value::Object(fields) => {
+                self.bytes.push(OBJECT);
+                self.varint(fields.len() as u64);
+
+                for (key, value) in fields {
+                    let key_index: usize = self.key(key);
+                    self.value(value, key_index);
+                }
+            }
+        }
+    }
+
+    /* Index of the key, written as the index plus one, or zero followed by the new key. */
+    fn key(&mut self, key: &str) -> usize {
+        if let Some(index) = self.strings.get(key) {
+            let index: usize = *index;
+            self.varint(index as u64 + 1);
+
+            return index;
+        }
+
+        self.varint(0);
+        self.new_string(key)
+    }
+
+    fn new_string(&mut self, string: &str) -> usize {
+        self.varint(string.len() as u64);
+        self.bytes.extend(string.as_bytes());
+
+        let index: usize = self.strings.len();
+        self.strings.insert(string.to_string(), index);
+
+        index
+    }
+
+    fn number(&mut self, number: &Number, context: usize) {
+        if let Some(integer) = number.as_i64() {
+            match self.previous.insert(context, Previous::Integer(integer)) {
+                Some(Previous::Integer(previous)) if integer.checked_sub(previous).is_some() => {
+                    self.bytes.push(INTEGER_DELTA);
+                    self.varint(zigzag(integer - previous));
+                }
+                _ => {
+                    self.bytes.push(INTEGER);
+                    self.varint(zigzag(integer));
+                }
+            }
+        } else if let Some(integer) = number.as_u64() {
+            self.bytes.push(LARGE_INTEGER);
+            self.varint(integer);
+        } else if let Some(float) = number.as_f64() {
+            let Some((scale, mantissa)) = decimal(float) else {
+                self.bytes.push(FLOAT_BITS);
+                self.bytes.extend(float.to_le_bytes());
+                return;
+            };
+
+            match self.previous.insert(context, Previous::Decimal { scale, mantissa }) {
+                Some(Previous::Decimal { scale: previous_scale, mantissa: previous })
+                    if previous_scale == scale && mantissa.checked_sub(previous).is_some() => {
+                    self.bytes.push(DECIMAL_DELTA | scale);
+                    self.varint(zigzag(mantissa - previous));
+                }
+                _ => {
+                    self.bytes.push(DECIMAL | scale);
+                    self.varint(zigzag(mantissa));
+                }
+            }
+        }
+    }
+
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.bytes.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+
+        self.bytes.push(value as u8);
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    /* Arrays and objects the decoder is in. */
+    depth: usize,
+    strings: Vec<String>,
+    previous: HashMap<usize, Previous>,
+}
+
+impl Decoder<'_> {
+    fn value(&mut self, context: usize) -> Result<Value, DecodeError> {
+        let tag: u8 = self.byte()?;
+
+        Ok(match tag {
+            NULL => Value::Null,
+            FALSE => Value::Bool(false),
+            TRUE => Value::Bool(true),
+            NEW_STRING => {
+                let index: usize = self.new_string()?;
+                Value::String(self.strings[index].clone())
+            }
+            STRING_REF => {
+                let index: usize = self.varint()? as usize;
+                Value::String(self.string(index)?.to_string())
+            }
+            ARRAY => {
+                let length: usize = self.length()?;
+                let mut values: Vec<Value> = Vec::with_capacity(length);
+
+                self.enter()?;
+                for _ in 0..length {
+                    values.push(self.value(context)?);
+                }
+                self.depth -= 1;
+
+                Value::Array(values)
+            }
+            OBJECT => {
+                let length: usize = self.length()?;
+                let mut fields: Map<String, Value> = Map::new();
+
+                self.enter()?;
+                for _ in 0..length {
+                    let key_index: usize = match self.varint()? as usize {
+                        0 => self.new_string()?,
+                        index => index - 1,
+                    };
+                    let key: String = self.string(key_index)?.to_string();
+
+                    fields.insert(key, self.value(key_index)?);
+                }
+                self.depth -= 1;
+
+                Value::Object(fields)
+            }
+            INTEGER => {
+                let integer: i64 = unzigzag(self.varint()?);
+                self.integer(context, integer)
+            }
+            INTEGER_DELTA => {
+                let Some(Previous::Integer(previous)) = self.previous.get(&context).copied() else {
+                    return Err(DecodeError::Delta);
+                };
+                let integer: i64 = previous.checked_add(unzigzag(self.varint()?)).ok_or(DecodeError::Delta)?;
+
+                self.integer(context, integer)
+            }
+            LARGE_INTEGER => Value::from(self.varint()?),
+            FLOAT_BITS => {
+                let bits: [u8; 8] = self.take(8)?.try_into().map_err(|_| DecodeError::UnexpectedEnd)?;
+                Value::Number(Number::from_f64(f64::from_le_bytes(bits)).ok_or(DecodeError::Float)?)
+            }
+            tag if tag & !0x0f == DECIMAL && tag & 0x0f <= MAX_SCALE => {
+                let mantissa: i64 = unzigzag(self.varint()?);
+                self.decimal(context, tag & 0x0f, mantissa)?
+            }
+            tag if tag & !0x0f == DECIMAL_DELTA && tag & 0x0f <= MAX_SCALE => {
+                let scale: u8 = tag & 0x0f;
+                let Some(Previous::Decimal { scale: previous_scale, mantissa: previous }) = self.previous.get(&context).copied() else {
+                    return Err(DecodeError::Delta);
+                };
+                if previous_scale != scale {
+                    return Err(DecodeError::Delta);
+                }
+                let mantissa: i64 = previous.checked_add(unzigzag(self.varint()?)).ok_or(DecodeError::Delta)?;
+
+                self.decimal(context, scale, mantissa)?
+            }
+            tag => return Err(DecodeError::Tag(tag)),
+        })
+    }
+
+    fn enter(&mut self) -> Result<(), DecodeError> {
+        self.depth += 1;
+
+        match self.depth <= MAX_DEPTH {
+            true => Ok(()),
+            false => Err(DecodeError::Depth),
+        }
+    }
+
+    fn integer(&mut self, context: usize, integer: i64) -> Value {
+        self.previous.insert(context, Previous::Integer(integer));
+
+        Value::from(integer)
+    }
+
+    fn decimal(&mut self, context: usize, scale: u8, mantissa: i64) -> Result<Value, DecodeError> {
+        self.previous.insert(context, Previous::Decimal { scale, mantissa });
+
+        let float: f64 = mantissa as f64 / 10f64.powi(scale as i32);
+        Number::from_f64(float).map(Value::Number).ok_or(DecodeError::Float)
+    }
+
+    fn new_string(&mut self) -> Result<usize, DecodeError> {
+        let length: usize = self.length()?;
+        let string: &str = std::str::from_utf8(self.take(length)?).map_err(|_| DecodeError::Utf8)?;
+
+        self.strings.push(string.to_string());
+
+        Ok(self.strings.len() - 1)
+    }
+
+    fn string(&self, index: usize) -> Result<&str, DecodeError> {
+        self.strings
+            .get(index)
+            .map(String::as_str)
+            .ok_or(DecodeError::StringIndex(index))
+    }
+
+    /* Count of bytes or values that follow. Can't be more than the bytes left, so corrupted lengths don't allocate. */
+    fn length(&mut self) -> Result<usize, DecodeError> {
+        let length: usize = self.varint()? as usize;
+
+        match length <= self.bytes.len() - self.position {
+            true => Ok(length),
+            false => Err(DecodeError::UnexpectedEnd),
+        }
+    }
+
+    fn byte(&mut self) -> Result<u8, DecodeError> {
+        let byte: u8 = *self.bytes.get(self.position).ok_or(DecodeError::UnexpectedEnd)?;
+        self.position += 1;
+
+        Ok(byte)
+    }
+
+    fn take(&mut self, count: usize) -> Result<&[u8], DecodeError> {
+        let end: usize = self.position.checked_add(count).ok_or(DecodeError::UnexpectedEnd)?;
+        let bytes: &[u8] = self.bytes.get(self.position..end).ok_or(DecodeError::UnexpectedEnd)?;
+        self.position = end;
+
+        Ok(bytes)
+    }
+
+    fn varint(&mut self) -> Result<u64, DecodeError> {
+        let mut value: u64 = 0;
+
+        for shift in (0..64).step_by(7) {
+            let byte: u8 = self.byte()?;
+            value |= ((byte & 0x7f) as u64).checked_shl(shift).ok_or(DecodeError::Varint)?;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(DecodeError::Varint)
+    }
+}
+
+/* Smallest scale at which the float is an integer that converts back to exactly the same float. */
+fn decimal(float: f64) -> Option<(u8, i64)> {
+    /* Mantissas must stay exact in f64. */
+    const MAX_MANTISSA: f64 = (1u64 << 53) as f64;
+
+    (0..=MAX_SCALE).find_map(|scale| {
+        let scaled: f64 = (float * 10f64.powi(scale as i32)).round();
+        if scaled.abs() >= MAX_MANTISSA {
+            return None;
+        }
+
+        /* Compared as bits, so negative zero is kept as raw bits. */
+        let mantissa: i64 = scaled as i64;
+        ((mantissa as f64 / 10f64.powi(scale as i32)).to_bits() == float.to_bits()).then_some((scale, mantissa))
+    })
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
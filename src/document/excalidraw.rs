@@ -0,0 +1,881 @@
+use crate::bounding_box::BoundingBox;
+use crate::document::Document;
+use crate::element::connector_model::{Arrowhead, ConnectorModel, Endpoint, Routing};
+use crate::element::ellipse_model::EllipseModel;
+use crate::element::frame_model::{FrameModel, PageSize};
+use crate::element::free_hand;
+use crate::element::id::Id;
+use crate::element::json_entity::JsonEntity;
+use crate::element::line_model::LineModel;
+use crate::element::regular_polygon_model::RegularPolygonModel;
+use crate::element::text_box_model::TextBoxModel;
+use crate::element::{Build, ElementType, Result};
+use crate::style::{LineCap, Style};
+use crate::text::font::Font;
+use crate::text::measure::{ApproximateTextMeasure, TextMeasure};
+use entity_model_feature::entity::Entity;
+use geometry::figure::path::command::Command;
+use geometry::figure::polygon::Polygon;
+use geometry::figure::rectangle::Rectangle;
+use geometry::point::point_2d::Point2D;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use standard_entity_plugin::model::path_model::PathModel;
+use standard_entity_plugin::model::polygon_model::PolygonModel;
+use standard_entity_plugin::model::rectangle_model::RectangleModel;
+use std::collections::{HashMap, HashSet};
+
+/*
+ * Conversion between boards and Excalidraw scenes, the json of `.excalidraw` files.
+ * Shapes keep their geometry and style, elements without an equivalent are listed in the report instead.
+ * Sketchy rendering (roughness) has no equivalent on either side and is not reported for every element.
+ */
+
+const SOURCE: &str = "whiteboard-rs";
+const TRANSPARENT: &str = "transparent";
+/* Excalidraw font families by number. Unknown families are exported by their generic family. */
+const FONT_FAMILIES: [(u32, &str); 8] = [
+    (1, "Virgil, cursive"),
+    (2, "Helvetica, sans-serif"),
+    (3, "Cascadia, monospace"),
+    (5, "Excalifont, cursive"),
+    (6, "Nunito, sans-serif"),
+    (7, "Lilita One, sans-serif"),
+    (8, "Comic Shanns, cursive"),
+    (9, "Liberation Sans, sans-serif"),
+];
+const DEFAULT_FONT_FAMILY: u32 = 2;
+/* Radius of rounded rectangles in Excalidraw, as a part of the shorter side and at most the adaptive radius. */
+const ROUNDNESS_PART: f64 = 0.25;
+const ADAPTIVE_RADIUS: f64 = 32.0;
+const ADAPTIVE_ROUNDNESS: u8 = 3;
+const PROPORTIONAL_ROUNDNESS: u8 = 2;
+const BOLD_WEIGHT: u16 = 600;
+const EPSILON: f64 = 1e-6;
+
+/* Element that was left out or converted with a visible difference. */
+#[derive(Clone, Serialize, PartialEq)]
+pub struct Note {
+    /* Excalidraw element id on import, entity id on export. */
+    pub id: String,
+    /* Excalidraw element type on import, entity element type on export. */
+    pub kind: String,
+    pub reason: String,
+}
+
+#[derive(Clone, Default, Serialize, PartialEq)]
+pub struct Report {
+    /* Elements converted, including the approximated ones. Groups are not counted. */
+    pub converted: usize,
+    pub skipped: Vec<Note>,
+    pub approximated: Vec<Note>,
+}
+
+impl Report {
+    fn skip(&mut self, id: &str, kind: &str, reason: &str) {
+        self.skipped.push(Note { id: id.to_string(), kind: kind.to_string(), reason: reason.to_string() });
+    }
+
+    fn approximate(&mut self, id: &str, kind: &str, reason: &str) {
+        self.approximated.push(Note { id: id.to_string(), kind: kind.to_string(), reason: reason.to_string() });
+    }
+}
+
+#[derive(Deserialize)]
+struct Scene {
+    elements: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+struct Roundness {
+    #[serde(rename = "type")]
+    kind: u8,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Binding {
+    element_id: String,
+}
+
+/* Fields of Excalidraw elements this conversion reads. Missing fields take Excalidraw defaults. */
+#[derive(Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Element {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    /* Clockwise rotation in radians around the center. */
+    angle: f64,
+    stroke_color: String,
+    background_color: String,
+    fill_style: String,
+    stroke_width: f64,
+    stroke_style: String,
+    /* In range 0..=100. */
+    opacity: f64,
+    /* Innermost group first. */
+    group_ids: Vec<String>,
+    roundness: Option<Roundness>,
+    is_deleted: bool,
+    /* Relative to x and y. */
+    points: Vec<[f64; 2]>,
+    start_arrowhead: Option<String>,
+    end_arrowhead: Option<String>,
+    start_binding: Option<Binding>,
+    end_binding: Option<Binding>,
+    elbowed: bool,
+    text: String,
+    font_size: f64,
+    font_family: u32,
+    text_align: String,
+    container_id: Option<String>,
+    name: Option<String>,
+}
+
+impl Default for Element {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            kind: String::new(),
+            x: 0.0,
+            y: 0.0,
+            width: 0.0,
+            height: 0.0,
+            angle: 0.0,
+            stroke_color: "#1e1e1e".to_string(),
+            background_color: TRANSPARENT.to_string(),
+            fill_style: "solid".to_string(),
+            stroke_width: 2.0,
+            stroke_style: "solid".to_string(),
+            opacity: 100.0,
+            group_ids: vec![],
+            roundness: None,
+            is_deleted: false,
+            points: vec![],
+            start_arrowhead: None,
+            end_arrowhead: None,
+            start_binding: None,
+            end_binding: None,
+            elbowed: false,
+            text: String::new(),
+            font_size: 20.0,
+            font_family: 1,
+            text_align: "left".to_string(),
+            container_id: None,
+            name: None,
+        }
+    }
+}
+
+impl Element {
+    fn center(&self) -> Point2D {
+        Point2D::new(self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    /* Points of lines, arrows and strokes on the board, with the rotation applied. */
+    fn absolute_points(&self) -> Vec<Point2D> {
+        let points: Vec<Point2D> = self.points.iter().map(|[x, y]| Point2D::new(self.x + x, self.y + y)).collect();
+
+        /* Linear elements rotate around the center of their points. */
+        match BoundingBox::from_points(&points) {
+            Some(bounds) if self.angle != 0.0 => {
+                let center: Point2D = Point2D::new(bounds.left + bounds.width() / 2.0, bounds.top + bounds.height() / 2.0);
+                points.iter().map(|point| rotate(point, &center, self.angle)).collect()
+            }
+            _ => points,
+        }
+    }
+
+    fn style(&self) -> Style {
+        let mut style: Style = Style {
+            fill_color: match self.background_color.as_str() {
+                "" | TRANSPARENT => None,
+                color => Some(color.to_string()),
+            },
+            stroke_color: self.stroke_color.clone(),
+            stroke_width: self.stroke_width.max(0.0),
+            opacity: (self.opacity / 100.0).clamp(0.0, 1.0),
+            ..Style::default()
+        };
+
+        /* Same dash patterns as Excalidraw draws. */
+        match self.stroke_style.as_str() {
+            "dashed" => style.dash_array = vec![8.0, 8.0 + self.stroke_width],
+            "dotted" => {
+                style.dash_array = vec![1.5, 6.0 + self.stroke_width];
+                style.line_cap = LineCap::Round;
+            }
+            _ => {}
+        }
+
+        /* Only rectangles have rounded corners on the board. */
+        if let Some(roundness) = self.roundness.as_ref().filter(|_| self.kind == "rectangle") {
+            let radius: f64 = self.width.abs().min(self.height.abs()) * ROUNDNESS_PART;
+
+            style.corner_radius = match roundness.kind {
+                ADAPTIVE_ROUNDNESS => radius.min(ADAPTIVE_RADIUS),
+                _ => radius,
+            };
+        }
+
+        style
+    }
+}
+
+/*
+ * Board entities for the elements of the scene, in z-order, with new ids of the owner.
+ * Groups become containers, arrows keep their bindings to converted elements outside of groups.
+ */
+pub fn import(json: &str, owner_id: &str, measure: &dyn TextMeasure) -> Result<(Vec<JsonEntity>, Report)> {
+    let scene: Scene = serde_json::from_str(json)?;
+    let mut report: Report = Report::default();
+
+    let mut elements: Vec<Element> = vec![];
+    for value in scene.elements {
+        match serde_json::from_value::<Element>(value.clone()) {
+            Ok(element) if element.is_deleted => {}
+            Ok(element) => elements.push(element),
+            Err(_) => report.skip(
+                value["id"].as_str().unwrap_or_default(),
+                value["type"].as_str().unwrap_or_default(),
+                "Malformed element",
+            ),
+        }
+    }
+
+    /* Texts bound to arrows are their labels. */
+    let arrows: HashSet<&str> = elements.iter().filter(|element| element.kind == "arrow").map(|element| element.id.as_str()).collect();
+    let labels: HashMap<&str, &str> = elements
+        .iter()
+        .filter(|element| element.kind == "text")
+        .filter_map(|element| {
+            let container_id: &str = element.container_id.as_deref().filter(|container_id| arrows.contains(container_id))?;
+
+            Some((container_id, element.text.as_str()))
+        })
+        .collect();
+
+    let mut converted: Vec<(&Element, JsonEntity)> = vec![];
+
+    for element in &elements {
+        if element.kind == "text" && element.container_id.as_deref().is_some_and(|container_id| labels.contains_key(container_id)) {
+            continue;
+        }
+
+        if let Some(json_entity) = convert(element, labels.get(element.id.as_str()).copied(), owner_id, measure, &mut report)? {
+            converted.push((element, json_entity));
+        }
+    }
+
+    let converted_labels: usize = converted.iter().filter(|(element, _)| labels.contains_key(element.id.as_str())).count();
+    report.converted = converted.len() + converted_labels;
+
+    /* Connectors bound into containers wouldn't follow them, so those arrows stay free. */
+    let ungrouped: HashMap<&str, Id> = converted
+        .iter()
+        .filter(|(element, _)| element.group_ids.is_empty())
+        .map(|&(element, ref json_entity)| (element.id.as_str(), json_entity.id.clone()))
+        .collect();
+
+    for (element, json_entity) in &mut converted {
+        if *json_entity.id.element_type() != ElementType::Connector {
+            continue;
+        }
+
+        for (binding, field) in [(&element.start_binding, "start"), (&element.end_binding, "end")] {
+            if let Some(id) = binding.as_ref().and_then(|binding| ungrouped.get(binding.element_id.as_str())) {
+                json_entity.model[field] = serde_json::to_value(Endpoint::Bound { id: id.clone() })?;
+            }
+        }
+    }
+
+    let grouped: Vec<(&[String], JsonEntity)> = converted
+        .into_iter()
+        .map(|(element, json_entity)| (element.group_ids.as_slice(), json_entity))
+        .collect();
+
+    Ok((group(grouped, owner_id)?, report))
+}
+
+fn convert(element: &Element, label: Option<&str>, owner_id: &str, measure: &dyn TextMeasure, report: &mut Report) -> Result<Option<JsonEntity>> {
+    let (id, kind) = (element.id.as_str(), element.kind.as_str());
+    let rotated: bool = element.angle != 0.0;
+    let center: Point2D = element.center();
+
+    if element.fill_style != "solid" && element.style().fill_color.is_some() {
+        report.approximate(id, kind, "Hatched fill is drawn solid");
+    }
+
+    let entity: Entity<Id> = match kind {
+        "rectangle" if !rotated => Build::default_with(owner_id, ElementType::Rectangle, None, |model: &mut RectangleModel| {
+            model.rectangle = Rectangle {
+                top_left: Point2D::new(element.x, element.y),
+                width: element.width,
+                height: element.height,
+            };
+        })?,
+        "rectangle" | "diamond" => {
+            if element.roundness.is_some() {
+                report.approximate(id, kind, "Rounded corners are dropped");
+            }
+
+            let (x, y, width, height) = (element.x, element.y, element.width, element.height);
+            let corners: Vec<Point2D> = match kind {
+                "diamond" => vec![
+                    Point2D::new(x + width / 2.0, y),
+                    Point2D::new(x + width, y + height / 2.0),
+                    Point2D::new(x + width / 2.0, y + height),
+                    Point2D::new(x, y + height / 2.0),
+                ],
+                _ => vec![
+                    Point2D::new(x, y),
+                    Point2D::new(x + width, y),
+                    Point2D::new(x + width, y + height),
+                    Point2D::new(x, y + height),
+                ],
+            };
+            let vertices: Vec<Point2D> = corners.iter().map(|corner| rotate(corner, &center, element.angle)).collect();
+
+            Build::default_with(owner_id, ElementType::Polygon, None, |model: &mut PolygonModel| {
+                model.polygon = Polygon::new(vertices);
+            })?
+        }
+        "ellipse" => {
+            if rotated {
+                report.approximate(id, kind, "Rotation is dropped");
+            }
+
+            Build::default_with(owner_id, ElementType::Ellipse, None, |model: &mut EllipseModel| {
+                (model.x, model.y, model.width, model.height) = (element.x, element.y, element.width, element.height);
+            })?
+        }
+        "line" | "arrow" if element.points.len() < 2 => {
+            report.skip(id, kind, "Line has less than two points");
+            return Ok(None);
+        }
+        "line" => {
+            let points: Vec<Point2D> = element.absolute_points();
+            let closed: bool = points.len() > 3 && same_point(&points[0], &points[points.len() - 1]);
+
+            if element.roundness.is_some() && points.len() > 2 {
+                report.approximate(id, kind, "Curved line is drawn straight");
+            }
+
+            match (points.as_slice(), closed) {
+                ([start, end], _) => Build::default_with(owner_id, ElementType::Line, None, |model: &mut LineModel| {
+                    (model.x1, model.y1) = (start.x, start.y);
+                    (model.x2, model.y2) = (end.x, end.y);
+                })?,
+                (points, true) => Build::default_with(owner_id, ElementType::Polygon, None, |model: &mut PolygonModel| {
+                    model.polygon = Polygon::new(points[..points.len() - 1].to_vec());
+                })?,
+                (points, false) => Build::default_with(owner_id, ElementType::FreeHand, None, |model: &mut PathModel| {
+                    model.path = free_hand::polyline(points);
+                })?,
+            }
+        }
+        "arrow" => {
+            let points: Vec<Point2D> = element.absolute_points();
+            let orthogonal: bool = points.windows(2).all(|pair| same(pair[0].x, pair[1].x) || same(pair[0].y, pair[1].y));
+
+            let routing: Routing = match points.len() {
+                2 => Routing::Straight,
+                _ if element.elbowed || orthogonal => Routing::Elbow,
+                _ => {
+                    report.approximate(id, kind, "Bends of the arrow are dropped");
+                    Routing::Straight
+                }
+            };
+            let start_arrowhead: Arrowhead = arrowhead(element.start_arrowhead.as_deref(), element, report);
+            let end_arrowhead: Arrowhead = arrowhead(element.end_arrowhead.as_deref(), element, report);
+
+            Build::default_with(owner_id, ElementType::Connector, None, |model: &mut ConnectorModel| {
+                let (start, end) = (points[0], points[points.len() - 1]);
+
+                model.start = Endpoint::Free { x: start.x, y: start.y };
+                model.end = Endpoint::Free { x: end.x, y: end.y };
+                model.routing = routing;
+                model.start_arrowhead = start_arrowhead;
+                model.end_arrowhead = end_arrowhead;
+                model.label = label.map(str::to_string);
+                model.route = match routing {
+                    Routing::Elbow => points.iter().map(|point| [point.x, point.y]).collect(),
+                    _ => vec![[start.x, start.y], [end.x, end.y]],
+                };
+            })?
+        }
+        "freedraw" => {
+            let points: Vec<Point2D> = element.absolute_points();
+
+            Build::default_with(owner_id, ElementType::FreeHand, None, |model: &mut PathModel| {
+                model.path = free_hand::polyline(&points);
+            })?
+        }
+        "text" => {
+            if rotated {
+                report.approximate(id, kind, "Rotation is dropped");
+            }
+            if element.text_align != "left" {
+                report.approximate(id, kind, "Text is aligned left");
+            }
+
+            let family: &str = match font_family(element.font_family) {
+                Some(family) => family,
+                None => {
+                    report.approximate(id, kind, "Unknown font is replaced");
+                    font_family(DEFAULT_FONT_FAMILY).unwrap_or_default()
+                }
+            };
+            let font: Font = Font::new(family, element.font_size, Font::default().weight);
+
+            /* Excalidraw breaks lines only at new lines, the box is widened so it doesn't wrap them again. */
+            let width: f64 = element.text
+                .split('\n')
+                .map(|line| measure.width(line, &font))
+                .fold(element.width, f64::max);
+
+            Build::default_with(owner_id, ElementType::Text, None, |model: &mut TextBoxModel| {
+                (model.x, model.y, model.width) = (element.x, element.y, width);
+                model.text = element.text.clone();
+                model.font = font;
+                model.color = element.stroke_color.clone();
+                model.relayout(measure);
+            })?
+        }
+        "frame" | "magicframe" => {
+            if rotated {
+                report.approximate(id, kind, "Rotation is dropped");
+            }
+
+            let entity: Entity<Id> = Build::default_with(owner_id, ElementType::Frame, None, |model: &mut FrameModel| {
+                (model.x, model.y, model.width, model.height) = (element.x, element.y, element.width, element.height);
+                model.name = element.name.clone().unwrap_or_default();
+                model.page_size = PageSize::Free;
+            })?;
+
+            /* Frames are drawn in the board style. */
+            let mut json_entity: JsonEntity = JsonEntity::try_from(&entity)?;
+            json_entity.style = None;
+
+            return Ok(Some(json_entity));
+        }
+        "image" => {
+            report.skip(id, kind, "Images are not imported");
+            return Ok(None);
+        }
+        _ => {
+            report.skip(id, kind, "No equivalent element");
+            return Ok(None);
+        }
+    };
+
+    let mut json_entity: JsonEntity = JsonEntity::try_from(&entity)?;
+    json_entity.style = Some(element.style());
+
+    Ok(Some(json_entity))
+}
+
+fn font_family(number: u32) -> Option<&'static str> {
+    FONT_FAMILIES.iter().find(|(family_number, _)| *family_number == number).map(|(_, family)| *family)
+}
+
+fn arrowhead(name: Option<&str>, element: &Element, report: &mut Report) -> Arrowhead {
+    match name {
+        None => Arrowhead::None,
+        Some("arrow") => Arrowhead::Arrow,
+        Some("triangle" | "triangle_outline") => Arrowhead::Triangle,
+        Some("diamond" | "diamond_outline") => Arrowhead::Diamond,
+        Some(name) => {
+            report.approximate(&element.id, &element.kind, &format!("Arrowhead {name} is drawn as an arrow"));
+            Arrowhead::Arrow
+        }
+    }
+}
+
+/* Entities with their group ids wrapped into containers, outermost groups first. Containers take the place of their first entity. */
+fn group(entities: Vec<(&[String], JsonEntity)>, owner_id: &str) -> Result<Vec<JsonEntity>> {
+    enum Node<'a> {
+        Entity(JsonEntity),
+        Group(&'a str, Vec<(&'a [String], JsonEntity)>),
+    }
+
+    let mut nodes: Vec<Node> = vec![];
+
+    for (group_ids, json_entity) in entities {
+        let Some((outermost, inner)) = group_ids.split_last() else {
+            nodes.push(Node::Entity(json_entity));
+            continue;
+        };
+
+        match nodes.iter_mut().find_map(|node| match node {
+            Node::Group(group_id, members) if *group_id == outermost.as_str() => Some(members),
+            _ => None,
+        }) {
+            Some(members) => members.push((inner, json_entity)),
+            None => nodes.push(Node::Group(outermost, vec![(inner, json_entity)])),
+        }
+    }
+
+    nodes
+        .into_iter()
+        .map(|node| match node {
+            Node::Entity(json_entity) => Ok(json_entity),
+            Node::Group(_, members) => {
+                let container: Entity<Id> = Build::default(owner_id, ElementType::Container, None);
+                let json_entity: JsonEntity = JsonEntity::try_from(&container)?
+                    .with_model(json!({ "children": group(members, owner_id)? }));
+
+                /* Built once to check the children. */
+                Build::from_json(json_entity.clone())?;
+
+                Ok(json_entity)
+            }
+        })
+        .collect()
+}
+
+/* Scene json of the document with the report of entities left out or drawn differently. */
+pub fn export(document: &Document) -> (String, Report) {
+    let mut exporter: Exporter = Exporter {
+        elements: vec![],
+        bindings: vec![],
+        report: Report::default(),
+    };
+
+    for json_entity in &document.entities {
+        exporter.entity(json_entity, &[]);
+    }
+
+    let Exporter { mut elements, bindings, report } = exporter;
+
+    /* Bound elements list the arrows bound to them, arrows bound to something not exported are left free. */
+    for (arrow_id, field, target_id) in bindings {
+        match elements.iter_mut().find(|element| element["id"] == target_id) {
+            Some(target) => bound_element(target, &arrow_id, "arrow"),
+            None => {
+                if let Some(arrow) = elements.iter_mut().find(|element| element["id"] == arrow_id) {
+                    arrow[field] = Value::Null;
+                }
+            }
+        }
+    }
+
+    let scene: Value = json!({
+        "type": "excalidraw",
+        "version": 2,
+        "source": SOURCE,
+        "elements": elements,
+        "appState": { "viewBackgroundColor": "#ffffff" },
+        "files": {},
+    });
+
+    (scene.to_string(), report)
+}
+
+struct Exporter {
+    elements: Vec<Value>,
+    /* Arrow id, binding field and bound element id. */
+    bindings: Vec<(String, &'static str, String)>,
+    report: Report,
+}
+
+impl Exporter {
+    /* Groups are the ids of the containers holding the entity, innermost first. */
+    fn entity(&mut self, json_entity: &JsonEntity, groups: &[String]) {
+        let id: String = json_entity.id.as_html_id();
+        let kind: String = serde_json::to_value(json_entity.id.element_type())
+            .ok()
+            .and_then(|kind| kind.as_str().map(str::to_string))
+            .unwrap_or_default();
+        let style: Style = json_entity.style.clone().unwrap_or_default();
+
+        let exported: Option<Value> = match json_entity.id.element_type() {
+            ElementType::Rectangle => model::<RectangleModel>(json_entity).map(|model| {
+                let rectangle: &Rectangle = &model.rectangle;
+                let bounds: [f64; 4] = normalized(rectangle.top_left.x, rectangle.top_left.y, rectangle.width, rectangle.height);
+
+                element("rectangle", &id, bounds, &style, groups)
+            }),
+            ElementType::Ellipse => model::<EllipseModel>(json_entity)
+                .map(|model| element("ellipse", &id, normalized(model.x, model.y, model.width, model.height), &style, groups)),
+            ElementType::Polygon => model::<PolygonModel>(json_entity).map(|model| {
+                let vertices: Vec<Point2D> = model.polygon.vertices().to_vec();
+
+                match diamond_bounds(&vertices) {
+                    Some(bounds) => element("diamond", &id, bounds, &style, groups),
+                    None => closed_line(&id, &vertices, &style, groups),
+                }
+            }),
+            ElementType::RegularPolygon => model::<RegularPolygonModel>(json_entity).map(|model| {
+                self.report.approximate(&id, &kind, "Exported as a closed line");
+                closed_line(&id, &model.vertices(), &style, groups)
+            }),
+            ElementType::FreeHand => model::<PathModel>(json_entity).map(|model| {
+                if model.path.commands().iter().any(|command| matches!(command, Command::BezierTo(_) | Command::ArcTo(_))) {
+                    self.report.approximate(&id, &kind, "Curves are exported through their end points");
+                }
+
+                let mut stroke: Value = linear("freedraw", &id, &free_hand::points(&model), &style, groups);
+                stroke["pressures"] = json!([]);
+                stroke["simulatePressure"] = json!(true);
+
+                stroke
+            }),
+            ElementType::Line => model::<LineModel>(json_entity)
+                .map(|model| linear("line", &id, &[model.start(), model.end()], &style, groups)),
+            ElementType::Connector => match model::<ConnectorModel>(json_entity) {
+                Some(model) => self.connector(&id, &kind, &model, &style, groups),
+                None => None,
+            },
+            ElementType::Text => model::<TextBoxModel>(json_entity).map(|model| {
+                if model.font.weight >= BOLD_WEIGHT {
+                    self.report.approximate(&id, &kind, "Bold is dropped");
+                }
+
+                let text_style: Style = Style { stroke_color: model.color.clone(), ..style.clone() };
+                let bounds: [f64; 4] = [model.x, model.y, model.width, model.height];
+
+                text(element("text", &id, bounds, &text_style, groups), &model.text, &model.font, "left", None)
+            }),
+            ElementType::Frame => model::<FrameModel>(json_entity).map(|model| {
+                let mut frame: Value = element("frame", &id, normalized(model.x, model.y, model.width, model.height), &Style::default(), groups);
+                frame["name"] = json!(model.name);
+
+                frame
+            }),
+            ElementType::Container => {
+                let inner: Vec<String> = [id.clone()].into_iter().chain(groups.iter().cloned()).collect();
+
+                for child in json_entity.model["children"].as_array().into_iter().flatten() {
+                    match serde_json::from_value::<JsonEntity>(child.clone()) {
+                        Ok(child) => self.entity(&child, &inner),
+                        Err(_) => self.report.skip(&id, &kind, "Malformed child"),
+                    }
+                }
+
+                return;
+            }
+            ElementType::Image => {
+                self.report.skip(&id, &kind, "Images are not exported");
+                return;
+            }
+            ElementType::StickyNote => {
+                self.report.skip(&id, &kind, "No equivalent element");
+                return;
+            }
+        };
+
+        match exported {
+            Some(exported) => {
+                self.elements.push(exported);
+                self.report.converted += 1;
+            }
+            None => self.report.skip(&id, &kind, "Malformed model"),
+        }
+    }
+
+    fn connector(&mut self, id: &str, kind: &str, model: &ConnectorModel, style: &Style, groups: &[String]) -> Option<Value> {
+        let points: Vec<Point2D> = match (&model.start, &model.end) {
+            _ if model.route.len() >= 2 => model.route.iter().map(|[x, y]| Point2D::new(*x, *y)).collect(),
+            (Endpoint::Free { x: x1, y: y1 }, Endpoint::Free { x: x2, y: y2 }) => vec![Point2D::new(*x1, *y1), Point2D::new(*x2, *y2)],
+            _ => {
+                self.report.skip(id, kind, "Route isn't resolved");
+                return None;
+            }
+        };
+
+        let mut arrow: Value = linear("arrow", id, &points, style, groups);
+        arrow["startArrowhead"] = arrowhead_name(model.start_arrowhead);
+        arrow["endArrowhead"] = arrowhead_name(model.end_arrowhead);
+
+        if model.routing == Routing::Curved {
+            self.report.approximate(id, kind, "Curve passes through its control points");
+            arrow["roundness"] = json!({ "type": PROPORTIONAL_ROUNDNESS });
+        }
+
+        for (endpoint, field) in [(&model.start, "startBinding"), (&model.end, "endBinding")] {
+            if let Some(bound_id) = endpoint.bound_id() {
+                let target_id: String = bound_id.as_html_id();
+
+                arrow[field] = json!({ "elementId": target_id, "focus": 0.0, "gap": 0.0 });
+                self.bindings.push((id.to_string(), field, target_id));
+            }
+        }
+
+        /* Labels are texts bound to the arrow, centered on the middle of the route. */
+        if let Some(label) = model.label.as_deref().filter(|label| !label.is_empty()) {
+            let label_id: String = format!("{id}-label");
+            let font: Font = Font::new(font_family(DEFAULT_FONT_FAMILY).unwrap_or_default(), Font::default().size, Font::default().weight);
+            let middle: Point2D = Point2D::new(
+                (points[(points.len() - 1) / 2].x + points[points.len() / 2].x) / 2.0,
+                (points[(points.len() - 1) / 2].y + points[points.len() / 2].y) / 2.0,
+            );
+            let width: f64 = label.split('\n').map(|line| ApproximateTextMeasure.width(line, &font)).fold(0.0, f64::max);
+            let height: f64 = label.split('\n').count() as f64 * font.line_height();
+            let bounds: [f64; 4] = [middle.x - width / 2.0, middle.y - height / 2.0, width, height];
+
+            let label_style: Style = Style { fill_color: None, dash_array: vec![], ..style.clone() };
+            self.elements.push(text(element("text", &label_id, bounds, &label_style, groups), label, &font, "center", Some(id)));
+            bound_element(&mut arrow, &label_id, "text");
+        }
+
+        Some(arrow)
+    }
+}
+
+fn model<M: DeserializeOwned>(json_entity: &JsonEntity) -> Option<M> {
+    serde_json::from_value(json_entity.model.clone()).ok()
+}
+
+/* Fields every Excalidraw element has. Bounds are x, y, width and height. */
+fn element(kind: &str, id: &str, [x, y, width, height]: [f64; 4], style: &Style, groups: &[String]) -> Value {
+    let stroke_style: &str = match style.dash_array.first() {
+        None => "solid",
+        Some(dash) if *dash <= 2.0 => "dotted",
+        Some(_) => "dashed",
+    };
+    let roundness: Value = match style.corner_radius > 0.0 {
+        true => json!({ "type": ADAPTIVE_ROUNDNESS }),
+        false => Value::Null,
+    };
+
+    json!({
+        "id": id,
+        "type": kind,
+        "x": x,
+        "y": y,
+        "width": width,
+        "height": height,
+        "angle": 0,
+        "strokeColor": style.stroke_color,
+        "backgroundColor": style.fill_color.as_deref().unwrap_or(TRANSPARENT),
+        "fillStyle": "solid",
+        "strokeWidth": style.stroke_width,
+        "strokeStyle": stroke_style,
+        "roughness": 0,
+        "opacity": (style.opacity * 100.0).round(),
+        "groupIds": groups,
+        "frameId": null,
+        "roundness": roundness,
+        "seed": seed(id),
+        "version": 1,
+        "versionNonce": seed(id),
+        "isDeleted": false,
+        "boundElements": null,
+        "updated": 1,
+        "link": null,
+        "locked": false,
+    })
+}
+
+/* Line, arrow or stroke through the points, positioned at the first one. */
+fn linear(kind: &str, id: &str, points: &[Point2D], style: &Style, groups: &[String]) -> Value {
+    let origin: Point2D = points.first().copied().unwrap_or(Point2D::new(0.0, 0.0));
+    let bounds: Option<BoundingBox> = BoundingBox::from_points(points);
+    let (width, height) = bounds.map_or((0.0, 0.0), |bounds| (bounds.width(), bounds.height()));
+
+    let mut line: Value = element(kind, id, [origin.x, origin.y, width, height], style, groups);
+    line["roundness"] = Value::Null;
+    line["points"] = json!(points.iter().map(|point| [point.x - origin.x, point.y - origin.y]).collect::<Vec<[f64; 2]>>());
+    line["lastCommittedPoint"] = Value::Null;
+    line["startBinding"] = Value::Null;
+    line["endBinding"] = Value::Null;
+    line["startArrowhead"] = Value::Null;
+    line["endArrowhead"] = Value::Null;
+
+    line
+}
+
+fn closed_line(id: &str, vertices: &[Point2D], style: &Style, groups: &[String]) -> Value {
+    let points: Vec<Point2D> = vertices.iter().chain(vertices.first()).copied().collect();
+
+    linear("line", id, &points, style, groups)
+}
+
+fn text(mut element: Value, text: &str, font: &Font, align: &str, container_id: Option<&str>) -> Value {
+    let generic: &str = font.family.rsplit(',').next().unwrap_or_default().trim();
+    let font_family: u32 = FONT_FAMILIES
+        .iter()
+        .find(|(_, family)| *family == font.family)
+        .or_else(|| FONT_FAMILIES.iter().find(|(_, family)| family.ends_with(generic)))
+        .map_or(DEFAULT_FONT_FAMILY, |(number, _)| *number);
+
+    element["text"] = json!(text);
+    element["originalText"] = json!(text);
+    element["fontSize"] = json!(font.size);
+    element["fontFamily"] = json!(font_family);
+    element["textAlign"] = json!(align);
+    element["verticalAlign"] = json!(if container_id.is_some() { "middle" } else { "top" });
+    element["containerId"] = json!(container_id);
+    element["lineHeight"] = json!(font.line_height() / font.size);
+    element["roundness"] = Value::Null;
+
+    element
+}
+
+fn bound_element(element: &mut Value, id: &str, kind: &str) {
+    let bound: Value = json!({ "id": id, "type": kind });
+
+    match element["boundElements"].as_array_mut() {
+        Some(bound_elements) => bound_elements.push(bound),
+        None => element["boundElements"] = json!([bound]),
+    }
+}
+
+fn arrowhead_name(arrowhead: Arrowhead) -> Value {
+    match arrowhead {
+        Arrowhead::None => Value::Null,
+        Arrowhead::Arrow => json!("arrow"),
+        Arrowhead::Triangle => json!("triangle"),
+        Arrowhead::Diamond => json!("diamond"),
+    }
+}
+
+/* Bounds of the polygon if its vertices are the middles of the sides of their bounding box. */
+fn diamond_bounds(vertices: &[Point2D]) -> Option<[f64; 4]> {
+    let bounds: BoundingBox = BoundingBox::from_points(vertices)?;
+    let (center_x, center_y) = (bounds.left + bounds.width() / 2.0, bounds.top + bounds.height() / 2.0);
+    let middles: [Point2D; 4] = [
+        Point2D::new(center_x, bounds.top),
+        Point2D::new(bounds.left + bounds.width(), center_y),
+        Point2D::new(center_x, bounds.top + bounds.height()),
+        Point2D::new(bounds.left, center_y),
+    ];
+
+    let diamond: bool = vertices.len() == 4
+        && middles.iter().all(|middle| vertices.iter().any(|vertex| same_point(vertex, middle)));
+
+    diamond.then_some([bounds.left, bounds.top, bounds.width(), bounds.height()])
+}
+
+/* Box with negative sizes flipped, Excalidraw expects positive ones. */
+fn normalized(x: f64, y: f64, width: f64, height: f64) -> [f64; 4] {
+    [x.min(x + width), y.min(y + height), width.abs(), height.abs()]
+}
+
+/* Clockwise in screen coordinates, like Excalidraw angles. */
+fn rotate(point: &Point2D, center: &Point2D, angle: f64) -> Point2D {
+    let (sin, cos) = angle.sin_cos();
+    let (dx, dy) = (point.x - center.x, point.y - center.y);
+
+    Point2D::new(center.x + dx * cos - dy * sin, center.y + dx * sin + dy * cos)
+}
+
+fn same(a: f64, b: f64) -> bool {
+    (a - b).abs() < EPSILON
+}
+
+fn same_point(a: &Point2D, b: &Point2D) -> bool {
+    same(a.x, b.x) && same(a.y, b.y)
+}
+
+/* Excalidraw draws its sketchy strokes from the seed, it is derived from the id so exports are stable. */
+fn seed(id: &str) -> u32 {
+    id.bytes().fold(2166136261u32, |hash, byte| (hash ^ byte as u32).wrapping_mul(16777619)) & 0x7fff_ffff
+}
@@ -1,5 +1,15 @@
+use crate::element::connector_model::ConnectorModel;
+use crate::element::ellipse_model::EllipseModel;
+use crate::element::frame_model::FrameModel;
 use crate::element::id::Id;
+use crate::element::image_model::ImageModel;
 use crate::element::json_entity::JsonEntity;
+use crate::element::line_model::LineModel;
+use crate::element::regular_polygon_model::RegularPolygonModel;
+use crate::element::sticky_note_model::StickyNoteModel;
+use crate::element::text_box_model::TextBoxModel;
+use crate::renderer::board_renderer::{BoardRender, BoardRenderer};
+use crate::style::{self, Style};
 use entity_model_feature::entity::Entity;
 use entity_model_feature::feature_set::FeatureSet;
 use entity_model_feature::Model;
@@ -10,23 +20,54 @@ use standard_entity_plugin::model::container_model::ContainerModel;
 use standard_entity_plugin::model::polygon_model::PolygonModel;
 use standard_entity_plugin::model::rectangle_model::RectangleModel;
 use standard_entity_plugin::model::path_model::PathModel;
-use standard_entity_plugin::model::text_model::TextModel;
 use std::result;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::wasm_bindgen;
 
 pub mod id;
 pub mod json_entity;
+pub mod free_hand;
+pub mod text_box_model;
+pub mod connector_model;
+pub mod ellipse_model;
+pub mod line_model;
+pub mod regular_polygon_model;
+pub mod image_model;
+pub mod sticky_note_model;
+pub mod frame_model;
+pub mod transform;
+pub mod patch;
 
 pub type Result<Ok> = result::Result<Ok, serde_json::Error>;
 
-#[derive(Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
-#[wasm_bindgen]
+/* Concrete model of the entity. None if the entity holds a model of another type. */
+pub fn model_of<M: Model + 'static>(entity: &Entity<Id>) -> Option<&M> {
+    entity.model().downcast_ref::<M>()
+}
+
+/* Feature set of the elements implemented by the whiteboard itself. */
+pub fn board_feature_set(render: fn(&Entity<Id>, &mut dyn BoardRenderer)) -> FeatureSet<Id> {
+    let mut feature_set: FeatureSet<Id> = FeatureSet::empty();
+    feature_set.add_feature(BoardRender { render });
+
+    feature_set
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub enum ElementType {
     Rectangle,
     Polygon,
     FreeHand,
     Text,
     Container,
+    Connector,
+    Ellipse,
+    Line,
+    RegularPolygon,
+    Image,
+    StickyNote,
+    Frame,
 }
 
 
@@ -34,40 +75,75 @@ pub enum Build {
     FromJson(JsonEntity),
     Default {
         owner_id: String,
-        element_type: ElementType
+        element_type: ElementType,
+        style: Option<Style>,
     }
 }
 
 impl Build {
-    pub fn default(owner_id: &str, element_type: ElementType) -> Entity<Id> {
+    pub fn default(owner_id: &str, element_type: ElementType, style: Option<Style>) -> Entity<Id> {
         /* Building default entity should not cause an error, so we unwrap the result safely */
-        Build::Default {owner_id: owner_id.to_string(), element_type}.build().unwrap()
+        Build::Default {owner_id: owner_id.to_string(), element_type, style}.build().unwrap()
     }
 
     pub fn from_json(json_entity: JsonEntity) -> Result<Entity<Id>> {
         Build::FromJson(json_entity).build()
     }
 
+    /* Default entity with its model adjusted by the callback. */
+    pub fn default_with<M: Serialize + DeserializeOwned>(owner_id: &str, element_type: ElementType, style: Option<Style>, modify: impl FnOnce(&mut M)) -> Result<Entity<Id>> {
+        Self::modified(&Self::default(owner_id, element_type, style), modify)
+    }
+
+    /* Copy of the entity with the same id and its model adjusted by the callback. */
+    pub fn modified<M: Serialize + DeserializeOwned>(entity: &Entity<Id>, modify: impl FnOnce(&mut M)) -> Result<Entity<Id>> {
+        let mut json_entity: JsonEntity = JsonEntity::try_from(entity)?;
+
+        let mut model: M = serde_json::from_value(json_entity.model)?;
+        modify(&mut model);
+        json_entity.model = serde_json::to_value(&model)?;
+
+        Self::from_json(json_entity)
+    }
+
     pub fn build(self) -> Result<Entity<Id>> {
         let element_type = match &self {
             Build::FromJson(JsonEntity { id, .. }) => id.element_type(),
             Build::Default { element_type, .. } => element_type,
         };
 
-        match element_type {
+        let style: Option<Style> = match &self {
+            Build::FromJson(json_entity) => json_entity.style.clone(),
+            Build::Default { style, .. } => style.clone(),
+        };
+
+        let mut entity: Entity<Id> = match element_type {
             ElementType::Rectangle => self.build_entity::<RectangleModel>(),
             ElementType::Polygon => self.build_entity::<PolygonModel>(),
             ElementType::FreeHand => self.build_entity::<PathModel>(),
-            ElementType::Text => self.build_entity::<TextModel>(),
+            ElementType::Text => self.build_entity::<TextBoxModel>(),
             ElementType::Container => self.build_container::<ContainerModel<Id>>(),
+            ElementType::Connector => self.build_entity::<ConnectorModel>(),
+            ElementType::Ellipse => self.build_entity::<EllipseModel>(),
+            ElementType::Line => self.build_entity::<LineModel>(),
+            ElementType::RegularPolygon => self.build_entity::<RegularPolygonModel>(),
+            ElementType::Image => self.build_entity::<ImageModel>(),
+            ElementType::StickyNote => self.build_entity::<StickyNoteModel>(),
+            ElementType::Frame => self.build_entity::<FrameModel>(),
+        }?;
+
+        if let Some(style) = style {
+            entity.add_feature_set(style::style_feature_set(style));
         }
+
+        Ok(entity)
     }
 
     fn build_entity<M: Model + DeserializeOwned + DefaultEntity<Id> + StandardFeatureSet<Id>>(self) -> Result<Entity<Id>> {
         let mut entity: Entity<Id> = match self {
             Build::FromJson(entity_json) =>
                 Self::entity_model_from_json::<M>(entity_json)?,
-            Build::Default { owner_id, element_type } =>
+            Build::Default { owner_id, element_type, .. } =>
                 M::default_entity(Id::generate(&owner_id, element_type)),
         };
 
@@ -79,7 +155,7 @@ impl Build {
         let mut container_entity: Entity<Id> = match self {
             Build::FromJson(entity_json) =>
                 Self::container_model_from_json(entity_json)?,
-            Build::Default { owner_id, element_type } =>
+            Build::Default { owner_id, element_type, .. } =>
                 M::default_entity(Id::generate(&owner_id, element_type)),
         };
 
@@ -0,0 +1,325 @@
+use crate::bounding_box::BoundingBox;
+use crate::element::id::Id;
+use crate::element::{board_feature_set, model_of};
+use crate::renderer::board_renderer::BoardRenderer;
+use crate::style::{EntityStyle, Style};
+use crate::text::font::Font;
+use crate::text::layout::TextLine;
+use crate::text::measure::{ApproximateTextMeasure, TextMeasure};
+use entity_model_feature::entity::Entity;
+use entity_model_feature::feature_set::FeatureSet;
+use entity_model_feature::{AsSerialize, Model};
+use geometry::figure::path::command::bezier_to::BezierTo;
+use geometry::figure::path::command::line_to::LineTo;
+use geometry::figure::path::command::move_to::MoveTo;
+use geometry::figure::path::command::Command;
+use geometry::figure::path::Path;
+use geometry::figure::polygon::Polygon;
+use geometry::point::point_2d::Point2D;
+use serde::{Deserialize, Serialize};
+use standard_entity_plugin::entity_model::{DefaultEntity, StandardFeatureSet};
+use standard_rendering_plugin::style::shape_style::ShapeStyle;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+const ARROWHEAD_LENGTH: f64 = 12.0;
+const ARROWHEAD_ANGLE: f64 = std::f64::consts::FRAC_PI_6;
+
+#[derive(Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub enum Routing {
+    Straight,
+    /* Orthogonal segments only. */
+    Elbow,
+    Curved,
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub enum Arrowhead {
+    None,
+    /* Two open strokes. */
+    Arrow,
+    Triangle,
+    Diamond,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub enum Endpoint {
+    Free {
+        x: f64,
+        y: f64,
+    },
+    /* Attached to the border of another entity, follows it when it moves. */
+    Bound {
+        id: Id,
+    },
+}
+
+impl Endpoint {
+    pub fn bound_id(&self) -> Option<&Id> {
+        match self {
+            Endpoint::Free { .. } => None,
+            Endpoint::Bound { id } => Some(id),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ConnectorModel {
+    pub start: Endpoint,
+    pub end: Endpoint,
+    pub routing: Routing,
+    pub start_arrowhead: Arrowhead,
+    pub end_arrowhead: Arrowhead,
+    #[serde(default)]
+    pub label: Option<String>,
+    /*
+     * Route resolved from the endpoints, updated by the view port whenever bound entities change.
+     * Curved route holds bezier control points between its ends.
+     */
+    #[serde(default)]
+    pub route: Vec<[f64; 2]>,
+}
+
+impl ConnectorModel {
+    pub fn bound_ids(&self) -> impl Iterator<Item = &Id> {
+        self.start.bound_id().into_iter().chain(self.end.bound_id())
+    }
+
+    /*
+     * Resolve route between the endpoints.
+     * Bounds of the bound entities are passed in, endpoints bound to missing entities keep their last position.
+     */
+    pub fn reroute(&mut self, start_bounds: Option<BoundingBox>, end_bounds: Option<BoundingBox>) {
+        let last_start: Option<[f64; 2]> = self.route.first().copied();
+        let last_end: Option<[f64; 2]> = self.route.last().copied();
+
+        let Some(start_reference) = Self::reference(&self.start, start_bounds, last_start) else {
+            return;
+        };
+        let Some(end_reference) = Self::reference(&self.end, end_bounds, last_end) else {
+            return;
+        };
+
+        let (dx, dy) = (end_reference[0] - start_reference[0], end_reference[1] - start_reference[1]);
+        let horizontal: bool = dx.abs() >= dy.abs();
+
+        let (start, end) = match self.routing {
+            Routing::Straight => (
+                Self::anchor(start_reference, start_bounds, end_reference),
+                Self::anchor(end_reference, end_bounds, start_reference),
+            ),
+            Routing::Elbow | Routing::Curved => (
+                Self::side_anchor(start_reference, start_bounds, horizontal, dx.signum(), dy.signum()),
+                Self::side_anchor(end_reference, end_bounds, horizontal, -dx.signum(), -dy.signum()),
+            ),
+        };
+
+        self.route = match self.routing {
+            Routing::Straight => vec![start, end],
+            Routing::Elbow if horizontal => {
+                let middle: f64 = (start[0] + end[0]) / 2.0;
+                vec![start, [middle, start[1]], [middle, end[1]], end]
+            }
+            Routing::Elbow => {
+                let middle: f64 = (start[1] + end[1]) / 2.0;
+                vec![start, [start[0], middle], [end[0], middle], end]
+            }
+            Routing::Curved if horizontal => {
+                let offset: f64 = (end[0] - start[0]) / 2.0;
+                vec![start, [start[0] + offset, start[1]], [end[0] - offset, end[1]], end]
+            }
+            Routing::Curved => {
+                let offset: f64 = (end[1] - start[1]) / 2.0;
+                vec![start, [start[0], start[1] + offset], [end[0], end[1] - offset], end]
+            }
+        };
+    }
+
+    /* Point the endpoint aims at: free position or center of the bound entity. */
+    fn reference(endpoint: &Endpoint, bounds: Option<BoundingBox>, last: Option<[f64; 2]>) -> Option<[f64; 2]> {
+        match (endpoint, bounds) {
+            (Endpoint::Free { x, y }, _) => Some([*x, *y]),
+            (Endpoint::Bound { .. }, Some(bounds)) => {
+                let (x, y) = bounds.center();
+                Some([x, y])
+            }
+            (Endpoint::Bound { .. }, None) => last,
+        }
+    }
+
+    /* Intersection of the bounds border with the line from its center towards the target. */
+    fn anchor(reference: [f64; 2], bounds: Option<BoundingBox>, target: [f64; 2]) -> [f64; 2] {
+        let Some(bounds) = bounds else {
+            return reference;
+        };
+
+        let (dx, dy) = (target[0] - reference[0], target[1] - reference[1]);
+        if dx == 0.0 && dy == 0.0 {
+            return reference;
+        }
+
+        let scale_x: f64 = if dx == 0.0 { f64::INFINITY } else { bounds.width() / 2.0 / dx.abs() };
+        let scale_y: f64 = if dy == 0.0 { f64::INFINITY } else { bounds.height() / 2.0 / dy.abs() };
+        let scale: f64 = scale_x.min(scale_y).min(1.0);
+
+        [reference[0] + dx * scale, reference[1] + dy * scale]
+    }
+
+    /* Middle of the bounds side facing the direction. */
+    fn side_anchor(reference: [f64; 2], bounds: Option<BoundingBox>, horizontal: bool, direction_x: f64, direction_y: f64) -> [f64; 2] {
+        let Some(bounds) = bounds else {
+            return reference;
+        };
+
+        if horizontal {
+            [reference[0] + direction_x * bounds.width() / 2.0, reference[1]]
+        } else {
+            [reference[0], reference[1] + direction_y * bounds.height() / 2.0]
+        }
+    }
+
+    fn path(&self) -> Path {
+        let point = |[x, y]: [f64; 2]| Point2D::new(x, y);
+
+        let Some(first) = self.route.first() else {
+            return Path::new(vec![]);
+        };
+
+        let mut commands: Vec<Command> = vec![Command::MoveTo(MoveTo { to_point: point(*first) })];
+
+        match (self.routing, self.route.as_slice()) {
+            (Routing::Curved, [_, control_point_1, control_point_2, end]) =>
+                commands.push(Command::BezierTo(BezierTo {
+                    control_point_1: point(*control_point_1),
+                    control_point_2: point(*control_point_2),
+                    to_point: point(*end),
+                })),
+            (_, [_, rest @ ..]) =>
+                commands.extend(rest.iter().map(|to| Command::LineTo(LineTo { to_point: point(*to) }))),
+            _ => {}
+        }
+
+        Path::new(commands)
+    }
+
+    /* Arrowhead at the tip, pointing away from the previous route point. Drawn solid in the stroke color of the connector. */
+    fn render_arrowhead(renderer: &mut dyn BoardRenderer, arrowhead: Arrowhead, from: [f64; 2], tip: [f64; 2], style: &Style) {
+        let filled: bool = matches!(arrowhead, Arrowhead::Triangle | Arrowhead::Diamond);
+        let arrowhead_style: Style = Style {
+            fill_color: filled.then(|| style.stroke_color.clone()),
+            dash_array: vec![],
+            ..style.clone()
+        };
+        renderer.set_style(Some(&arrowhead_style));
+
+        let shape_style: &ShapeStyle = &ShapeStyle::default();
+        let angle: f64 = (tip[1] - from[1]).atan2(tip[0] - from[0]);
+        let at = |length: f64, side: f64| {
+            let angle: f64 = angle + side * ARROWHEAD_ANGLE;
+            Point2D::new(tip[0] - length * angle.cos(), tip[1] - length * angle.sin())
+        };
+        let tip_point: Point2D = Point2D::new(tip[0], tip[1]);
+
+        match arrowhead {
+            Arrowhead::None => {}
+            Arrowhead::Arrow => {
+                let path: Path = Path::new(vec![
+                    Command::MoveTo(MoveTo { to_point: at(ARROWHEAD_LENGTH, 1.0) }),
+                    Command::LineTo(LineTo { to_point: tip_point }),
+                    Command::LineTo(LineTo { to_point: at(ARROWHEAD_LENGTH, -1.0) }),
+                ]);
+
+                renderer.path(&path, shape_style, None);
+            }
+            Arrowhead::Triangle => {
+                let triangle: Polygon<Point2D> = Polygon::new(vec![tip_point, at(ARROWHEAD_LENGTH, 1.0), at(ARROWHEAD_LENGTH, -1.0)]);
+
+                renderer.polygon_2d(&triangle, shape_style, None);
+            }
+            Arrowhead::Diamond => {
+                let back: Point2D = at(ARROWHEAD_LENGTH * 2.0 * ARROWHEAD_ANGLE.cos(), 0.0);
+                let diamond: Polygon<Point2D> = Polygon::new(vec![tip_point, at(ARROWHEAD_LENGTH, 1.0), back, at(ARROWHEAD_LENGTH, -1.0)]);
+
+                renderer.polygon_2d(&diamond, shape_style, None);
+            }
+        }
+
+        renderer.set_style(Some(style));
+    }
+
+    fn render(entity: &Entity<Id>, renderer: &mut dyn BoardRenderer) {
+        let Some(model) = model_of::<ConnectorModel>(entity) else {
+            return;
+        };
+
+        let [first, second, ..] = model.route.as_slice() else {
+            return;
+        };
+        let [.., before_last, last] = model.route.as_slice() else {
+            return;
+        };
+
+        let style: Style = entity
+            .query::<EntityStyle>()
+            .map(|entity_style| entity_style.style.clone())
+            .unwrap_or_default();
+
+        renderer.set_style(Some(&style));
+        renderer.path(&model.path(), &ShapeStyle::default(), None);
+        Self::render_arrowhead(renderer, model.start_arrowhead, *second, *first, &style);
+        Self::render_arrowhead(renderer, model.end_arrowhead, *before_last, *last, &style);
+
+        if let Some(label) = model.label.as_deref().filter(|label| !label.is_empty()) {
+            /* Labels are short single lines, estimated width is enough to center them identically everywhere. */
+            let font: Font = Font::default();
+            let width: f64 = ApproximateTextMeasure.width(label, &font);
+            let ([x1, y1], [x2, y2]) = (*first, *last);
+
+            let line: TextLine = TextLine {
+                text: label.to_string(),
+                start: 0,
+                x: (x1 + x2) / 2.0 - width / 2.0,
+                y: (y1 + y2) / 2.0 - font.line_height() / 2.0,
+                width,
+            };
+
+            renderer.text(&line, &font, &style.stroke_color);
+        }
+    }
+}
+
+impl Default for ConnectorModel {
+    fn default() -> Self {
+        Self {
+            start: Endpoint::Free { x: 0.0, y: 0.0 },
+            end: Endpoint::Free { x: 0.0, y: 0.0 },
+            routing: Routing::Straight,
+            start_arrowhead: Arrowhead::None,
+            end_arrowhead: Arrowhead::Arrow,
+            label: None,
+            route: vec![],
+        }
+    }
+}
+
+impl AsSerialize for ConnectorModel {
+    fn as_serialize(&self) -> &dyn dyn_serde::ser::Serialize {
+        self
+    }
+}
+
+impl Model for ConnectorModel {}
+
+impl DefaultEntity<Id> for ConnectorModel {
+    fn default_entity(id: Id) -> Entity<Id> {
+        Entity::new(id, ConnectorModel::default(), FeatureSet::empty())
+    }
+}
+
+impl StandardFeatureSet<Id> for ConnectorModel {
+    fn standard_feature_set() -> FeatureSet<Id> {
+        board_feature_set(ConnectorModel::render)
+    }
+}
@@ -0,0 +1,69 @@
+use crate::element::id::Id;
+use crate::element::{board_feature_set, model_of};
+use crate::renderer::board_renderer::BoardRenderer;
+use entity_model_feature::entity::Entity;
+use entity_model_feature::feature_set::FeatureSet;
+use entity_model_feature::{AsSerialize, Model};
+use geometry::figure::ellipse::Ellipse;
+use geometry::point::point_2d::Point2D;
+use serde::{Deserialize, Serialize};
+use standard_entity_plugin::entity_model::{DefaultEntity, StandardFeatureSet};
+use standard_rendering_plugin::style::shape_style::ShapeStyle;
+
+/* Ellipse inscribed into the box. */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EllipseModel {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl EllipseModel {
+    pub fn ellipse(&self) -> Ellipse {
+        Ellipse {
+            center: Point2D::new(self.x + self.width / 2.0, self.y + self.height / 2.0),
+            radius_x: self.width.abs() / 2.0,
+            radius_y: self.height.abs() / 2.0,
+        }
+    }
+
+    fn render(entity: &Entity<Id>, renderer: &mut dyn BoardRenderer) {
+        let Some(model) = model_of::<EllipseModel>(entity) else {
+            return;
+        };
+
+        renderer.ellipse(&model.ellipse(), &ShapeStyle::default(), None);
+    }
+}
+
+impl Default for EllipseModel {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: 100.0,
+            height: 100.0,
+        }
+    }
+}
+
+impl AsSerialize for EllipseModel {
+    fn as_serialize(&self) -> &dyn dyn_serde::ser::Serialize {
+        self
+    }
+}
+
+impl Model for EllipseModel {}
+
+impl DefaultEntity<Id> for EllipseModel {
+    fn default_entity(id: Id) -> Entity<Id> {
+        Entity::new(id, EllipseModel::default(), FeatureSet::empty())
+    }
+}
+
+impl StandardFeatureSet<Id> for EllipseModel {
+    fn standard_feature_set() -> FeatureSet<Id> {
+        board_feature_set(EllipseModel::render)
+    }
+}
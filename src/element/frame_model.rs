@@ -0,0 +1,172 @@
+use crate::bounding_box::BoundingBox;
+use crate::element::id::Id;
+use crate::element::{board_feature_set, model_of};
+use crate::renderer::board_renderer::BoardRenderer;
+use crate::style::{EntityStyle, Style};
+use crate::text::font::Font;
+use crate::text::layout::TextLine;
+use entity_model_feature::entity::Entity;
+use entity_model_feature::feature_set::FeatureSet;
+use entity_model_feature::{AsSerialize, Model};
+use geometry::figure::path::command::line_to::LineTo;
+use geometry::figure::path::command::move_to::MoveTo;
+use geometry::figure::path::command::Command;
+use geometry::figure::path::Path;
+use geometry::point::point_2d::Point2D;
+use serde::{Deserialize, Serialize};
+use standard_entity_plugin::entity_model::{DefaultEntity, StandardFeatureSet};
+use standard_rendering_plugin::style::shape_style::ShapeStyle;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+const DEFAULT_WIDTH: f64 = 800.0;
+const BACKGROUND_COLOR: &str = "#ffffff";
+const BORDER_COLOR: &str = "#bdbdbd";
+const LABEL_COLOR: &str = "#616161";
+const LABEL_FONT_SIZE: f64 = 14.0;
+const LABEL_GAP: f64 = 4.0;
+const DEFAULT_NAME: &str = "Frame";
+
+#[derive(Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub enum PageSize {
+    /* Any size, drawn freely. */
+    Free,
+    A4Portrait,
+    A4Landscape,
+    /* 16:9 slide. */
+    Widescreen,
+}
+
+impl PageSize {
+    /* Width to height, None if the frame can have any proportions. */
+    pub fn aspect_ratio(&self) -> Option<f64> {
+        match self {
+            PageSize::Free => None,
+            PageSize::A4Portrait => Some(210.0 / 297.0),
+            PageSize::A4Landscape => Some(297.0 / 210.0),
+            PageSize::Widescreen => Some(16.0 / 9.0),
+        }
+    }
+}
+
+/* Named region of the board drawn behind other entities. Entities with their center inside it belong to it and are clipped by it. */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FrameModel {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    #[serde(default)]
+    pub name: String,
+    pub page_size: PageSize,
+}
+
+impl FrameModel {
+    pub fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::new(self.x, self.y, self.width, self.height)
+    }
+
+    /* Fixed page sizes keep the width and follow it with the height. */
+    pub fn resize(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = match self.page_size.aspect_ratio() {
+            Some(aspect_ratio) => width / aspect_ratio,
+            None => height,
+        };
+    }
+
+    /* Name shown above the frame. */
+    pub fn label(&self) -> &str {
+        match self.name.is_empty() {
+            true => DEFAULT_NAME,
+            false => &self.name,
+        }
+    }
+
+    fn render(entity: &Entity<Id>, renderer: &mut dyn BoardRenderer) {
+        let Some(model) = model_of::<FrameModel>(entity) else {
+            return;
+        };
+
+        let frame: BoundingBox = model.bounding_box();
+        let background: &str = entity
+            .query::<EntityStyle>()
+            .and_then(|entity_style| entity_style.style.fill_color.as_deref())
+            .unwrap_or(BACKGROUND_COLOR);
+
+        renderer.fill_rectangle(&frame, background);
+
+        let corners: [Point2D; 5] = [
+            Point2D::new(frame.left, frame.top),
+            Point2D::new(frame.right, frame.top),
+            Point2D::new(frame.right, frame.bottom),
+            Point2D::new(frame.left, frame.bottom),
+            Point2D::new(frame.left, frame.top),
+        ];
+        let border: Path = Path::new(
+            corners
+                .iter()
+                .enumerate()
+                .map(|(index, point)| match index {
+                    0 => Command::MoveTo(MoveTo { to_point: *point }),
+                    _ => Command::LineTo(LineTo { to_point: *point }),
+                })
+                .collect()
+        );
+
+        let style: Style = Style {
+            stroke_color: BORDER_COLOR.to_string(),
+            stroke_width: 1.0,
+            ..Style::default()
+        };
+
+        renderer.set_style(Some(&style));
+        renderer.path(&border, &ShapeStyle::default(), None);
+        renderer.set_style(None);
+
+        let font: Font = Font::new("sans-serif", LABEL_FONT_SIZE, 500);
+        let label: TextLine = TextLine {
+            text: model.label().to_string(),
+            start: 0,
+            x: frame.left,
+            y: frame.top - font.line_height() - LABEL_GAP,
+            width: 0.0,
+        };
+
+        renderer.text(&label, &font, LABEL_COLOR);
+    }
+}
+
+impl Default for FrameModel {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_WIDTH * 9.0 / 16.0,
+            name: String::new(),
+            page_size: PageSize::Widescreen,
+        }
+    }
+}
+
+impl AsSerialize for FrameModel {
+    fn as_serialize(&self) -> &dyn dyn_serde::ser::Serialize {
+        self
+    }
+}
+
+impl Model for FrameModel {}
+
+impl DefaultEntity<Id> for FrameModel {
+    fn default_entity(id: Id) -> Entity<Id> {
+        Entity::new(id, FrameModel::default(), FeatureSet::empty())
+    }
+}
+
+impl StandardFeatureSet<Id> for FrameModel {
+    fn standard_feature_set() -> FeatureSet<Id> {
+        board_feature_set(FrameModel::render)
+    }
+}
@@ -0,0 +1,197 @@
+pub mod simplification;
+pub mod smoothing;
+pub mod recognition;
+
+use crate::element::free_hand::smoothing::Smoothing;
+use crate::element::id::Id;
+use crate::element::{Build, Result};
+use crate::math;
+use entity_model_feature::entity::Entity;
+use geometry::figure::path::command::bezier_to::BezierTo;
+use geometry::figure::path::command::line_to::LineTo;
+use geometry::figure::path::command::move_to::MoveTo;
+use geometry::figure::path::command::Command;
+use geometry::figure::path::Path;
+use geometry::point::point_2d::Point2D;
+use standard_entity_plugin::model::path_model::PathModel;
+
+/* Sampled points of the freehand stroke. Curves are represented by their end points. */
+pub fn points(path_model: &PathModel) -> Vec<Point2D> {
+    path_model.path
+        .commands()
+        .iter()
+        .filter_map(|command| match command {
+            Command::MoveTo(move_to) => Some(move_to.to_point),
+            Command::LineTo(line_to) => Some(line_to.to_point),
+            Command::BezierTo(bezier_to) => Some(bezier_to.to_point),
+            Command::ArcTo(arc_to) => Some(arc_to.to_point),
+            Command::HorizontalLineTo(_) | Command::VerticalLineTo(_) | Command::Close => None,
+        })
+        .collect()
+}
+
+pub fn polyline(points: &[Point2D]) -> Path {
+    let commands: Vec<Command> = points
+        .iter()
+        .enumerate()
+        .map(|(index, point)| match index {
+            0 => Command::MoveTo(MoveTo { to_point: *point }),
+            _ => Command::LineTo(LineTo { to_point: *point }),
+        })
+        .collect();
+
+    Path::new(commands)
+}
+
+/* Drawn part of a stroke, straight or a cubic bezier curve. */
+#[derive(Copy, Clone)]
+pub enum Segment {
+    Line { from: Point2D, to: Point2D },
+    Curve { from: Point2D, control_point_1: Point2D, control_point_2: Point2D, to: Point2D },
+}
+
+impl Segment {
+    pub fn first_point(&self) -> Point2D {
+        match self {
+            Segment::Line { from, .. } | Segment::Curve { from, .. } => *from,
+        }
+    }
+
+    pub fn point_at(&self, t: f64) -> Point2D {
+        match self {
+            Segment::Line { from, to } => math::lerp(from, to, t),
+            Segment::Curve { .. } => self.split(t).0.last_point(),
+        }
+    }
+
+    /* Length of the line, or of the control polygon of the curve, which is never shorter than the curve. */
+    pub fn hull_length(&self) -> f64 {
+        match self {
+            Segment::Line { from, to } => math::distance(from, to),
+            Segment::Curve { from, control_point_1, control_point_2, to } =>
+                math::distance(from, control_point_1) + math::distance(control_point_1, control_point_2) + math::distance(control_point_2, to),
+        }
+    }
+
+    /* Part of the segment between the parameters. Curves are cut with de Casteljau's algorithm, so the part lies on the curve. */
+    pub fn part(&self, start: f64, end: f64) -> Segment {
+        let before_end: Segment = self.split(end).0;
+
+        match end > 0.0 {
+            true => before_end.split(start / end).1,
+            false => before_end,
+        }
+    }
+
+    fn last_point(&self) -> Point2D {
+        match self {
+            Segment::Line { to, .. } | Segment::Curve { to, .. } => *to,
+        }
+    }
+
+    fn split(&self, t: f64) -> (Segment, Segment) {
+        match self {
+            Segment::Line { from, to } => {
+                let middle: Point2D = math::lerp(from, to, t);
+
+                (Segment::Line { from: *from, to: middle }, Segment::Line { from: middle, to: *to })
+            }
+            Segment::Curve { from, control_point_1, control_point_2, to } => {
+                let (a, b, c) = (math::lerp(from, control_point_1, t), math::lerp(control_point_1, control_point_2, t), math::lerp(control_point_2, to, t));
+                let (d, e) = (math::lerp(&a, &b, t), math::lerp(&b, &c, t));
+                let middle: Point2D = math::lerp(&d, &e, t);
+
+                (
+                    Segment::Curve { from: *from, control_point_1: a, control_point_2: d, to: middle },
+                    Segment::Curve { from: middle, control_point_1: e, control_point_2: c, to: *to },
+                )
+            }
+        }
+    }
+
+    fn command(&self) -> Command {
+        match self {
+            Segment::Line { to, .. } => Command::LineTo(LineTo { to_point: *to }),
+            Segment::Curve { control_point_1, control_point_2, to, .. } => Command::BezierTo(BezierTo {
+                control_point_1: *control_point_1,
+                control_point_2: *control_point_2,
+                to_point: *to,
+            }),
+        }
+    }
+}
+
+/* Segments of the stroke as drawn, arcs are represented by straight lines to their end points. */
+pub fn segments(path_model: &PathModel) -> Vec<Segment> {
+    let mut current: Option<Point2D> = None;
+    let mut segments: Vec<Segment> = vec![];
+
+    for command in path_model.path.commands() {
+        let (segment, to) = match (command, current) {
+            (Command::MoveTo(move_to), _) => (None, move_to.to_point),
+            (Command::LineTo(line_to), Some(from)) => (Some(Segment::Line { from, to: line_to.to_point }), line_to.to_point),
+            (Command::ArcTo(arc_to), Some(from)) => (Some(Segment::Line { from, to: arc_to.to_point }), arc_to.to_point),
+            (Command::BezierTo(bezier_to), Some(from)) => (
+                Some(Segment::Curve {
+                    from,
+                    control_point_1: bezier_to.control_point_1,
+                    control_point_2: bezier_to.control_point_2,
+                    to: bezier_to.to_point,
+                }),
+                bezier_to.to_point,
+            ),
+            _ => continue,
+        };
+
+        segments.extend(segment);
+        current = Some(to);
+    }
+
+    segments
+}
+
+/* Path drawing the connected segments. */
+pub fn path(segments: &[Segment]) -> Path {
+    let commands: Vec<Command> = segments
+        .first()
+        .map(|first| Command::MoveTo(MoveTo { to_point: first.first_point() }))
+        .into_iter()
+        .chain(segments.iter().map(Segment::command))
+        .collect();
+
+    Path::new(commands)
+}
+
+/* Copy of the stroke with the same style, but different path. */
+pub fn with_path(path_model: &PathModel, path: Path) -> PathModel {
+    let mut path_model: PathModel = path_model.clone();
+    path_model.path = path;
+
+    path_model
+}
+
+/* Post-processing applied to freehand strokes when drawing finishes. */
+#[derive(Copy, Clone)]
+pub struct StrokeOptions {
+    pub smoothing: Smoothing,
+    /* Ramer–Douglas–Peucker tolerance. Zero disables simplification. */
+    pub tolerance: f64,
+}
+
+impl Default for StrokeOptions {
+    fn default() -> Self {
+        Self {
+            smoothing: Smoothing::None,
+            tolerance: 0.0,
+        }
+    }
+}
+
+/* Simplify and smooth finished stroke. Returns new entity with the same id. */
+pub fn finish_stroke(entity: Entity<Id>, options: &StrokeOptions) -> Result<Entity<Id>> {
+    Build::modified(&entity, |path_model: &mut PathModel| {
+        let points: Vec<Point2D> = simplification::simplify(&points(path_model), options.tolerance);
+
+        path_model.path = options.smoothing.fit(&points);
+    })
+}
@@ -0,0 +1,261 @@
+use crate::bounding_box::BoundingBox;
+use crate::element::connector_model::{Arrowhead, ConnectorModel, Endpoint};
+use crate::element::ellipse_model::EllipseModel;
+use crate::element::free_hand::simplification;
+use crate::element::id::Id;
+use crate::element::line_model::LineModel;
+use crate::element::{Build, ElementType, Result};
+use crate::math;
+use crate::style::Style;
+use entity_model_feature::entity::Entity;
+use geometry::figure::polygon::Polygon;
+use geometry::figure::rectangle::Rectangle;
+use geometry::point::point_2d::Point2D;
+use standard_entity_plugin::model::polygon_model::PolygonModel;
+use standard_entity_plugin::model::rectangle_model::RectangleModel;
+
+/* Stroke is closed if its ends are closer than this part of the bounding box diagonal. */
+const CLOSED_THRESHOLD: f64 = 0.2;
+/* Corner detection tolerance as a part of the bounding box diagonal. */
+const CORNER_TOLERANCE: f64 = 0.06;
+/* Arrow head segments must stay within this part of the shaft length from the shaft end. */
+const ARROW_HEAD_SIZE: f64 = 0.4;
+
+#[derive(Clone)]
+pub enum Shape {
+    Rectangle(BoundingBox),
+    Polygon(Vec<Point2D>),
+    Ellipse {
+        center: Point2D,
+        radius_x: f64,
+        radius_y: f64,
+    },
+    Line(Point2D, Point2D),
+    Arrow(Point2D, Point2D),
+}
+
+impl Shape {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shape::Rectangle(_) => "rectangle",
+            Shape::Polygon(_) => "polygon",
+            Shape::Ellipse { .. } => "ellipse",
+            Shape::Line(..) => "line",
+            Shape::Arrow(..) => "arrow",
+        }
+    }
+}
+
+/* Best matching shape with confidence in range 0..=1. */
+#[derive(Clone)]
+pub struct Recognition {
+    pub shape: Shape,
+    pub confidence: f64,
+}
+
+impl Recognition {
+    /* Build clean entity replacing the stroke. */
+    pub fn build(&self, owner_id: &str, style: Option<Style>) -> Result<Entity<Id>> {
+        match &self.shape {
+            Shape::Rectangle(bounding_box) =>
+                Build::default_with(owner_id, ElementType::Rectangle, style, |model: &mut RectangleModel| {
+                    model.rectangle = Rectangle {
+                        top_left: Point2D::new(bounding_box.left, bounding_box.top),
+                        width: bounding_box.width(),
+                        height: bounding_box.height(),
+                    };
+                }),
+            Shape::Polygon(vertices) =>
+                Build::default_with(owner_id, ElementType::Polygon, style, |model: &mut PolygonModel| {
+                    model.polygon = Polygon::new(vertices.clone());
+                }),
+            Shape::Ellipse { center, radius_x, radius_y } =>
+                Build::default_with(owner_id, ElementType::Ellipse, style, |model: &mut EllipseModel| {
+                    model.x = center.x - radius_x;
+                    model.y = center.y - radius_y;
+                    model.width = radius_x * 2.0;
+                    model.height = radius_y * 2.0;
+                }),
+            Shape::Line(start, end) =>
+                Build::default_with(owner_id, ElementType::Line, style, |model: &mut LineModel| {
+                    (model.x1, model.y1) = (start.x, start.y);
+                    (model.x2, model.y2) = (end.x, end.y);
+                }),
+            Shape::Arrow(start, end) =>
+                Build::default_with(owner_id, ElementType::Connector, style, |model: &mut ConnectorModel| {
+                    model.start = Endpoint::Free { x: start.x, y: start.y };
+                    model.end = Endpoint::Free { x: end.x, y: end.y };
+                    model.end_arrowhead = Arrowhead::Arrow;
+                }),
+        }
+    }
+}
+
+/* Recognize the stroke as one of the known shapes. Returns None if nothing matches at all. */
+pub fn recognize(points: &[Point2D]) -> Option<Recognition> {
+    let bounding_box: BoundingBox = BoundingBox::from_points(points)?;
+    let diagonal: f64 = bounding_box.width().hypot(bounding_box.height());
+
+    if points.len() < 2 || diagonal == 0.0 {
+        return None;
+    }
+
+    let first: &Point2D = points.first()?;
+    let last: &Point2D = points.last()?;
+
+    let candidates: Vec<Recognition> = if math::distance(first, last) < diagonal * CLOSED_THRESHOLD {
+        [
+            recognize_rectangle(points, &bounding_box, diagonal),
+            recognize_ellipse(points, &bounding_box),
+            recognize_polygon(points, diagonal),
+        ].into_iter().flatten().collect()
+    } else {
+        [
+            recognize_line(points),
+            recognize_arrow(points, diagonal),
+        ].into_iter().flatten().collect()
+    };
+
+    candidates
+        .into_iter()
+        .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+}
+
+fn length(points: &[Point2D]) -> f64 {
+    points
+        .windows(2)
+        .map(|segment| math::distance(&segment[0], &segment[1]))
+        .sum()
+}
+
+/* Corners of the closed stroke, without repeating the first one. */
+fn corners(points: &[Point2D], diagonal: f64) -> Vec<Point2D> {
+    let mut corners: Vec<Point2D> = simplification::simplify(points, diagonal * CORNER_TOLERANCE);
+
+    if corners.len() > 2 {
+        let (first, last) = (corners[0], corners[corners.len() - 1]);
+
+        /* Ends of the stroke meet somewhere on the side or in the same corner. */
+        if math::distance(&first, &last) < diagonal * CLOSED_THRESHOLD {
+            corners.pop();
+        }
+    }
+
+    corners
+}
+
+/* Mean distance from the points to the closed outline, relative to the diagonal. */
+fn outline_error(points: &[Point2D], outline: &[Point2D], diagonal: f64) -> f64 {
+    let edges: Vec<(&Point2D, &Point2D)> = outline
+        .iter()
+        .zip(outline.iter().cycle().skip(1))
+        .collect();
+
+    let total: f64 = points
+        .iter()
+        .map(|point| edges
+            .iter()
+            .map(|(start, end)| math::distance_to_segment(point, start, end))
+            .fold(f64::INFINITY, f64::min)
+        )
+        .sum();
+
+    total / points.len() as f64 / diagonal
+}
+
+fn recognize_rectangle(points: &[Point2D], bounding_box: &BoundingBox, diagonal: f64) -> Option<Recognition> {
+    if corners(points, diagonal).len() != 4 {
+        return None;
+    }
+
+    let outline: [Point2D; 4] = [
+        Point2D::new(bounding_box.left, bounding_box.top),
+        Point2D::new(bounding_box.right, bounding_box.top),
+        Point2D::new(bounding_box.right, bounding_box.bottom),
+        Point2D::new(bounding_box.left, bounding_box.bottom),
+    ];
+
+    Some(Recognition {
+        shape: Shape::Rectangle(*bounding_box),
+        confidence: (1.0 - outline_error(points, &outline, diagonal) * 10.0).max(0.0),
+    })
+}
+
+fn recognize_ellipse(points: &[Point2D], bounding_box: &BoundingBox) -> Option<Recognition> {
+    let (center_x, center_y) = bounding_box.center();
+    let radius_x: f64 = bounding_box.width() / 2.0;
+    let radius_y: f64 = bounding_box.height() / 2.0;
+
+    if radius_x == 0.0 || radius_y == 0.0 {
+        return None;
+    }
+
+    /* Normalized radius is 1 for every point lying on the ellipse. */
+    let error: f64 = points
+        .iter()
+        .map(|point| (((point.x - center_x) / radius_x).hypot((point.y - center_y) / radius_y) - 1.0).abs())
+        .sum::<f64>() / points.len() as f64;
+
+    Some(Recognition {
+        shape: Shape::Ellipse {
+            center: Point2D::new(center_x, center_y),
+            radius_x,
+            radius_y,
+        },
+        confidence: (1.0 - error * 5.0).max(0.0),
+    })
+}
+
+fn recognize_polygon(points: &[Point2D], diagonal: f64) -> Option<Recognition> {
+    let corners: Vec<Point2D> = corners(points, diagonal);
+
+    if !(3..=8).contains(&corners.len()) {
+        return None;
+    }
+
+    let error: f64 = outline_error(points, &corners, diagonal);
+
+    Some(Recognition {
+        /* Polygon fits any closed stroke best, so it is slightly penalized in favor of specific shapes. */
+        confidence: (0.95 - error * 10.0).max(0.0),
+        shape: Shape::Polygon(corners),
+    })
+}
+
+fn recognize_line(points: &[Point2D]) -> Option<Recognition> {
+    let (first, last) = (points.first()?, points.last()?);
+    let straightness: f64 = math::distance(first, last) / length(points);
+
+    Some(Recognition {
+        shape: Shape::Line(*first, *last),
+        confidence: ((straightness - 0.8) * 5.0).clamp(0.0, 1.0),
+    })
+}
+
+/* Shaft followed by the head drawn with the same stroke. */
+fn recognize_arrow(points: &[Point2D], diagonal: f64) -> Option<Recognition> {
+    let corners: Vec<Point2D> = simplification::simplify(points, diagonal * CORNER_TOLERANCE);
+
+    let [start, end, head @ ..] = corners.as_slice() else {
+        return None;
+    };
+
+    if !(2..=3).contains(&head.len()) {
+        return None;
+    }
+
+    let shaft: f64 = math::distance(start, end);
+    let head_extent: f64 = head
+        .iter()
+        .map(|point| math::distance(point, end))
+        .fold(0.0, f64::max);
+
+    if head_extent > shaft * ARROW_HEAD_SIZE {
+        return None;
+    }
+
+    Some(Recognition {
+        shape: Shape::Arrow(*start, *end),
+        confidence: (1.0 - head_extent / shaft).clamp(0.0, 1.0),
+    })
+}
@@ -0,0 +1,41 @@
+use crate::math;
+use geometry::point::point_2d::Point2D;
+
+/*
+ * Ramer–Douglas–Peucker simplification.
+ * Removes points closer than tolerance to the line connecting their neighbours that are kept.
+ */
+pub fn simplify(points: &[Point2D], tolerance: f64) -> Vec<Point2D> {
+    if points.len() < 3 || tolerance <= 0.0 {
+        return points.to_vec();
+    }
+
+    let mut keep: Vec<bool> = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    /* Iterative to not overflow the stack on long strokes. */
+    let mut ranges: Vec<(usize, usize)> = vec![(0, points.len() - 1)];
+
+    while let Some((first, last)) = ranges.pop() {
+        let farthest = (first + 1..last)
+            .map(|index| (index, math::distance_to_segment(&points[index], &points[first], &points[last])))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        let Some((index, distance)) = farthest else {
+            continue;
+        };
+
+        if distance > tolerance {
+            keep[index] = true;
+            ranges.push((first, index));
+            ranges.push((index, last));
+        }
+    }
+
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(point, keep)| keep.then_some(*point))
+        .collect()
+}
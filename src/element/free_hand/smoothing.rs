@@ -0,0 +1,86 @@
+use geometry::figure::path::command::bezier_to::BezierTo;
+use geometry::figure::path::command::line_to::LineTo;
+use geometry::figure::path::command::move_to::MoveTo;
+use geometry::figure::path::command::Command;
+use geometry::figure::path::Path;
+use geometry::point::point_2d::Point2D;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[derive(Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub enum Smoothing {
+    /* Keep the stroke as a polyline. */
+    None,
+    /* Curve passing through every point. */
+    CatmullRom,
+    /* Limit curve of Chaikin corner cutting. Doesn't pass through inner points, but never overshoots. */
+    Chaikin,
+}
+
+impl Smoothing {
+    pub fn fit(&self, points: &[Point2D]) -> Path {
+        if points.len() < 3 {
+            return super::polyline(points);
+        }
+
+        match self {
+            Smoothing::None => super::polyline(points),
+            Smoothing::CatmullRom => catmull_rom(points),
+            Smoothing::Chaikin => chaikin(points),
+        }
+    }
+}
+
+/* Uniform Catmull-Rom spline converted into cubic bezier segments. End points are duplicated. */
+fn catmull_rom(points: &[Point2D]) -> Path {
+    let last: usize = points.len() - 1;
+    let mut commands: Vec<Command> = vec![Command::MoveTo(MoveTo { to_point: points[0] })];
+
+    for index in 0..last {
+        let previous: &Point2D = &points[index.saturating_sub(1)];
+        let start: &Point2D = &points[index];
+        let end: &Point2D = &points[index + 1];
+        let next: &Point2D = &points[(index + 2).min(last)];
+
+        commands.push(Command::BezierTo(BezierTo {
+            control_point_1: Point2D::new(start.x + (end.x - previous.x) / 6.0, start.y + (end.y - previous.y) / 6.0),
+            control_point_2: Point2D::new(end.x - (next.x - start.x) / 6.0, end.y - (next.y - start.y) / 6.0),
+            to_point: *end,
+        }));
+    }
+
+    Path::new(commands)
+}
+
+/*
+ * Chaikin corner cutting converges to the quadratic B-spline over the points.
+ * Each of its quadratic segments is written as an equivalent cubic bezier.
+ */
+fn chaikin(points: &[Point2D]) -> Path {
+    let middle = |a: &Point2D, b: &Point2D| Point2D::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0);
+    let two_thirds = |from: &Point2D, to: &Point2D| Point2D::new(from.x + (to.x - from.x) * 2.0 / 3.0, from.y + (to.y - from.y) * 2.0 / 3.0);
+
+    let last: usize = points.len() - 1;
+    let mut commands: Vec<Command> = vec![
+        Command::MoveTo(MoveTo { to_point: points[0] }),
+        Command::LineTo(LineTo { to_point: middle(&points[0], &points[1]) }),
+    ];
+
+    for index in 1..last {
+        let control: &Point2D = &points[index];
+        let start: Point2D = middle(&points[index - 1], control);
+        let end: Point2D = middle(control, &points[index + 1]);
+
+        commands.push(Command::BezierTo(BezierTo {
+            control_point_1: two_thirds(&start, control),
+            control_point_2: two_thirds(&end, control),
+            to_point: end,
+        }));
+    }
+
+    commands.push(Command::LineTo(LineTo { to_point: points[last] }));
+
+    Path::new(commands)
+}
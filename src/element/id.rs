@@ -1,12 +1,18 @@
 use crate::element::ElementType;
+use crate::platform;
 use entity_model_feature::entity_id::EntityId;
 use entity_model_feature::AsSerialize;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::wasm_bindgen;
 
-#[wasm_bindgen]
-#[derive(Clone, Serialize, Deserialize, Eq, PartialEq)]
+/* Last generated index. Indices are strictly increasing, so ids generated within the same millisecond don't collide. */
+static LAST_INDEX: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Serialize, Deserialize, Eq, PartialEq, Hash)]
 pub struct Id {
     owner_id: String,
     index: usize,
@@ -17,11 +23,22 @@ impl Id {
     pub fn generate(owner_id: &str, element_type: ElementType) -> Self {
         Self {
             owner_id: owner_id.to_string(),
-            index: js_sys::Date::new_0().get_milliseconds() as usize,
+            index: Self::next_index(),
             element_type
         }
     }
 
+    fn next_index() -> usize {
+        /* usize is 32 bit on wasm, so the timestamp wraps around instead of saturating. */
+        let now: usize = (platform::now() as u64 % usize::MAX as u64) as usize;
+
+        let previous: usize = LAST_INDEX
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| Some(now.max(last + 1)))
+            .expect("Index update closure always returns a value");
+
+        now.max(previous + 1)
+    }
+
     pub fn owner_id(&self) -> &str {
         self.owner_id.as_str()
     }
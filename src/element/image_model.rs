@@ -0,0 +1,185 @@
+pub mod natural_size;
+
+use crate::bounding_box::BoundingBox;
+use crate::element::id::Id;
+use crate::element::{board_feature_set, model_of};
+use crate::renderer::board_renderer::BoardRenderer;
+use base64::Engine;
+use entity_model_feature::entity::Entity;
+use entity_model_feature::feature_set::FeatureSet;
+use entity_model_feature::{AsSerialize, Model};
+use serde::{Deserialize, Serialize};
+use standard_entity_plugin::entity_model::{DefaultEntity, StandardFeatureSet};
+
+/* Size used when the natural size can't be read from the image data. */
+const FALLBACK_SIZE: f64 = 100.0;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub enum ImageSource {
+    /* Image data is kept in the board itself. */
+    Embedded {
+        data_url: String,
+    },
+    Referenced {
+        url: String,
+    },
+}
+
+impl ImageSource {
+    pub fn embed(bytes: &[u8], mime: &str) -> Self {
+        ImageSource::Embedded {
+            data_url: format!("data:{mime};base64,{}", base64::engine::general_purpose::STANDARD.encode(bytes)),
+        }
+    }
+
+    pub fn href(&self) -> &str {
+        match self {
+            ImageSource::Embedded { data_url } => data_url,
+            ImageSource::Referenced { url } => url,
+        }
+    }
+}
+
+/* Image with its size in pixels. */
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct Bitmap {
+    pub source: ImageSource,
+    pub natural_width: f64,
+    pub natural_height: f64,
+}
+
+/* Visible part of the image in its natural pixels. */
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Crop {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/* Cropped image stretched into the box. Box keeps the aspect ratio of the crop. */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ImageModel {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub bitmap: Bitmap,
+    #[serde(default)]
+    pub crop: Option<Crop>,
+}
+
+impl ImageModel {
+    /* Embedded image shown in its natural size with top left corner at the point. */
+    pub fn embedded(bytes: &[u8], mime: &str, x: f64, y: f64) -> Self {
+        let (natural_width, natural_height) = natural_size::natural_size(bytes)
+            .map_or((FALLBACK_SIZE, FALLBACK_SIZE), |(width, height)| (width as f64, height as f64));
+
+        Self::new(ImageSource::embed(bytes, mime), natural_width, natural_height, x, y)
+    }
+
+    pub fn new(source: ImageSource, natural_width: f64, natural_height: f64, x: f64, y: f64) -> Self {
+        Self {
+            x,
+            y,
+            width: natural_width,
+            height: natural_height,
+            bitmap: Bitmap {
+                source,
+                natural_width,
+                natural_height,
+            },
+            crop: None,
+        }
+    }
+
+    /* Part of the natural image that is drawn. */
+    pub fn source_box(&self) -> BoundingBox {
+        match self.crop {
+            Some(crop) => BoundingBox::new(crop.x, crop.y, crop.width, crop.height),
+            None => BoundingBox::new(0.0, 0.0, self.bitmap.natural_width, self.bitmap.natural_height),
+        }
+    }
+
+    pub fn target_box(&self) -> BoundingBox {
+        BoundingBox::new(self.x, self.y, self.width, self.height)
+    }
+
+    pub fn aspect_ratio(&self) -> f64 {
+        let source: BoundingBox = self.source_box();
+
+        if source.height() == 0.0 {
+            return 1.0;
+        }
+
+        source.width() / source.height()
+    }
+
+    /* Resize keeping aspect ratio. The box fits into the requested size. */
+    pub fn resize(&mut self, width: f64, height: f64) {
+        let aspect_ratio: f64 = self.aspect_ratio();
+
+        if width / height.max(f64::EPSILON) > aspect_ratio {
+            self.width = height * aspect_ratio;
+            self.height = height;
+        } else {
+            self.width = width;
+            self.height = width / aspect_ratio;
+        }
+    }
+
+    /* Crop is clamped into the image. Width is kept, height follows the new aspect ratio. None shows whole image. */
+    pub fn set_crop(&mut self, crop: Option<Crop>) {
+        let (natural_width, natural_height) = (self.bitmap.natural_width, self.bitmap.natural_height);
+
+        self.crop = crop
+            .map(|crop| {
+                let left: f64 = crop.x.clamp(0.0, natural_width);
+                let top: f64 = crop.y.clamp(0.0, natural_height);
+
+                Crop {
+                    x: left,
+                    y: top,
+                    width: crop.width.clamp(0.0, natural_width - left),
+                    height: crop.height.clamp(0.0, natural_height - top),
+                }
+            })
+            .filter(|crop| crop.width > 0.0 && crop.height > 0.0);
+
+        self.height = self.width / self.aspect_ratio();
+    }
+
+    fn render(entity: &Entity<Id>, renderer: &mut dyn BoardRenderer) {
+        let Some(model) = model_of::<ImageModel>(entity) else {
+            return;
+        };
+
+        renderer.image(&model.bitmap, &model.source_box(), &model.target_box());
+    }
+}
+
+impl Default for ImageModel {
+    fn default() -> Self {
+        Self::new(ImageSource::Referenced { url: String::new() }, FALLBACK_SIZE, FALLBACK_SIZE, 0.0, 0.0)
+    }
+}
+
+impl AsSerialize for ImageModel {
+    fn as_serialize(&self) -> &dyn dyn_serde::ser::Serialize {
+        self
+    }
+}
+
+impl Model for ImageModel {}
+
+impl DefaultEntity<Id> for ImageModel {
+    fn default_entity(id: Id) -> Entity<Id> {
+        Entity::new(id, ImageModel::default(), FeatureSet::empty())
+    }
+}
+
+impl StandardFeatureSet<Id> for ImageModel {
+    fn standard_feature_set() -> FeatureSet<Id> {
+        board_feature_set(ImageModel::render)
+    }
+}
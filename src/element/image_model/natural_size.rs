@@ -0,0 +1,82 @@
+/* Pixel size read from the header of PNG, GIF, JPEG or WebP data. None for other or broken formats. */
+pub fn natural_size(bytes: &[u8]) -> Option<(u32, u32)> {
+    png(bytes)
+        .or_else(|| gif(bytes))
+        .or_else(|| jpeg(bytes))
+        .or_else(|| webp(bytes))
+}
+
+fn u16_be(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u16::from_be_bytes([*bytes.get(at)?, *bytes.get(at + 1)?]) as u32)
+}
+
+fn u16_le(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u16::from_le_bytes([*bytes.get(at)?, *bytes.get(at + 1)?]) as u32)
+}
+
+fn u24_le(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes([*bytes.get(at)?, *bytes.get(at + 1)?, *bytes.get(at + 2)?, 0]))
+}
+
+fn u32_be(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn png(bytes: &[u8]) -> Option<(u32, u32)> {
+    if !bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return None;
+    }
+
+    /* IHDR is always the first chunk. */
+    Some((u32_be(bytes, 16)?, u32_be(bytes, 20)?))
+}
+
+fn gif(bytes: &[u8]) -> Option<(u32, u32)> {
+    if !bytes.starts_with(b"GIF8") {
+        return None;
+    }
+
+    Some((u16_le(bytes, 6)?, u16_le(bytes, 8)?))
+}
+
+fn jpeg(bytes: &[u8]) -> Option<(u32, u32)> {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+
+    let mut at: usize = 2;
+
+    loop {
+        if *bytes.get(at)? != 0xFF {
+            return None;
+        }
+
+        let marker: u8 = *bytes.get(at + 1)?;
+
+        match marker {
+            /* Fill bytes before the marker. */
+            0xFF => at += 1,
+            /* Start of frame markers, except huffman and arithmetic coding tables. */
+            0xC0..=0xCF if marker != 0xC4 && marker != 0xC8 && marker != 0xCC =>
+                return Some((u16_be(bytes, at + 7)?, u16_be(bytes, at + 5)?)),
+            _ => at += 2 + u16_be(bytes, at + 2)? as usize,
+        }
+    }
+}
+
+fn webp(bytes: &[u8]) -> Option<(u32, u32)> {
+    if !bytes.starts_with(b"RIFF") || bytes.get(8..12)? != b"WEBP" {
+        return None;
+    }
+
+    match bytes.get(12..16)? {
+        b"VP8X" => Some((u24_le(bytes, 24)? + 1, u24_le(bytes, 27)? + 1)),
+        b"VP8 " => Some((u16_le(bytes, 26)? & 0x3FFF, u16_le(bytes, 28)? & 0x3FFF)),
+        b"VP8L" => {
+            let bits: u32 = u32::from_le_bytes(bytes.get(21..25)?.try_into().ok()?);
+
+            Some(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1))
+        }
+        _ => None,
+    }
+}
@@ -1,15 +1,65 @@
 use crate::element::id::Id;
 use crate::element::Build;
+use crate::schema;
+use crate::style::{EntityStyle, Style};
 use entity_model_feature::entity::Entity;
-use serde::Deserialize;
+use entity_model_feature::AsSerialize;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize};
 
-#[derive(Deserialize)]
+#[derive(Clone, Serialize)]
 pub struct JsonEntity {
     pub id: Id,
     pub model: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<Style>,
+    /* Schema version the json was written with, entities of this build have the current one. */
+    pub version: u32,
 }
 
-/* Try to parse json string. */
+/* Entity json in the layout of the current schema version. */
+#[derive(Deserialize)]
+struct UpgradedJsonEntity {
+    id: Id,
+    model: serde_json::Value,
+    #[serde(default)]
+    style: Option<Style>,
+    version: u32,
+}
+
+/*
+ * Entities are upgraded however they are read, as a part of a board, a container, a patch or a remote update.
+ * Entity json without version was saved before versioning.
+ */
+impl<'de> Deserialize<'de> for JsonEntity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let entity: serde_json::Value = serde_json::Value::deserialize(deserializer)?;
+        let entity: UpgradedJsonEntity = schema::upgrade_entity(entity)
+            .and_then(serde_json::from_value)
+            .map_err(D::Error::custom)?;
+
+        Ok(JsonEntity {
+            id: entity.id,
+            model: entity.model,
+            style: entity.style,
+            version: entity.version,
+        })
+    }
+}
+
+impl JsonEntity {
+    /* Same entity with another model. */
+    pub fn with_model(&self, model: serde_json::Value) -> JsonEntity {
+        JsonEntity {
+            id: self.id.clone(),
+            model,
+            style: self.style.clone(),
+            version: self.version,
+        }
+    }
+}
+
+/* Try to parse json string, upgrading entities saved with an earlier schema version. */
 impl TryFrom<&str> for JsonEntity {
     type Error = serde_json::Error;
 
@@ -18,6 +68,22 @@ impl TryFrom<&str> for JsonEntity {
     }
 }
 
+/* Try to serialize entity model into json value. */
+impl TryFrom<&Entity<Id>> for JsonEntity {
+    type Error = serde_json::Error;
+
+    fn try_from(entity: &Entity<Id>) -> Result<Self, Self::Error> {
+        Ok(
+            JsonEntity {
+                id: entity.id().clone(),
+                model: serde_json::to_value(entity.model().as_serialize())?,
+                style: entity.query::<EntityStyle>().map(|entity_style| entity_style.style.clone()),
+                version: schema::VERSION,
+            }
+        )
+    }
+}
+
 /* Try to parse json string. */
 impl TryInto<Entity<Id>> for JsonEntity {
     type Error = serde_json::Error;
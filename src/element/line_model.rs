@@ -0,0 +1,80 @@
+use crate::element::id::Id;
+use crate::element::{board_feature_set, model_of};
+use crate::renderer::board_renderer::BoardRenderer;
+use entity_model_feature::entity::Entity;
+use entity_model_feature::feature_set::FeatureSet;
+use entity_model_feature::{AsSerialize, Model};
+use geometry::figure::path::command::line_to::LineTo;
+use geometry::figure::path::command::move_to::MoveTo;
+use geometry::figure::path::command::Command;
+use geometry::figure::path::Path;
+use geometry::point::point_2d::Point2D;
+use serde::{Deserialize, Serialize};
+use standard_entity_plugin::entity_model::{DefaultEntity, StandardFeatureSet};
+use standard_rendering_plugin::style::shape_style::ShapeStyle;
+
+/* Straight line between two free points. Unlike connectors it is never bound to other entities. */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LineModel {
+    pub x1: f64,
+    pub y1: f64,
+    pub x2: f64,
+    pub y2: f64,
+}
+
+impl LineModel {
+    pub fn start(&self) -> Point2D {
+        Point2D::new(self.x1, self.y1)
+    }
+
+    pub fn end(&self) -> Point2D {
+        Point2D::new(self.x2, self.y2)
+    }
+
+    /* Drawn as a path, svg renderer styles segments as dashed guides. */
+    fn path(&self) -> Path {
+        Path::new(vec![
+            Command::MoveTo(MoveTo { to_point: self.start() }),
+            Command::LineTo(LineTo { to_point: self.end() }),
+        ])
+    }
+
+    fn render(entity: &Entity<Id>, renderer: &mut dyn BoardRenderer) {
+        let Some(model) = model_of::<LineModel>(entity) else {
+            return;
+        };
+
+        renderer.path(&model.path(), &ShapeStyle::default(), None);
+    }
+}
+
+impl Default for LineModel {
+    fn default() -> Self {
+        Self {
+            x1: 0.0,
+            y1: 0.0,
+            x2: 100.0,
+            y2: 0.0,
+        }
+    }
+}
+
+impl AsSerialize for LineModel {
+    fn as_serialize(&self) -> &dyn dyn_serde::ser::Serialize {
+        self
+    }
+}
+
+impl Model for LineModel {}
+
+impl DefaultEntity<Id> for LineModel {
+    fn default_entity(id: Id) -> Entity<Id> {
+        Entity::new(id, LineModel::default(), FeatureSet::empty())
+    }
+}
+
+impl StandardFeatureSet<Id> for LineModel {
+    fn standard_feature_set() -> FeatureSet<Id> {
+        board_feature_set(LineModel::render)
+    }
+}
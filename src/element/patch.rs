@@ -0,0 +1,255 @@
+use crate::element::id::Id;
+use crate::element::json_entity::JsonEntity;
+use crate::element::transform::{self, Transform};
+use crate::element::{Build, Result};
+use crate::text::measure::TextMeasure;
+use entity_model_feature::entity::Entity;
+use serde::de::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+const MODEL_FIELD: &str = "model";
+const STYLE_FIELD: &str = "style";
+
+/* Step of a path into the entity json, a field of an object or an element of an array. */
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum Key {
+    Field(String),
+    Index(usize),
+}
+
+/* Paths start at the model or the style of the entity, e.g. ["model", "lines", 2, "text"]. */
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Operation {
+    /* Replace the value, or add the field. */
+    Set {
+        path: Vec<Key>,
+        value: Value,
+    },
+    Remove {
+        path: Vec<Key>,
+    },
+    /* Replace `delete` elements of the array from the index with the inserted ones. */
+    Splice {
+        path: Vec<Key>,
+        index: usize,
+        delete: usize,
+        insert: Vec<Value>,
+    },
+    /* Map the geometry of the whole entity, like a move or resize. */
+    Transform {
+        transform: Transform,
+    },
+}
+
+/* Change of one entity as a list of operations, applied in order. Much smaller than the model when little has changed. */
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(transparent)]
+pub struct Patch {
+    operations: Vec<Operation>,
+}
+
+impl Patch {
+    /* Operations turning the model and style of one entity into the other ones. Ids are not compared. */
+    pub fn diff(before: &JsonEntity, after: &JsonEntity) -> Patch {
+        let mut operations: Vec<Operation> = vec![];
+        let mut path: Vec<Key> = vec![Key::Field(MODEL_FIELD.to_string())];
+        diff_value(&mut path, &before.model, &after.model, &mut operations);
+
+        if before.style != after.style {
+            operations.push(Operation::Set {
+                path: vec![Key::Field(STYLE_FIELD.to_string())],
+                value: json!(after.style),
+            });
+        }
+
+        Patch { operations }
+    }
+
+    pub fn transform(transform: Transform) -> Patch {
+        Patch {
+            operations: vec![Operation::Transform { transform }],
+        }
+    }
+
+    /*
+     * Patch undoing this one without keeping the models, for patches that only move the entity.
+     * None otherwise, resizing back may not restore details like the aspect ratio of images or wrapped text.
+     */
+    pub fn inverse(&self) -> Option<Patch> {
+        let operations: Option<Vec<Operation>> = self.operations
+            .iter()
+            .rev()
+            .map(|operation| match operation {
+                Operation::Transform { transform } if transform.is_move() =>
+                    transform.inverse().map(|transform| Operation::Transform { transform }),
+                _ => None,
+            })
+            .collect();
+
+        operations.filter(|operations| !operations.is_empty()).map(|operations| Patch { operations })
+    }
+
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /* Patched copy of the entity json. Nothing is changed if any operation doesn't fit the entity. */
+    pub fn apply(&self, json_entity: &JsonEntity, measure: &dyn TextMeasure) -> Result<JsonEntity> {
+        let mut json_entity: JsonEntity = json_entity.clone();
+
+        for operation in &self.operations {
+            json_entity = match operation {
+                Operation::Transform { transform } => transform::transformed(&json_entity, transform, measure)?,
+                operation => {
+                    let mut parts: Value = Value::Object(Map::from_iter([
+                        (MODEL_FIELD.to_string(), json_entity.model.take()),
+                        (STYLE_FIELD.to_string(), serde_json::to_value(json_entity.style.take())?),
+                    ]));
+                    apply_to_value(operation, &mut parts)?;
+
+                    json_entity.model = parts[MODEL_FIELD].take();
+                    json_entity.style = serde_json::from_value(parts[STYLE_FIELD].take())?;
+
+                    json_entity
+                }
+            };
+        }
+
+        Ok(json_entity)
+    }
+
+    pub fn apply_to_entity(&self, entity: &Entity<Id>, measure: &dyn TextMeasure) -> Result<Entity<Id>> {
+        Build::from_json(self.apply(&JsonEntity::try_from(entity)?, measure)?)
+    }
+}
+
+fn diff_value(path: &mut Vec<Key>, before: &Value, after: &Value, operations: &mut Vec<Operation>) {
+    if before == after {
+        return;
+    }
+
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            for field in before.keys().filter(|field| !after.contains_key(*field)) {
+                operations.push(Operation::Remove { path: with(path, Key::Field(field.clone())) });
+            }
+
+            for (field, value) in after {
+                path.push(Key::Field(field.clone()));
+                match before.get(field) {
+                    Some(before) => diff_value(path, before, value, operations),
+                    None => operations.push(Operation::Set { path: path.clone(), value: value.clone() }),
+                }
+                path.pop();
+            }
+        }
+        (Value::Array(before), Value::Array(after)) => diff_array(path, before, after, operations),
+        _ => operations.push(Operation::Set { path: path.clone(), value: after.clone() }),
+    }
+}
+
+/* Equal ends are skipped. The rest is changed in place if it kept its length, like moved points, or spliced otherwise. */
+fn diff_array(path: &mut Vec<Key>, before: &[Value], after: &[Value], operations: &mut Vec<Operation>) {
+    let prefix: usize = before.iter().zip(after).take_while(|(before, after)| before == after).count();
+    let suffix: usize = before[prefix..]
+        .iter()
+        .rev()
+        .zip(after[prefix..].iter().rev())
+        .take_while(|(before, after)| before == after)
+        .count();
+
+    let removed: &[Value] = &before[prefix..before.len() - suffix];
+    let inserted: &[Value] = &after[prefix..after.len() - suffix];
+
+    if removed.len() != inserted.len() {
+        operations.push(Operation::Splice {
+            path: path.clone(),
+            index: prefix,
+            delete: removed.len(),
+            insert: inserted.to_vec(),
+        });
+
+        return;
+    }
+
+    for (offset, (before, after)) in removed.iter().zip(inserted).enumerate() {
+        path.push(Key::Index(prefix + offset));
+        diff_value(path, before, after, operations);
+        path.pop();
+    }
+}
+
+fn with(path: &[Key], key: Key) -> Vec<Key> {
+    let mut path: Vec<Key> = path.to_vec();
+    path.push(key);
+
+    path
+}
+
+fn apply_to_value(operation: &Operation, parts: &mut Value) -> Result<()> {
+    match operation {
+        Operation::Set { path, value } => {
+            let (parent, key) = parent(parts, path)?;
+
+            match key {
+                Key::Field(field) => {
+                    parent
+                        .as_object_mut()
+                        .ok_or_else(|| path_error(path))?
+                        .insert(field.clone(), value.clone());
+                }
+                Key::Index(index) => *parent.get_mut(*index).ok_or_else(|| path_error(path))? = value.clone(),
+            }
+        }
+        Operation::Remove { path } => {
+            let (parent, Key::Field(field)) = parent(parts, path)? else {
+                return Err(path_error(path));
+            };
+
+            parent.as_object_mut().ok_or_else(|| path_error(path))?.remove(field);
+        }
+        Operation::Splice { path, index, delete, insert } => {
+            let array: &mut Vec<Value> = target(parts, path)?.as_array_mut().ok_or_else(|| path_error(path))?;
+
+            let end: usize = index.checked_add(*delete).filter(|end| *end <= array.len()).ok_or_else(|| path_error(path))?;
+            array.splice(*index..end, insert.iter().cloned());
+        }
+        Operation::Transform { .. } => {}
+    }
+
+    Ok(())
+}
+
+/* Value holding the last key of the path. Only the model and the style can be changed. */
+fn parent<'a>(parts: &'a mut Value, path: &'a [Key]) -> Result<(&'a mut Value, &'a Key)> {
+    let Some((key, parent_path)) = path.split_last() else {
+        return Err(path_error(path));
+    };
+
+    if parent_path.is_empty() && !matches!(key, Key::Field(field) if field == MODEL_FIELD || field == STYLE_FIELD) {
+        return Err(path_error(path));
+    }
+
+    Ok((target(parts, parent_path)?, key))
+}
+
+fn target<'a>(value: &'a mut Value, path: &[Key]) -> Result<&'a mut Value> {
+    path.iter().try_fold(value, |value, key| {
+        match key {
+            Key::Field(field) => value.get_mut(field.as_str()),
+            Key::Index(index) => value.get_mut(*index),
+        }
+        .ok_or_else(|| path_error(path))
+    })
+}
+
+fn path_error(path: &[Key]) -> serde_json::Error {
+    serde_json::Error::custom(format!("Patch path {} doesn't fit the entity", json!(path)))
+}
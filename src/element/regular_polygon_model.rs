@@ -0,0 +1,95 @@
+use crate::element::id::Id;
+use crate::element::{board_feature_set, model_of};
+use crate::renderer::board_renderer::BoardRenderer;
+use entity_model_feature::entity::Entity;
+use entity_model_feature::feature_set::FeatureSet;
+use entity_model_feature::{AsSerialize, Model};
+use geometry::figure::polygon::Polygon;
+use geometry::point::point_2d::Point2D;
+use serde::{Deserialize, Serialize};
+use standard_entity_plugin::entity_model::{DefaultEntity, StandardFeatureSet};
+use standard_rendering_plugin::style::shape_style::ShapeStyle;
+
+pub const MIN_SIDES: u32 = 3;
+
+/*
+ * Regular polygon or star inscribed into the box, first vertex points up.
+ * Star has two vertices per side, inner ones at the inner radius given as a part of the outer radius.
+ */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RegularPolygonModel {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub sides: u32,
+    #[serde(default)]
+    pub inner_radius: Option<f64>,
+}
+
+impl RegularPolygonModel {
+    pub fn vertices(&self) -> Vec<Point2D> {
+        let sides: u32 = self.sides.max(MIN_SIDES);
+        let (center_x, center_y) = (self.x + self.width / 2.0, self.y + self.height / 2.0);
+        let (radius_x, radius_y) = (self.width.abs() / 2.0, self.height.abs() / 2.0);
+
+        let count: u32 = match self.inner_radius {
+            Some(_) => sides * 2,
+            None => sides,
+        };
+        let step: f64 = std::f64::consts::TAU / count as f64;
+
+        (0..count)
+            .map(|index| {
+                let scale: f64 = match self.inner_radius {
+                    Some(inner_radius) if index % 2 == 1 => inner_radius.clamp(0.0, 1.0),
+                    _ => 1.0,
+                };
+                let angle: f64 = index as f64 * step - std::f64::consts::FRAC_PI_2;
+
+                Point2D::new(center_x + radius_x * scale * angle.cos(), center_y + radius_y * scale * angle.sin())
+            })
+            .collect()
+    }
+
+    fn render(entity: &Entity<Id>, renderer: &mut dyn BoardRenderer) {
+        let Some(model) = model_of::<RegularPolygonModel>(entity) else {
+            return;
+        };
+
+        renderer.polygon_2d(&Polygon::new(model.vertices()), &ShapeStyle::default(), None);
+    }
+}
+
+impl Default for RegularPolygonModel {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: 100.0,
+            height: 100.0,
+            sides: 5,
+            inner_radius: None,
+        }
+    }
+}
+
+impl AsSerialize for RegularPolygonModel {
+    fn as_serialize(&self) -> &dyn dyn_serde::ser::Serialize {
+        self
+    }
+}
+
+impl Model for RegularPolygonModel {}
+
+impl DefaultEntity<Id> for RegularPolygonModel {
+    fn default_entity(id: Id) -> Entity<Id> {
+        Entity::new(id, RegularPolygonModel::default(), FeatureSet::empty())
+    }
+}
+
+impl StandardFeatureSet<Id> for RegularPolygonModel {
+    fn standard_feature_set() -> FeatureSet<Id> {
+        board_feature_set(RegularPolygonModel::render)
+    }
+}
@@ -0,0 +1,139 @@
+use crate::bounding_box::BoundingBox;
+use crate::element::id::Id;
+use crate::element::{board_feature_set, model_of};
+use crate::renderer::board_renderer::BoardRenderer;
+use crate::text::font::Font;
+use crate::text::layout::{self, TextLine};
+use crate::text::measure::TextMeasure;
+use entity_model_feature::entity::Entity;
+use entity_model_feature::feature_set::FeatureSet;
+use entity_model_feature::{AsSerialize, Model};
+use serde::{Deserialize, Serialize};
+use standard_entity_plugin::entity_model::{DefaultEntity, StandardFeatureSet};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+const DEFAULT_SIZE: f64 = 200.0;
+const PADDING: f64 = 16.0;
+const SHADOW_OFFSET: f64 = 4.0;
+const SHADOW_COLOR: &str = "rgba(0, 0, 0, 0.15)";
+const TEXT_COLOR: &str = "#1f1f1f";
+const MAX_FONT_SIZE: f64 = 32.0;
+const MIN_FONT_SIZE: f64 = 8.0;
+
+#[derive(Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub enum StickyNoteColor {
+    Yellow,
+    Orange,
+    Pink,
+    Purple,
+    Blue,
+    Green,
+}
+
+impl StickyNoteColor {
+    pub fn hex(&self) -> &'static str {
+        match self {
+            StickyNoteColor::Yellow => "#fff59d",
+            StickyNoteColor::Orange => "#ffcc80",
+            StickyNoteColor::Pink => "#f8bbd0",
+            StickyNoteColor::Purple => "#d1c4e9",
+            StickyNoteColor::Blue => "#b3e5fc",
+            StickyNoteColor::Green => "#c5e1a5",
+        }
+    }
+}
+
+/* Coloured square with text. Font shrinks until the wrapped text fits inside the padding. */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StickyNoteModel {
+    pub x: f64,
+    pub y: f64,
+    pub size: f64,
+    pub color: StickyNoteColor,
+    pub text: String,
+    /* Font size fitted to the text. */
+    pub font: Font,
+    #[serde(default)]
+    pub lines: Vec<TextLine>,
+}
+
+impl StickyNoteModel {
+    pub fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::new(self.x, self.y, self.size, self.size)
+    }
+
+    /* Lay the text out with the largest font that fits, lines are centered in the note. */
+    pub fn fit(&mut self, measure: &dyn TextMeasure) {
+        let content_size: f64 = (self.size - PADDING * 2.0).max(0.0);
+        let (content_x, content_y) = (self.x + PADDING, self.y + PADDING);
+
+        let mut font: Font = Font { size: MAX_FONT_SIZE, ..self.font.clone() };
+        let mut lines: Vec<TextLine> = layout::layout(&self.text, &font, content_x, content_y, content_size, measure);
+
+        while font.size > MIN_FONT_SIZE && lines.len() as f64 * font.line_height() > content_size {
+            font.size -= 1.0;
+            lines = layout::layout(&self.text, &font, content_x, content_y, content_size, measure);
+        }
+
+        let vertical_offset: f64 = ((content_size - lines.len() as f64 * font.line_height()) / 2.0).max(0.0);
+        for line in &mut lines {
+            line.x += ((content_size - line.width) / 2.0).max(0.0);
+            line.y += vertical_offset;
+        }
+
+        self.font = font;
+        self.lines = lines;
+    }
+
+    fn render(entity: &Entity<Id>, renderer: &mut dyn BoardRenderer) {
+        let Some(model) = model_of::<StickyNoteModel>(entity) else {
+            return;
+        };
+
+        let note: BoundingBox = model.bounding_box();
+        let shadow: BoundingBox = BoundingBox::new(note.left + SHADOW_OFFSET, note.top + SHADOW_OFFSET, note.width(), note.height());
+
+        renderer.fill_rectangle(&shadow, SHADOW_COLOR);
+        renderer.fill_rectangle(&note, model.color.hex());
+
+        for line in &model.lines {
+            renderer.text(line, &model.font, TEXT_COLOR);
+        }
+    }
+}
+
+impl Default for StickyNoteModel {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            size: DEFAULT_SIZE,
+            color: StickyNoteColor::Yellow,
+            text: String::new(),
+            font: Font { size: MAX_FONT_SIZE, ..Font::default() },
+            lines: vec![],
+        }
+    }
+}
+
+impl AsSerialize for StickyNoteModel {
+    fn as_serialize(&self) -> &dyn dyn_serde::ser::Serialize {
+        self
+    }
+}
+
+impl Model for StickyNoteModel {}
+
+impl DefaultEntity<Id> for StickyNoteModel {
+    fn default_entity(id: Id) -> Entity<Id> {
+        Entity::new(id, StickyNoteModel::default(), FeatureSet::empty())
+    }
+}
+
+impl StandardFeatureSet<Id> for StickyNoteModel {
+    fn standard_feature_set() -> FeatureSet<Id> {
+        board_feature_set(StickyNoteModel::render)
+    }
+}
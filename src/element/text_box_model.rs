@@ -0,0 +1,88 @@
+use crate::element::id::Id;
+use crate::element::{board_feature_set, model_of};
+use crate::renderer::board_renderer::BoardRenderer;
+use crate::text::font::Font;
+use crate::text::layout::{self, TextLine};
+use crate::text::measure::TextMeasure;
+use entity_model_feature::entity::Entity;
+use entity_model_feature::feature_set::FeatureSet;
+use entity_model_feature::{AsSerialize, Model};
+use serde::{Deserialize, Serialize};
+use standard_entity_plugin::entity_model::{DefaultEntity, StandardFeatureSet};
+
+const DEFAULT_WIDTH: f64 = 200.0;
+
+/* Text wrapped into a box of fixed width. Height follows the number of lines. */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TextBoxModel {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub text: String,
+    #[serde(default)]
+    pub font: Font,
+    #[serde(default = "default_color")]
+    pub color: String,
+    /* Layout is stored with the text, so every renderer and every peer draws the same lines. */
+    #[serde(default)]
+    pub lines: Vec<TextLine>,
+}
+
+fn default_color() -> String {
+    "#000000".to_string()
+}
+
+impl TextBoxModel {
+    pub fn relayout(&mut self, measure: &dyn TextMeasure) {
+        self.lines = layout::layout(&self.text, &self.font, self.x, self.y, self.width, measure);
+        self.height = self.lines.len().max(1) as f64 * self.font.line_height();
+    }
+
+    fn render(entity: &Entity<Id>, renderer: &mut dyn BoardRenderer) {
+        let Some(model) = model_of::<TextBoxModel>(entity) else {
+            return;
+        };
+
+        for line in &model.lines {
+            renderer.text(line, &model.font, &model.color);
+        }
+    }
+}
+
+impl Default for TextBoxModel {
+    fn default() -> Self {
+        let font: Font = Font::default();
+
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: DEFAULT_WIDTH,
+            height: font.line_height(),
+            text: String::new(),
+            font,
+            color: default_color(),
+            lines: vec![],
+        }
+    }
+}
+
+impl AsSerialize for TextBoxModel {
+    fn as_serialize(&self) -> &dyn dyn_serde::ser::Serialize {
+        self
+    }
+}
+
+impl Model for TextBoxModel {}
+
+impl DefaultEntity<Id> for TextBoxModel {
+    fn default_entity(id: Id) -> Entity<Id> {
+        Entity::new(id, TextBoxModel::default(), FeatureSet::empty())
+    }
+}
+
+impl StandardFeatureSet<Id> for TextBoxModel {
+    fn standard_feature_set() -> FeatureSet<Id> {
+        board_feature_set(TextBoxModel::render)
+    }
+}
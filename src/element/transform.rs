@@ -0,0 +1,211 @@
+use crate::bounding_box::BoundingBox;
+use crate::element::connector_model::{ConnectorModel, Endpoint};
+use crate::element::ellipse_model::EllipseModel;
+use crate::element::frame_model::FrameModel;
+use crate::element::image_model::ImageModel;
+use crate::element::json_entity::JsonEntity;
+use crate::element::line_model::LineModel;
+use crate::element::regular_polygon_model::RegularPolygonModel;
+use crate::element::sticky_note_model::StickyNoteModel;
+use crate::element::text_box_model::TextBoxModel;
+use crate::element::{ElementType, Result};
+use crate::text::measure::TextMeasure;
+use geometry::figure::path::command::bezier_to::BezierTo;
+use geometry::figure::path::command::line_to::LineTo;
+use geometry::figure::path::command::move_to::MoveTo;
+use geometry::figure::path::command::Command;
+use geometry::figure::path::Path;
+use geometry::figure::polygon::Polygon;
+use geometry::point::point_2d::Point2D;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use standard_entity_plugin::model::path_model::PathModel;
+use standard_entity_plugin::model::polygon_model::PolygonModel;
+use standard_entity_plugin::model::rectangle_model::RectangleModel;
+
+/* Scale followed by translation, maps one bounding box onto another. */
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Transform {
+    pub scale_x: f64,
+    pub scale_y: f64,
+    pub translate_x: f64,
+    pub translate_y: f64,
+}
+
+impl Transform {
+    pub fn between(from: &BoundingBox, to: &BoundingBox) -> Self {
+        let scale = |to: f64, from: f64| if from == 0.0 { 1.0 } else { to / from };
+
+        let scale_x: f64 = scale(to.width(), from.width());
+        let scale_y: f64 = scale(to.height(), from.height());
+
+        Self {
+            scale_x,
+            scale_y,
+            translate_x: to.left - from.left * scale_x,
+            translate_y: to.top - from.top * scale_y,
+        }
+    }
+
+    /* Transform mapping back, None if it collapses the entity to a line or a point. */
+    pub fn inverse(&self) -> Option<Self> {
+        if self.scale_x == 0.0 || self.scale_y == 0.0 {
+            return None;
+        }
+
+        Some(Self {
+            scale_x: 1.0 / self.scale_x,
+            scale_y: 1.0 / self.scale_y,
+            translate_x: -self.translate_x / self.scale_x,
+            translate_y: -self.translate_y / self.scale_y,
+        })
+    }
+
+    pub fn is_move(&self) -> bool {
+        self.scale_x == 1.0 && self.scale_y == 1.0
+    }
+
+    pub fn point(&self, point: &Point2D) -> Point2D {
+        let (x, y) = self.xy(point.x, point.y);
+
+        Point2D::new(x, y)
+    }
+
+    pub fn xy(&self, x: f64, y: f64) -> (f64, f64) {
+        (x * self.scale_x + self.translate_x, y * self.scale_y + self.translate_y)
+    }
+
+    /* Map box given by its top left corner and size. */
+    pub fn rect(&self, x: f64, y: f64, width: f64, height: f64) -> (f64, f64, f64, f64) {
+        let (x, y) = self.xy(x, y);
+
+        (x, y, width * self.scale_x, height * self.scale_y)
+    }
+
+    fn path(&self, path: &Path) -> Path {
+        let commands: Vec<Command> = path
+            .commands()
+            .iter()
+            .map(|command| match command {
+                Command::MoveTo(move_to) =>
+                    Command::MoveTo(MoveTo { to_point: self.point(&move_to.to_point) }),
+                Command::LineTo(line_to) =>
+                    Command::LineTo(LineTo { to_point: self.point(&line_to.to_point) }),
+                Command::BezierTo(bezier_to) =>
+                    Command::BezierTo(BezierTo {
+                        control_point_1: self.point(&bezier_to.control_point_1),
+                        control_point_2: self.point(&bezier_to.control_point_2),
+                        to_point: self.point(&bezier_to.to_point),
+                    }),
+                /* Arc radii keep their size, only the end point is moved. */
+                Command::ArcTo(arc_to) => {
+                    let mut arc_to = arc_to.clone();
+                    arc_to.to_point = self.point(&arc_to.to_point);
+
+                    Command::ArcTo(arc_to)
+                }
+                /* Not produced by whiteboard tools. */
+                command => command.clone(),
+            })
+            .collect();
+
+        Path::new(commands)
+    }
+}
+
+/*
+ * Copy of the entity with its geometry mapped by the transform.
+ * Text is laid out again when its box changes width, bound connector ends are left to the view port.
+ */
+pub fn transformed(json_entity: &JsonEntity, transform: &Transform, measure: &dyn TextMeasure) -> Result<JsonEntity> {
+    let model: serde_json::Value = match json_entity.id.element_type() {
+        ElementType::Rectangle => map_model(json_entity, |model: &mut RectangleModel| {
+            let rectangle = &mut model.rectangle;
+            let top_left: Point2D = transform.point(&rectangle.top_left);
+
+            rectangle.top_left = top_left;
+            rectangle.width *= transform.scale_x;
+            rectangle.height *= transform.scale_y;
+        })?,
+        ElementType::Polygon => map_model(json_entity, |model: &mut PolygonModel| {
+            let vertices: Vec<Point2D> = model.polygon.vertices().iter().map(|vertex| transform.point(vertex)).collect();
+            model.polygon = Polygon::new(vertices);
+        })?,
+        ElementType::FreeHand => map_model(json_entity, |model: &mut PathModel| {
+            model.path = transform.path(&model.path);
+        })?,
+        ElementType::Text => map_model(json_entity, |model: &mut TextBoxModel| {
+            let width: f64 = model.width;
+            (model.x, model.y, model.width, _) = transform.rect(model.x, model.y, model.width, model.height);
+
+            if model.width != width {
+                model.relayout(measure);
+            } else {
+                for line in &mut model.lines {
+                    (line.x, line.y) = transform.xy(line.x, line.y);
+                }
+            }
+        })?,
+        ElementType::Container => {
+            let mut model: serde_json::Value = json_entity.model.clone();
+
+            if let Some(children) = model.get_mut("children").and_then(serde_json::Value::as_array_mut) {
+                for child in children.iter_mut() {
+                    let child_entity: JsonEntity = serde_json::from_value(child.clone())?;
+                    *child = serde_json::to_value(transformed(&child_entity, transform, measure)?)?;
+                }
+            }
+
+            model
+        }
+        ElementType::Connector => map_model(json_entity, |model: &mut ConnectorModel| {
+            for endpoint in [&mut model.start, &mut model.end] {
+                if let Endpoint::Free { x, y } = endpoint {
+                    (*x, *y) = transform.xy(*x, *y);
+                }
+            }
+
+            for point in &mut model.route {
+                (point[0], point[1]) = transform.xy(point[0], point[1]);
+            }
+        })?,
+        ElementType::Ellipse => map_model(json_entity, |model: &mut EllipseModel| {
+            (model.x, model.y, model.width, model.height) = transform.rect(model.x, model.y, model.width, model.height);
+        })?,
+        ElementType::Line => map_model(json_entity, |model: &mut LineModel| {
+            (model.x1, model.y1) = transform.xy(model.x1, model.y1);
+            (model.x2, model.y2) = transform.xy(model.x2, model.y2);
+        })?,
+        ElementType::RegularPolygon => map_model(json_entity, |model: &mut RegularPolygonModel| {
+            (model.x, model.y, model.width, model.height) = transform.rect(model.x, model.y, model.width, model.height);
+        })?,
+        /* Images keep their aspect ratio and notes stay square, both fit into the mapped box. */
+        ElementType::Image => map_model(json_entity, |model: &mut ImageModel| {
+            let (x, y, width, height) = transform.rect(model.x, model.y, model.width, model.height);
+
+            (model.x, model.y) = (x, y);
+            model.resize(width, height);
+        })?,
+        ElementType::StickyNote => map_model(json_entity, |model: &mut StickyNoteModel| {
+            let (x, y, width, height) = transform.rect(model.x, model.y, model.size, model.size);
+
+            (model.x, model.y, model.size) = (x, y, width.min(height));
+            model.fit(measure);
+        })?,
+        ElementType::Frame => map_model(json_entity, |model: &mut FrameModel| {
+            let (x, y, width, height) = transform.rect(model.x, model.y, model.width, model.height);
+
+            (model.x, model.y) = (x, y);
+            model.resize(width, height);
+        })?,
+    };
+
+    Ok(json_entity.with_model(model))
+}
+
+fn map_model<M: Serialize + DeserializeOwned>(json_entity: &JsonEntity, map: impl FnOnce(&mut M)) -> Result<serde_json::Value> {
+    let mut model: M = serde_json::from_value(json_entity.model.clone())?;
+    map(&mut model);
+
+    serde_json::to_value(&model)
+}
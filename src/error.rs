@@ -0,0 +1,28 @@
+use std::fmt::{Display, Formatter};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::{JsError, JsValue};
+
+/* Call refused by the whiteboard, like a change the board mode doesn't allow. Thrown as Error in the browser. */
+#[derive(Debug)]
+pub struct BoardError(String);
+
+impl BoardError {
+    pub fn new(message: &str) -> Self {
+        Self(message.to_string())
+    }
+}
+
+impl Display for BoardError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for BoardError {}
+
+#[cfg(feature = "wasm")]
+impl From<BoardError> for JsValue {
+    fn from(error: BoardError) -> Self {
+        JsError::new(&error.0).into()
+    }
+}
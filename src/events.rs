@@ -0,0 +1,196 @@
+use crate::bounding_box::BoundingBox;
+use crate::element::id::Id;
+use crate::element::json_entity::JsonEntity;
+use crate::element::ElementType;
+use crate::platform;
+use serde_json::json;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::JsValue;
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub enum EventKind {
+    EntityAdded,
+    EntityModified,
+    EntityRemoved,
+    SelectionChanged,
+    ToolChanged,
+    CameraChanged,
+    HistoryChanged,
+}
+
+impl EventKind {
+    /* Event names used by the host. */
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "entity_added" => Some(Self::EntityAdded),
+            "entity_modified" => Some(Self::EntityModified),
+            "entity_removed" => Some(Self::EntityRemoved),
+            "selection_changed" => Some(Self::SelectionChanged),
+            "tool_changed" => Some(Self::ToolChanged),
+            "camera_changed" => Some(Self::CameraChanged),
+            "history_changed" => Some(Self::HistoryChanged),
+            _ => None,
+        }
+    }
+}
+
+pub enum BoardEvent {
+    EntityAdded(JsonEntity),
+    EntityModified(JsonEntity),
+    /* Entity as it was before removal. */
+    EntityRemoved(JsonEntity),
+    SelectionChanged(Vec<Id>),
+    ToolChanged { tool: &'static str, element_type: Option<ElementType> },
+    CameraChanged(BoundingBox),
+    HistoryChanged { can_undo: bool, can_redo: bool },
+}
+
+impl BoardEvent {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            BoardEvent::EntityAdded(_) => EventKind::EntityAdded,
+            BoardEvent::EntityModified(_) => EventKind::EntityModified,
+            BoardEvent::EntityRemoved(_) => EventKind::EntityRemoved,
+            BoardEvent::SelectionChanged(_) => EventKind::SelectionChanged,
+            BoardEvent::ToolChanged { .. } => EventKind::ToolChanged,
+            BoardEvent::CameraChanged(_) => EventKind::CameraChanged,
+            BoardEvent::HistoryChanged { .. } => EventKind::HistoryChanged,
+        }
+    }
+
+    /* Entity events carry the entity in its json form, the same one the history and serialization use. */
+    pub fn payload(&self) -> serde_json::Result<serde_json::Value> {
+        match self {
+            BoardEvent::EntityAdded(json_entity)
+            | BoardEvent::EntityModified(json_entity)
+            | BoardEvent::EntityRemoved(json_entity) => serde_json::to_value(json_entity),
+            BoardEvent::SelectionChanged(ids) => Ok(json!({ "ids": ids })),
+            BoardEvent::ToolChanged { tool, element_type } => Ok(json!({ "tool": tool, "element_type": element_type })),
+            BoardEvent::CameraChanged(view_box) => Ok(json!({
+                "x": view_box.left,
+                "y": view_box.top,
+                "width": view_box.width(),
+                "height": view_box.height(),
+            })),
+            BoardEvent::HistoryChanged { can_undo, can_redo } => Ok(json!({ "can_undo": can_undo, "can_redo": can_redo })),
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Listener {
+    #[cfg(feature = "wasm")]
+    Host(js_sys::Function),
+    Native(Rc<dyn Fn(&BoardEvent)>),
+}
+
+#[derive(Default)]
+struct Listeners {
+    listeners: HashMap<EventKind, Vec<Listener>>,
+    queue: Vec<BoardEvent>,
+    flush_scheduled: bool,
+}
+
+/*
+ * Callbacks for board changes, shared by the view port, history and whiteboard.
+ * Events are queued and delivered after the current call returns,
+ * so callbacks can call back into the whiteboard.
+ */
+#[derive(Clone, Default)]
+pub struct Events {
+    listeners: Rc<RefCell<Listeners>>,
+}
+
+impl Events {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg(feature = "wasm")]
+    pub fn on(&self, kind: EventKind, callback: js_sys::Function) {
+        self.add_listener(kind, Listener::Host(callback));
+    }
+
+    /* Listener inside the crate. */
+    pub fn subscribe(&self, kind: EventKind, callback: impl Fn(&BoardEvent) + 'static) {
+        self.add_listener(kind, Listener::Native(Rc::new(callback)));
+    }
+
+    #[cfg(feature = "wasm")]
+    pub fn off(&self, kind: EventKind, callback: &js_sys::Function) {
+        if let Some(listeners) = self.listeners.borrow_mut().listeners.get_mut(&kind) {
+            listeners.retain(|listener| !matches!(listener, Listener::Host(host) if host == callback));
+        }
+    }
+
+    /* Lets emitters skip building payloads nobody listens to. */
+    pub fn listens(&self, kind: EventKind) -> bool {
+        self.listeners
+            .borrow()
+            .listeners
+            .get(&kind)
+            .is_some_and(|listeners| !listeners.is_empty())
+    }
+
+    pub fn emit(&self, event: BoardEvent) {
+        if !self.listens(event.kind()) {
+            return;
+        }
+
+        let mut listeners = self.listeners.borrow_mut();
+        listeners.queue.push(event);
+
+        if listeners.flush_scheduled {
+            return;
+        }
+        listeners.flush_scheduled = true;
+        drop(listeners);
+
+        let events: Events = self.clone();
+        platform::spawn_local(async move { events.flush() });
+    }
+
+    fn add_listener(&self, kind: EventKind, listener: Listener) {
+        self.listeners.borrow_mut().listeners.entry(kind).or_default().push(listener);
+    }
+
+    fn flush(&self) {
+        let mut listeners = self.listeners.borrow_mut();
+
+        let queue: Vec<BoardEvent> = std::mem::take(&mut listeners.queue);
+        listeners.flush_scheduled = false;
+        let snapshot: HashMap<EventKind, Vec<Listener>> = listeners.listeners.clone();
+        drop(listeners);
+
+        for event in queue {
+            let Some(listeners) = snapshot.get(&event.kind()) else {
+                continue;
+            };
+
+            /* Payload is built once and only if the host listens. */
+            #[cfg(feature = "wasm")]
+            let mut payload: Option<JsValue> = None;
+
+            for listener in listeners {
+                match listener {
+                    Listener::Native(callback) => callback(&event),
+                    #[cfg(feature = "wasm")]
+                    Listener::Host(callback) => {
+                        if payload.is_none() {
+                            payload = event.payload()
+                                .ok()
+                                .and_then(|payload| js_sys::JSON::parse(&payload.to_string()).ok());
+                        }
+
+                        if let Some(payload) = &payload {
+                            let _ = callback.call1(&JsValue::NULL, payload);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
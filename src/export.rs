@@ -0,0 +1,114 @@
+pub mod pdf;
+
+use crate::bounding_box::BoundingBox;
+use crate::element::id::Id;
+use crate::export::pdf::PdfPage;
+use crate::renderer::renderer::canvas_renderer::CanvasRenderer;
+use crate::renderer::renderer::svg_renderer::SVGRenderer;
+use crate::view_port::{LockError, ViewPort};
+use base64::Engine;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlCanvasElement, SvgElement};
+
+/* Board units are css pixels, a point is 1/72 of an inch. */
+const POINTS_PER_UNIT: f64 = 0.75;
+const JPEG_QUALITY: f64 = 0.92;
+
+/* Frame with its content as a standalone svg document, scaled into pixels. None if there is no such frame. */
+pub fn frame_svg(view_port: &ViewPort, frame_id: &Id, scale: f64) -> Result<Option<String>, LockError> {
+    let Some(frame_box) = view_port.frame_box(frame_id)? else {
+        return Ok(None);
+    };
+
+    let svg: SvgElement = document()
+        .create_element_ns(Some("http://www.w3.org/2000/svg"), "svg")
+        .expect("can't create svg element")
+        .dyn_into::<SvgElement>()
+        .expect("can't create svg element");
+
+    svg
+        .set_attribute("xmlns", "http://www.w3.org/2000/svg")
+        .expect("Can't set xmlns");
+    svg
+        .set_attribute("viewBox", &format!("{} {} {} {}", frame_box.left, frame_box.top, frame_box.width(), frame_box.height()))
+        .expect("Can't set viewBox");
+    svg
+        .set_attribute("width", &format!("{}", frame_box.width() * scale))
+        .expect("Can't set width");
+    svg
+        .set_attribute("height", &format!("{}", frame_box.height() * scale))
+        .expect("Can't set height");
+
+    view_port.render_frame(frame_id, &mut SVGRenderer::new(svg.clone()))?;
+
+    Ok(Some(svg.outer_html()))
+}
+
+/* Frame rasterized as a data url of the mime type. Images not decoded yet by the browser are left out. */
+pub fn frame_raster(view_port: &ViewPort, frame_id: &Id, scale: f64, mime: &str) -> Result<Option<String>, LockError> {
+    Ok(frame_canvas(view_port, frame_id, scale)?.map(|(canvas, _)| data_url(&canvas, mime)))
+}
+
+/* Frames as pages of one pdf, in the given order. Frames not on the board are skipped. */
+pub fn frames_pdf(view_port: &ViewPort, frame_ids: &[Id], scale: f64) -> Result<Vec<u8>, LockError> {
+    let mut pages: Vec<PdfPage> = vec![];
+
+    for frame_id in frame_ids {
+        let Some((canvas, frame_box)) = frame_canvas(view_port, frame_id, scale)? else {
+            continue;
+        };
+
+        let data_url: String = data_url(&canvas, "image/jpeg");
+        let Some(jpeg) = data_url
+            .split_once(',')
+            .and_then(|(_, data)| base64::engine::general_purpose::STANDARD.decode(data).ok())
+        else {
+            continue;
+        };
+
+        pages.push(PdfPage {
+            jpeg,
+            pixel_width: canvas.width(),
+            pixel_height: canvas.height(),
+            width: frame_box.width() * POINTS_PER_UNIT,
+            height: frame_box.height() * POINTS_PER_UNIT,
+        });
+    }
+
+    Ok(pdf::write(&pages))
+}
+
+fn frame_canvas(view_port: &ViewPort, frame_id: &Id, scale: f64) -> Result<Option<(HtmlCanvasElement, BoundingBox)>, LockError> {
+    let Some(frame_box) = view_port.frame_box(frame_id)? else {
+        return Ok(None);
+    };
+
+    let canvas: HtmlCanvasElement = document()
+        .create_element("canvas")
+        .expect("can't create canvas element")
+        .dyn_into::<HtmlCanvasElement>()
+        .expect("can't create canvas element");
+
+    canvas.set_width((frame_box.width() * scale).ceil().max(1.0) as u32);
+    canvas.set_height((frame_box.height() * scale).ceil().max(1.0) as u32);
+
+    let mut renderer: CanvasRenderer = CanvasRenderer::new(canvas.clone());
+    renderer.show_area(&frame_box, scale);
+    view_port.render_frame(frame_id, &mut renderer)?;
+
+    Ok(Some((canvas, frame_box)))
+}
+
+fn data_url(canvas: &HtmlCanvasElement, mime: &str) -> String {
+    match mime {
+        "image/jpeg" => canvas.to_data_url_with_type_and_encoder_options(mime, &JPEG_QUALITY.into()),
+        _ => canvas.to_data_url_with_type(mime),
+    }
+    .expect("Can't encode canvas")
+}
+
+fn document() -> web_sys::Document {
+    web_sys::window()
+        .and_then(|window| window.document())
+        .expect("global document does not exists")
+}
@@ -0,0 +1,70 @@
+/* Page showing one JPEG image over the whole page. */
+pub struct PdfPage {
+    pub jpeg: Vec<u8>,
+    /* Size of the image in pixels. */
+    pub pixel_width: u32,
+    pub pixel_height: u32,
+    /* Size of the page in points. */
+    pub width: f64,
+    pub height: f64,
+}
+
+/* Minimal PDF document, one image per page. JPEG data is embedded as it is. */
+pub fn write(pages: &[PdfPage]) -> Vec<u8> {
+    /* Catalog and page tree come first, each page takes three objects: page, content and image. */
+    let page_object = |index: usize| 3 + index * 3;
+
+    let kids: Vec<String> = (0..pages.len()).map(|index| format!("{} 0 R", page_object(index))).collect();
+
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()).into_bytes(),
+    ];
+
+    for (index, page) in pages.iter().enumerate() {
+        let number: usize = page_object(index);
+
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /XObject << /Frame {} 0 R >> >> /Contents {} 0 R >>",
+            page.width, page.height, number + 2, number + 1,
+        ).into_bytes());
+
+        let content: String = format!("q {} 0 0 {} 0 0 cm /Frame Do Q", page.width, page.height);
+        objects.push(stream(&format!("<< /Length {} >>", content.len()), content.as_bytes()));
+
+        objects.push(stream(
+            &format!(
+                "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /DCTDecode /Length {} >>",
+                page.pixel_width, page.pixel_height, page.jpeg.len(),
+            ),
+            &page.jpeg,
+        ));
+    }
+
+    let mut pdf: Vec<u8> = b"%PDF-1.4\n".to_vec();
+    let mut offsets: Vec<usize> = vec![];
+
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n", index + 1).bytes());
+        pdf.extend(object);
+        pdf.extend(b"\nendobj\n");
+    }
+
+    let xref: usize = pdf.len();
+    pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).bytes());
+    for offset in offsets {
+        pdf.extend(format!("{offset:010} 00000 n \n").bytes());
+    }
+    pdf.extend(format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n", objects.len() + 1).bytes());
+
+    pdf
+}
+
+fn stream(dictionary: &str, data: &[u8]) -> Vec<u8> {
+    let mut object: Vec<u8> = format!("{dictionary}\nstream\n").into_bytes();
+    object.extend(data);
+    object.extend(b"\nendstream");
+
+    object
+}
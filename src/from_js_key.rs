@@ -13,3 +13,17 @@ pub fn from_js_key(key: &str) -> Option<Key> {
         _ => None
     }
 }
+
+/* Name the host sends for the key, so keys delivered as interactions are handled like host key events. */
+pub fn to_js_key(key: &Key) -> &'static str {
+    match key {
+        Key::Esc => "Escape",
+        Key::Enter => "Enter",
+        Key::Backspace => "Backspace",
+        Key::Delete => "Delete",
+        Key::ArrowLeft => "ArrowLeft",
+        Key::ArrowUp => "ArrowUp",
+        Key::ArrowRight => "ArrowRight",
+        Key::ArrowDown => "ArrowDown",
+    }
+}
@@ -0,0 +1,295 @@
+use crate::element::id::Id;
+use crate::element::json_entity::JsonEntity;
+use crate::element::patch::Patch;
+use crate::element::transform::Transform;
+use crate::element::Build;
+use crate::events::{BoardEvent, Events};
+use crate::text::measure::TextMeasure;
+use crate::view_port::{LockError, ViewPort};
+use entity_model_feature::entity::Entity;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::{Arc, RwLock};
+
+#[derive(Debug)]
+pub enum HistoryError {
+    Lock,
+    Json(serde_json::Error),
+}
+
+impl From<LockError<'_>> for HistoryError {
+    fn from(_: LockError) -> Self {
+        HistoryError::Lock
+    }
+}
+
+impl From<serde_json::Error> for HistoryError {
+    fn from(error: serde_json::Error) -> Self {
+        HistoryError::Json(error)
+    }
+}
+
+/*
+ * Entities are kept as json, so history doesn't depend on entity features.
+ * Modifications are kept as patches both ways, so editing a long stroke doesn't keep two copies of it.
+ */
+#[derive(Clone)]
+pub enum Change {
+    Add(JsonEntity),
+    Remove(JsonEntity),
+    Modify {
+        id: Id,
+        redo: Patch,
+        undo: Patch,
+    },
+}
+
+impl Change {
+    /* Modify model of the entity in the view port and describe the modification. None if there is no such entity. */
+    pub fn modify<M: Serialize + DeserializeOwned>(view_port: &mut ViewPort, id: &Id, modify: impl FnOnce(&mut M)) -> Result<Option<Change>, HistoryError> {
+        Self::modify_json(view_port, id, |json_entity| {
+            let mut model: M = serde_json::from_value(json_entity.model.clone())?;
+            modify(&mut model);
+
+            json_entity.model = serde_json::to_value(&model)?;
+
+            Ok(())
+        })
+    }
+
+    /* Modify json of the entity, e.g. its style, in the view port. None if there is no such entity. */
+    pub fn modify_json(view_port: &mut ViewPort, id: &Id, modify: impl FnOnce(&mut JsonEntity) -> Result<(), HistoryError>) -> Result<Option<Change>, HistoryError> {
+        let Some(before) = view_port.with_entity(id, JsonEntity::try_from)? else {
+            return Ok(None);
+        };
+        let before: JsonEntity = before?;
+
+        let mut after: JsonEntity = before.clone();
+        modify(&mut after)?;
+
+        let entity: Entity<Id> = Build::from_json(after.clone())?;
+        view_port.modify_entity(entity)?;
+
+        Ok(Some(Change::modified(&before, &after)))
+    }
+
+    /* Map geometry of the entity in the view port. Redo repeats the transform instead of keeping the mapped model. */
+    pub fn transform(view_port: &mut ViewPort, id: &Id, transform: &Transform, measure: &dyn TextMeasure) -> Result<Option<Change>, HistoryError> {
+        Self::patch(view_port, id, &Patch::transform(*transform), measure)
+    }
+
+    /*
+     * Apply the patch to the entity in the view port. Redo repeats the patch, moves are undone by moving back,
+     * other patches by the difference of the models. None if there is no such entity.
+     */
+    pub fn patch(view_port: &mut ViewPort, id: &Id, patch: &Patch, measure: &dyn TextMeasure) -> Result<Option<Change>, HistoryError> {
+        let Some(before) = view_port.with_entity(id, JsonEntity::try_from)? else {
+            return Ok(None);
+        };
+        let before: JsonEntity = before?;
+
+        let after: JsonEntity = patch.apply(&before, measure)?;
+        view_port.modify_entity(Build::from_json(after.clone())?)?;
+
+        Ok(Some(Change::Modify {
+            id: id.clone(),
+            redo: patch.clone(),
+            undo: patch.inverse().unwrap_or_else(|| Patch::diff(&after, &before)),
+        }))
+    }
+
+    /* Already applied modification of the entity from one json to the other. */
+    pub fn modified(before: &JsonEntity, after: &JsonEntity) -> Change {
+        Change::Modify {
+            id: after.id.clone(),
+            redo: Patch::diff(before, after),
+            undo: Patch::diff(after, before),
+        }
+    }
+
+    pub fn inverse(&self) -> Change {
+        match self {
+            Change::Add(entity) => Change::Remove(entity.clone()),
+            Change::Remove(entity) => Change::Add(entity.clone()),
+            Change::Modify { id, redo, undo } => Change::Modify {
+                id: id.clone(),
+                redo: undo.clone(),
+                undo: redo.clone(),
+            },
+        }
+    }
+
+    pub fn apply(&self, view_port: &mut ViewPort, measure: &dyn TextMeasure) -> Result<(), HistoryError> {
+        match self {
+            Change::Add(entity) =>
+                view_port.add_entity(Build::from_json(entity.clone())?)?,
+            Change::Remove(entity) => {
+                view_port.remove_entity(&entity.id)?;
+            }
+            Change::Modify { id, redo, .. } => {
+                /* Entity removed by a collaborator meanwhile. */
+                let Some(current) = view_port.with_entity(id, JsonEntity::try_from)? else {
+                    return Ok(());
+                };
+
+                view_port.modify_entity(Build::from_json(redo.apply(&current?, measure)?)?)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/* Changes made by one user action. Undo and redo always operate on whole steps. */
+#[derive(Clone, Default)]
+pub struct Step {
+    changes: Vec<Change>,
+}
+
+impl Step {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, change: Change) {
+        self.changes.push(change);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn inverse(&self) -> Step {
+        Step {
+            changes: self.changes.iter().rev().map(Change::inverse).collect(),
+        }
+    }
+
+    pub fn apply(&self, view_port: &mut ViewPort, measure: &dyn TextMeasure) -> Result<(), HistoryError> {
+        for change in &self.changes {
+            change.apply(view_port, measure)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Change> for Step {
+    fn from(change: Change) -> Self {
+        Step {
+            changes: vec![change],
+        }
+    }
+}
+
+#[derive(Default)]
+struct Stacks {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+}
+
+#[derive(Clone)]
+pub struct History {
+    stacks: Arc<RwLock<Stacks>>,
+    events: Events,
+    /* Connectors rerouted by the recorded changes are taken from it into the same step. */
+    view_port: ViewPort,
+    /* Lays out text again when a transform is redone. */
+    measure: Arc<dyn TextMeasure>,
+}
+
+impl History {
+    pub fn new(events: Events, view_port: ViewPort, measure: Arc<dyn TextMeasure>) -> Self {
+        Self {
+            stacks: Arc::new(RwLock::new(Stacks::default())),
+            events,
+            view_port,
+            measure,
+        }
+    }
+
+    /* Record already applied step together with the connectors it rerouted. */
+    pub fn push(&mut self, mut step: Step) -> Result<(), HistoryError> {
+        for change in self.view_port.take_reroutes()? {
+            step.push(change);
+        }
+
+        if step.is_empty() {
+            return Ok(());
+        }
+
+        let mut stacks = self.stacks.write().map_err(|_| HistoryError::Lock)?;
+        stacks.undo.push(step);
+        stacks.redo.clear();
+        drop(stacks);
+
+        self.emit_changed();
+
+        Ok(())
+    }
+
+    /* Returns false if there is nothing to undo. */
+    pub fn undo(&mut self, view_port: &mut ViewPort) -> Result<bool, HistoryError> {
+        let mut stacks = self.stacks.write().map_err(|_| HistoryError::Lock)?;
+
+        let Some(step) = stacks.undo.pop() else {
+            return Ok(false);
+        };
+
+        step.inverse().apply(view_port, &*self.measure)?;
+        /* Rerouted connectors are in the step already. */
+        view_port.take_reroutes()?;
+        stacks.redo.push(step);
+        drop(stacks);
+
+        self.emit_changed();
+
+        Ok(true)
+    }
+
+    /* Returns false if there is nothing to redo. */
+    pub fn redo(&mut self, view_port: &mut ViewPort) -> Result<bool, HistoryError> {
+        let mut stacks = self.stacks.write().map_err(|_| HistoryError::Lock)?;
+
+        let Some(step) = stacks.redo.pop() else {
+            return Ok(false);
+        };
+
+        step.apply(view_port, &*self.measure)?;
+        view_port.take_reroutes()?;
+        stacks.undo.push(step);
+        drop(stacks);
+
+        self.emit_changed();
+
+        Ok(true)
+    }
+
+    /* Forget all steps, used when the whole board is replaced. */
+    pub fn clear(&mut self) -> Result<(), HistoryError> {
+        self.view_port.take_reroutes()?;
+        *self.stacks.write().map_err(|_| HistoryError::Lock)? = Stacks::default();
+        self.emit_changed();
+
+        Ok(())
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.stacks.read().is_ok_and(|stacks| !stacks.undo.is_empty())
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.stacks.read().is_ok_and(|stacks| !stacks.redo.is_empty())
+    }
+
+    fn emit_changed(&self) {
+        self.events.emit(BoardEvent::HistoryChanged {
+            can_undo: self.can_undo(),
+            can_redo: self.can_redo(),
+        });
+    }
+}
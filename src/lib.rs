@@ -1,29 +1,110 @@
 #![feature(extern_types)]
 
-mod view_port;
-mod renderer;
-mod element;
+pub mod view_port;
+pub mod renderer;
+pub mod element;
 mod from_js_key;
+pub mod bounding_box;
+mod math;
+pub mod history;
+pub mod tool;
+mod shape_recognizer;
+pub mod text;
+pub mod style;
+mod snapping;
+pub mod arrange;
+mod minimap;
+pub mod events;
+pub mod document;
+pub mod storage;
+mod crdt;
+mod presence;
+pub mod mode;
+mod presentation;
+#[cfg(feature = "dom-renderers")]
+mod export;
+mod platform;
+pub mod error;
+pub mod schema;
 
+use crate::arrange::{AlignEdge, Axis, Dimension};
+use crate::bounding_box::BoundingBox;
+use crate::crdt::{self, StateVector};
+use crate::document::{excalidraw, Document};
+use crate::error::BoardError;
+use crate::element::connector_model::{Arrowhead, ConnectorModel, Routing};
+use crate::element::ellipse_model::EllipseModel;
+use crate::element::frame_model::{FrameModel, PageSize};
+use crate::element::free_hand::recognition::Recognition;
+use crate::element::free_hand::smoothing::Smoothing;
+use crate::element::free_hand::{self, StrokeOptions};
 use crate::element::id::Id;
+use crate::element::image_model::{Crop, ImageModel, ImageSource};
+use crate::element::json_entity::JsonEntity;
+use crate::element::line_model::LineModel;
+use crate::element::patch::Patch;
+use crate::element::regular_polygon_model::{self, RegularPolygonModel};
+use crate::element::sticky_note_model::{StickyNoteColor, StickyNoteModel};
+use crate::element::transform::Transform;
 use crate::element::{Build, ElementType};
-use crate::from_js_key::from_js_key;
+use crate::events::{BoardEvent, EventKind, Events};
+use crate::from_js_key::{from_js_key, to_js_key};
+use crate::history::{Change, History, HistoryError, Step};
+use crate::minimap::Minimap;
+use crate::mode::{BoardMode, CurrentMode};
+use crate::platform;
+use crate::presence::{Presence, Presences};
+use crate::presentation::Presentation;
+use crate::renderer::board_renderer::BoardRenderer;
+#[cfg(feature = "dom-renderers")]
 use crate::renderer::renderer::canvas_renderer::CanvasRenderer;
+#[cfg(feature = "dom-renderers")]
 use crate::renderer::renderer::svg_renderer::SVGRenderer;
+use crate::shape_recognizer::ShapeRecognizer;
+use crate::snapping::grid::{Grid, GridKind};
+use crate::snapping::Snapping;
+use crate::storage::autosave::Autosave;
+#[cfg(feature = "wasm")]
+use crate::storage::js_storage::JsStorage;
+use crate::storage::memory_storage::MemoryStorage;
+use crate::storage::Storage;
+#[cfg(feature = "wasm")]
+use crate::storage::StorageError;
+use crate::style::{CurrentStyle, Style};
+use crate::text::font::Font;
+#[cfg(all(feature = "dom-renderers", target_arch = "wasm32"))]
+use crate::text::measure::CanvasTextMeasure;
+#[cfg(not(all(feature = "dom-renderers", target_arch = "wasm32")))]
+use crate::text::measure::ApproximateTextMeasure;
+use crate::text::measure::TextMeasure;
+use crate::text::text_editor::TextEditor;
+use crate::tool::connector_tool::{ConnectorOptions, ConnectorTool};
+use crate::tool::eraser_tool::{EraserMode, EraserTool};
+use crate::tool::modifiers::Modifiers;
+use crate::tool::select_tool::SelectTool;
+use crate::tool::shape_tool::ShapeTool;
+use crate::tool::text_tool::TextTool;
 use crate::view_port::ViewPort;
 use entity_model_feature::entity::Entity;
 use event_handler::Receiver;
 use geometry::point::point_2d::Point2D;
 use standard_rendering_plugin::renderable::Renderable;
-use standard_rendering_plugin::renderer::renderer::Renderer;
 use standard_tool_plugin::tool::draw_tool::click_draw_tool::ClickDrawTool;
 use standard_tool_plugin::tool::draw_tool::move_draw_tool::MoveDrawTool;
-use standard_tool_plugin::tool::select_tool::SelectTool;
 use standard_tool_plugin::tool::Interaction;
 use standard_tool_plugin::tool::{PointingDevice, Tool};
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::wasm_bindgen;
-use wasm_bindgen_futures::spawn_local;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::JsValue;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+#[cfg(feature = "wasm")]
+use wasm_bindgen_futures::future_to_promise;
 
+#[cfg(feature = "wasm")]
 #[wasm_bindgen]
 unsafe extern "C" {
     #[wasm_bindgen(js_namespace = console)]
@@ -32,134 +113,1414 @@ unsafe extern "C" {
 
 #[inline]
 fn listen_async<E: 'static>(receiver: Receiver<E>, mut callback: impl FnMut(E) + 'static) {
-    spawn_local(async move {
+    platform::spawn_local(async move {
         while let Ok(event) = receiver.recv().await {
             callback(event);
         }
     });
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub struct Whiteboard {
     owner_id: String,
     view_port: ViewPort,
+    history: History,
+    stroke_options: StrokeOptions,
+    shape_recognizer: Option<ShapeRecognizer>,
+    text_editor: TextEditor,
+    connector_options: ConnectorOptions,
+    /* Sides and star inner radius of newly drawn regular polygons. */
+    regular_polygon: RegularPolygonModel,
+    /* Colour of newly created sticky notes. */
+    sticky_note: StickyNoteModel,
+    /* Page size of newly drawn frames. */
+    frame: FrameModel,
+    modifiers: Modifiers,
+    /* Style of newly created entities, shared with the tools. */
+    current_style: CurrentStyle,
+    snapping: Snapping,
     active_tool: Option<Box<dyn Tool>>,
+    events: Events,
+    /* Ids selected by the host, reported to selection listeners. */
+    selection: Vec<Id>,
+    /* Shared with the history listener that schedules saves. */
+    autosave: Rc<RefCell<Option<Autosave>>>,
+    /* Active tool name and element type, reported in presence. */
+    tool: Option<(&'static str, Option<ElementType>)>,
+    pointer: Option<Point2D>,
+    /* Pointer trail of the freehand stroke being drawn. */
+    stroke: Vec<Point2D>,
+    presences: Presences,
+    mode: CurrentMode,
+    /* Modes of remote owners, enforced when their updates are applied. */
+    owner_modes: HashMap<String, BoardMode>,
+    presentation: Presentation,
 }
 
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl Whiteboard {
     pub fn new(owner_id: &str) -> Self {
         /* enable panic message printing */
+        #[cfg(feature = "console_error_panic_hook")]
         console_error_panic_hook::set_once();
 
+        #[cfg(all(feature = "dom-renderers", target_arch = "wasm32"))]
+        let measure: Arc<dyn TextMeasure> = Arc::new(CanvasTextMeasure::new());
+        /* Without browser font metrics text is measured approximately. */
+        #[cfg(not(all(feature = "dom-renderers", target_arch = "wasm32")))]
+        let measure: Arc<dyn TextMeasure> = Arc::new(ApproximateTextMeasure);
+
+        let events: Events = Events::new();
+        let view_port: ViewPort = ViewPort::new(events.clone());
+        let history: History = History::new(events.clone(), view_port.clone(), measure.clone());
+        let text_editor: TextEditor = TextEditor::new(measure, view_port.clone(), history.clone());
+
+        let autosave: Rc<RefCell<Option<Autosave>>> = Self::save_on_change(&events);
+
         Self {
             owner_id: owner_id.to_string(),
-            view_port: ViewPort::new(),
+            view_port,
+            history,
+            stroke_options: StrokeOptions::default(),
+            shape_recognizer: None,
+            text_editor,
+            connector_options: ConnectorOptions::default(),
+            regular_polygon: RegularPolygonModel::default(),
+            sticky_note: StickyNoteModel::default(),
+            frame: FrameModel::default(),
+            modifiers: Modifiers::new(),
+            current_style: CurrentStyle::new(),
+            snapping: Snapping::new(),
             active_tool: None,
+            events,
+            selection: vec![],
+            autosave,
+            tool: None,
+            pointer: None,
+            stroke: vec![],
+            presences: Presences::default(),
+            mode: CurrentMode::new(),
+            owner_modes: HashMap::new(),
+            presentation: Presentation::default(),
         }
     }
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl Whiteboard {
-    pub fn activate_move_draw(&mut self, element_type: ElementType) {
-        let owner_id: String = self.owner_id.clone();
+    pub fn activate_move_draw(&mut self, element_type: ElementType) -> Result<(), BoardError> {
+        self.ensure_allowed(Some(element_type))?;
 
-        let move_draw_tool: MoveDrawTool<Id> = MoveDrawTool::new(move || Build::default(&owner_id, element_type));
+        match element_type {
+            ElementType::Text => return self.activate_text_tool(),
+            ElementType::Connector => return self.activate_connector_tool(),
+            ElementType::Ellipse | ElementType::Line | ElementType::RegularPolygon | ElementType::StickyNote | ElementType::Frame =>
+                return self.activate_shape_tool(element_type),
+            _ => {}
+        }
 
-        let mut view_port: ViewPort = self.view_port.clone();
-        listen_async(move_draw_tool.event.finish_drawing(), move |entity: Entity<Id>| {
-            view_port.add_entity(entity).expect("Can't lock view port to add entity");
-        });
+        let (owner_id, current_style) = (self.owner_id.clone(), self.current_style.clone());
+
+        let move_draw_tool: MoveDrawTool<Id> = MoveDrawTool::new(move || Build::default(&owner_id, element_type, current_style.get()));
+
+        listen_async(move_draw_tool.event.finish_drawing(), self.add_drawn_entity());
+
+        self.set_active_tool(Box::new(move_draw_tool), "move_draw", Some(element_type));
+
+        Ok(())
+    }
+
+    pub fn activate_click_draw(&mut self, element_type: ElementType) -> Result<(), BoardError> {
+        self.ensure_allowed(Some(element_type))?;
+
+        match element_type {
+            ElementType::Text => return self.activate_text_tool(),
+            ElementType::Connector => return self.activate_connector_tool(),
+            ElementType::Ellipse | ElementType::Line | ElementType::RegularPolygon | ElementType::StickyNote | ElementType::Frame =>
+                return self.activate_shape_tool(element_type),
+            _ => {}
+        }
+
+        let (owner_id, current_style) = (self.owner_id.clone(), self.current_style.clone());
+
+        let click_draw_tool: ClickDrawTool<Id> = ClickDrawTool::new(move || Build::default(&owner_id, element_type, current_style.get()));
+
+        listen_async(click_draw_tool.event.finish_drawing(), self.add_drawn_entity());
+
+        self.set_active_tool(Box::new(click_draw_tool), "click_draw", Some(element_type));
+
+        Ok(())
+    }
+
+    pub fn activate_select_tool(&mut self) {
+        let select_tool: SelectTool = SelectTool::new(self.mode.clone(), self.view_port.clone(), self.history.clone(), self.text_editor.clone());
+
+        self.set_active_tool(Box::new(select_tool), "select", None);
+    }
+
+    pub fn activate_eraser(&mut self, mode: EraserMode, radius: f64) -> Result<(), BoardError> {
+        self.ensure_allowed(None)?;
+
+        let eraser_tool: EraserTool = EraserTool::new(
+            &self.owner_id,
+            mode,
+            radius,
+            self.view_port.clone(),
+            self.history.clone(),
+        );
+
+        self.set_active_tool(Box::new(eraser_tool), "eraser", None);
 
-        self.active_tool = Some(Box::new(move_draw_tool));
+        Ok(())
     }
 
-    pub fn activate_click_draw(&mut self, element_type: ElementType) {
-        let owner_id: String = self.owner_id.clone();
+    pub fn activate_text_tool(&mut self) -> Result<(), BoardError> {
+        self.ensure_allowed(Some(ElementType::Text))?;
 
-        let click_draw_tool: ClickDrawTool<Id> = ClickDrawTool::new(move || Build::default(&owner_id, element_type));
+        let text_tool: TextTool = TextTool::new(
+            &self.owner_id,
+            self.current_style.clone(),
+            self.view_port.clone(),
+            self.history.clone(),
+            self.text_editor.clone(),
+        );
+
+        self.set_active_tool(Box::new(text_tool), "text", Some(ElementType::Text));
+
+        Ok(())
+    }
+
+    pub fn activate_connector_tool(&mut self) -> Result<(), BoardError> {
+        self.ensure_allowed(Some(ElementType::Connector))?;
+
+        let connector_tool: ConnectorTool = ConnectorTool::new(
+            &self.owner_id,
+            self.current_style.clone(),
+            self.connector_options,
+            self.view_port.clone(),
+            self.history.clone(),
+        );
+
+        self.set_active_tool(Box::new(connector_tool), "connector", Some(ElementType::Connector));
+
+        Ok(())
+    }
+
+    /* Drag to draw ellipse, line, regular polygon, sticky note or frame, shift keeps proportions. Other types are ignored. */
+    pub fn activate_shape_tool(&mut self, element_type: ElementType) -> Result<(), BoardError> {
+        self.ensure_allowed(Some(element_type))?;
+
+        let (view_port, history, modifiers) = (self.view_port.clone(), self.history.clone(), self.modifiers.clone());
+        let current_style: &CurrentStyle = &self.current_style;
+
+        let shape_tool: Box<dyn Tool> = match element_type {
+            ElementType::Ellipse =>
+                Box::new(ShapeTool::new(&self.owner_id, current_style.clone(), element_type, EllipseModel::default(), modifiers, view_port, history)),
+            ElementType::Line =>
+                Box::new(ShapeTool::new(&self.owner_id, current_style.clone(), element_type, LineModel::default(), modifiers, view_port, history)),
+            ElementType::RegularPolygon =>
+                Box::new(ShapeTool::new(&self.owner_id, current_style.clone(), element_type, self.regular_polygon.clone(), modifiers, view_port, history)),
+            ElementType::StickyNote =>
+                Box::new(ShapeTool::new(&self.owner_id, current_style.clone(), element_type, self.sticky_note.clone(), modifiers, view_port, history)),
+            ElementType::Frame =>
+                Box::new(ShapeTool::new(&self.owner_id, current_style.clone(), element_type, self.frame.clone(), modifiers, view_port, history)),
+            _ => return Ok(()),
+        };
+
+        self.set_active_tool(shape_tool, "shape", Some(element_type));
+
+        Ok(())
+    }
+
+    /*
+     * Applies to regular polygons drawn after the call.
+     * Star inner radius is a part of the outer radius in range 0..1, zero draws a plain polygon.
+     */
+    pub fn set_regular_polygon_options(&mut self, sides: u32, star_inner_radius: f64) {
+        self.regular_polygon.sides = sides.max(regular_polygon_model::MIN_SIDES);
+        self.regular_polygon.inner_radius = Some(star_inner_radius.clamp(0.0, 1.0)).filter(|inner_radius| *inner_radius > 0.0);
+    }
+
+    /* Applies to sticky note tools activated after the call. */
+    pub fn set_sticky_note_color(&mut self, color: StickyNoteColor) {
+        self.sticky_note.color = color;
+    }
+
+    /* Replace the note text, font is shrunk to fit it. Returns false if there is no such note. */
+    pub fn set_sticky_note_text(&mut self, id: &Id, text: &str) -> Result<bool, BoardError> {
+        let text_editor: TextEditor = self.text_editor.clone();
+
+        self.modify_sticky_note(id, |model| {
+            model.text = text.to_string();
+            model.fit(text_editor.measure());
+        })
+    }
+
+    pub fn recolor_sticky_note(&mut self, id: &Id, color: StickyNoteColor) -> Result<bool, BoardError> {
+        self.modify_sticky_note(id, |model| model.color = color)
+    }
+
+    fn modify_sticky_note(&mut self, id: &Id, modify: impl FnOnce(&mut StickyNoteModel)) -> Result<bool, BoardError> {
+        self.ensure_allowed(Some(ElementType::StickyNote))?;
+
+        if *id.element_type() != ElementType::StickyNote {
+            return Ok(false);
+        }
+
+        let Some(change) = Change::modify(&mut self.view_port, id, modify).expect("Can't modify sticky note") else {
+            return Ok(false);
+        };
 
+        self.history.push(Step::from(change)).expect("Can't lock history to record sticky note change");
+
+        Ok(true)
+    }
+
+    /* Applies to connector tools activated after the call. */
+    pub fn set_connector_options(&mut self, routing: Routing, start_arrowhead: Arrowhead, end_arrowhead: Arrowhead) {
+        self.connector_options = ConnectorOptions {
+            routing,
+            start_arrowhead,
+            end_arrowhead,
+        };
+    }
+
+    /* Set or clear (with empty string) the connector label. Returns false if there is no such connector. */
+    pub fn set_connector_label(&mut self, id: &Id, label: &str) -> Result<bool, BoardError> {
+        self.ensure_allowed(Some(ElementType::Connector))?;
+
+        if *id.element_type() != ElementType::Connector {
+            return Ok(false);
+        }
+
+        let label: Option<String> = Some(label.to_string()).filter(|label| !label.is_empty());
+        let change: Option<Change> = Change::modify(&mut self.view_port, id, |model: &mut ConnectorModel| model.label = label)
+            .expect("Can't set connector label");
+
+        let Some(change) = change else {
+            return Ok(false);
+        };
+
+        self.history.push(Step::from(change)).expect("Can't lock history to record connector label");
+
+        Ok(true)
+    }
+
+    fn set_active_tool(&mut self, tool: Box<dyn Tool>, name: &'static str, element_type: Option<ElementType>) {
+        self.text_editor.finish().expect("Can't finish text editing");
+
+        self.active_tool = Some(tool);
+        self.tool = Some((name, element_type));
+        self.stroke.clear();
+        self.events.emit(BoardEvent::ToolChanged { tool: name, element_type });
+    }
+
+    /* Applies to draw tools activated after the call. */
+    pub fn set_stroke_options(&mut self, smoothing: Smoothing, tolerance: f64) {
+        self.stroke_options = StrokeOptions {
+            smoothing,
+            tolerance,
+        };
+    }
+
+    fn add_drawn_entity(&self) -> impl FnMut(Entity<Id>) + 'static {
         let mut view_port: ViewPort = self.view_port.clone();
-        listen_async(click_draw_tool.event.finish_drawing(), move |entity: Entity<Id>| {
+        let mut history: History = self.history.clone();
+        let stroke_options: StrokeOptions = self.stroke_options;
+        let shape_recognizer: Option<ShapeRecognizer> = self.shape_recognizer.clone();
+
+        move |entity: Entity<Id>| {
+            let mut recognition: Option<Recognition> = None;
+
+            let entity: Entity<Id> = match entity.id().element_type() {
+                ElementType::FreeHand => {
+                    /* Recognize raw samples, they are more precise than the smoothed stroke. */
+                    recognition = shape_recognizer
+                        .as_ref()
+                        .and_then(|shape_recognizer| shape_recognizer.recognize(&entity));
+
+                    free_hand::finish_stroke(entity, &stroke_options).expect("Can't finish freehand stroke")
+                }
+                _ => entity,
+            };
+
+            let id: Id = entity.id().clone();
+            let json_entity: JsonEntity = JsonEntity::try_from(&entity).expect("Can't serialize drawn entity");
+
             view_port.add_entity(entity).expect("Can't lock view port to add entity");
+            history.push(Step::from(Change::Add(json_entity))).expect("Can't lock history to record drawing");
+
+            if let (Some(shape_recognizer), Some(recognition)) = (&shape_recognizer, recognition) {
+                shape_recognizer.offer(&id, recognition);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+impl Whiteboard {
+    /*
+     * Offer replacing finished freehand strokes with clean shapes.
+     * Callback is called as callback(stroke_id, shape_name, confidence) for recognitions with confidence >= threshold.
+     * Applies to draw tools activated after the call.
+     */
+    pub fn enable_shape_recognition(&mut self, threshold: f64, callback: js_sys::Function) {
+        self.enable_shape_recognition_with(threshold, move |stroke_id, shape_name, confidence| {
+            let _ = callback.call3(
+                &JsValue::NULL,
+                &JsValue::from(stroke_id.clone()),
+                &JsValue::from_str(shape_name),
+                &JsValue::from_f64(confidence),
+            );
         });
+    }
+}
 
-        self.active_tool = Some(Box::new(click_draw_tool));
+impl Whiteboard {
+    /* Shape recognition with a callback in Rust, called the same way as the host one. */
+    pub fn enable_shape_recognition_with(&mut self, threshold: f64, callback: impl Fn(&Id, &str, f64) + 'static) {
+        self.shape_recognizer = Some(ShapeRecognizer::new(threshold, callback));
     }
+}
 
-    pub fn activate_select_tool(&mut self) {
-        self.active_tool = Some(Box::new(SelectTool::<Id>::new()));
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Whiteboard {
+    pub fn disable_shape_recognition(&mut self) {
+        self.shape_recognizer = None;
+    }
+
+    /* Replace the stroke with the offered shape. Returns false if there is no offer for the stroke. */
+    pub fn accept_recognized_shape(&mut self, stroke_id: &Id) -> Result<bool, BoardError> {
+        self.ensure_allowed(None)?;
+
+        let Some(recognition) = self.shape_recognizer.as_ref().and_then(|shape_recognizer| shape_recognizer.take(stroke_id)) else {
+            return Ok(false);
+        };
+
+        let Some(stroke) = self.view_port
+            .with_entity(stroke_id, JsonEntity::try_from)
+            .expect("Can't lock view port to find stroke") else {
+            /* Stroke was erased or undone meanwhile. */
+            return Ok(false);
+        };
+
+        let stroke: JsonEntity = stroke.expect("Can't serialize stroke");
+        /* Shape looks like the stroke it replaces. */
+        let shape: Entity<Id> = recognition.build(&self.owner_id, stroke.style.clone()).expect("Can't build recognized shape");
+
+        let mut step: Step = Step::new();
+        step.push(Change::Remove(stroke));
+        step.push(Change::Add(JsonEntity::try_from(&shape).expect("Can't serialize recognized shape")));
+
+        step.apply(&mut self.view_port, self.text_editor.measure()).expect("Can't replace stroke with recognized shape");
+        self.history.push(step).expect("Can't lock history to record shape recognition");
+
+        Ok(true)
+    }
+
+    pub fn reject_recognized_shape(&mut self, stroke_id: &Id) {
+        if let Some(shape_recognizer) = &self.shape_recognizer {
+            shape_recognizer.take(stroke_id);
+        }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Whiteboard {
+    /* Embed the image into the board in its natural size with top left corner at the point. */
+    pub fn insert_image(&mut self, bytes: &[u8], mime: &str, x: f64, y: f64) -> Result<Id, BoardError> {
+        self.insert_image_model(ImageModel::embedded(bytes, mime, x, y))
+    }
+
+    /* Add image referenced by url. Natural size is the pixel size of the image at the url. */
+    pub fn insert_image_url(&mut self, url: &str, natural_width: f64, natural_height: f64, x: f64, y: f64) -> Result<Id, BoardError> {
+        let source: ImageSource = ImageSource::Referenced { url: url.to_string() };
+
+        self.insert_image_model(ImageModel::new(source, natural_width, natural_height, x, y))
+    }
+
+    /* Resize the image to fit into the size, keeping its aspect ratio. Returns false if there is no such image. */
+    pub fn resize_image(&mut self, id: &Id, width: f64, height: f64) -> Result<bool, BoardError> {
+        self.modify_image(id, |model| model.resize(width, height))
+    }
+
+    /* Crop the image to the rectangle in its natural pixels. Empty rectangle removes the crop. */
+    pub fn crop_image(&mut self, id: &Id, x: f64, y: f64, width: f64, height: f64) -> Result<bool, BoardError> {
+        self.modify_image(id, |model| model.set_crop(Some(Crop { x, y, width, height })))
+    }
+
+    fn insert_image_model(&mut self, image_model: ImageModel) -> Result<Id, BoardError> {
+        self.ensure_allowed(Some(ElementType::Image))?;
+
+        let entity: Entity<Id> = Build::default_with(&self.owner_id, ElementType::Image, self.current_style.get(), |model: &mut ImageModel| *model = image_model)
+            .expect("Can't build image");
+
+        let id: Id = entity.id().clone();
+        let json_entity: JsonEntity = JsonEntity::try_from(&entity).expect("Can't serialize image");
+
+        self.view_port.add_entity(entity).expect("Can't lock view port to add image");
+        self.history.push(Step::from(Change::Add(json_entity))).expect("Can't lock history to record image");
+
+        Ok(id)
+    }
+
+    fn modify_image(&mut self, id: &Id, modify: impl FnOnce(&mut ImageModel)) -> Result<bool, BoardError> {
+        self.ensure_allowed(Some(ElementType::Image))?;
+
+        if *id.element_type() != ElementType::Image {
+            return Ok(false);
+        }
+
+        let Some(change) = Change::modify(&mut self.view_port, id, modify).expect("Can't modify image") else {
+            return Ok(false);
+        };
+
+        self.history.push(Step::from(change)).expect("Can't lock history to record image change");
+
+        Ok(true)
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Whiteboard {
+    /*
+     * Change style of the entities as one undoable step. Style json may contain only the changed fields:
+     * fill_color, stroke_color, stroke_width, dash_array, opacity, line_cap, line_join and corner_radius.
+     * Returns false if the json is invalid or none of the entities exist.
+     */
+    pub fn set_style(&mut self, ids: Vec<Id>, style_json: &str) -> Result<bool, BoardError> {
+        self.ensure_allowed_all(&ids)?;
+
+        if Style::default().patched(style_json).is_err() {
+            return Ok(false);
+        }
+
+        let mut step: Step = Step::new();
+
+        for id in &ids {
+            let change: Option<Change> = Change::modify_json(&mut self.view_port, id, |json_entity| {
+                let style: Style = json_entity.style.clone().unwrap_or_default();
+                json_entity.style = Some(style.patched(style_json)?);
+
+                Ok(())
+            }).expect("Can't set style");
+
+            if let Some(change) = change {
+                step.push(change);
+            }
+        }
+
+        let changed: bool = !step.is_empty();
+        self.history.push(step).expect("Can't lock history to record style change");
+
+        Ok(changed)
+    }
+
+    /* Style json of the entity. None if there is no such entity. */
+    pub fn get_style(&self, id: &Id) -> Option<String> {
+        let json_entity: JsonEntity = self.view_port
+            .with_entity(id, JsonEntity::try_from)
+            .expect("Can't lock view port to get style")?
+            .expect("Can't serialize entity");
+
+        serde_json::to_string(&json_entity.style.unwrap_or_default()).ok()
+    }
+
+    /* Style entities created after the call inherit. Returns false if the json is invalid. */
+    pub fn set_current_style(&mut self, style_json: &str) -> bool {
+        let current_style: Style = self.current_style.get().unwrap_or_default();
+
+        let Ok(current_style) = current_style.patched(style_json) else {
+            return false;
+        };
+
+        self.current_style.set(Some(current_style));
+
+        true
+    }
+
+    pub fn get_current_style(&self) -> String {
+        serde_json::to_string(&self.current_style.get().unwrap_or_default()).expect("Can't serialize style")
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Whiteboard {
+    /*
+     * Apply a patch to the entity as one undoable step. Patch json is a list of operations on paths into the entity,
+     * e.g. [{"op": "set", "path": ["model", "x"], "value": 10}]. Operations are set, remove, splice and transform.
+     * Returns false if the json is invalid, there is no such entity or the patch doesn't fit it.
+     */
+    pub fn patch_entity(&mut self, id: &Id, patch_json: &str) -> Result<bool, BoardError> {
+        self.ensure_allowed(Some(*id.element_type()))?;
+
+        let Ok(patch) = serde_json::from_str::<Patch>(patch_json) else {
+            return Ok(false);
+        };
+
+        let change: Option<Change> = match Change::patch(&mut self.view_port, id, &patch, self.text_editor.measure()) {
+            Ok(change) => change,
+            Err(HistoryError::Json(_)) => return Ok(false),
+            Err(HistoryError::Lock) => panic!("Can't lock view port to patch entity"),
+        };
+
+        let Some(change) = change else {
+            return Ok(false);
+        };
+
+        self.history.push(Step::from(change)).expect("Can't lock history to record patch");
+
+        Ok(true)
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Whiteboard {
+    /* Align bounding boxes of the entities to the edge or centre of the selection. Returns false if nothing moved. */
+    pub fn align(&mut self, ids: Vec<Id>, edge: AlignEdge) -> Result<bool, BoardError> {
+        self.arrange(&ids, |boxes| arrange::align(boxes, edge))
+    }
+
+    /* Space the entities equally between the outermost two. Needs at least three entities. */
+    pub fn distribute(&mut self, ids: Vec<Id>, axis: Axis) -> Result<bool, BoardError> {
+        self.arrange(&ids, |boxes| arrange::distribute(boxes, axis))
+    }
+
+    /* Resize the entities to the largest one. */
+    pub fn match_size(&mut self, ids: Vec<Id>, dimension: Dimension) -> Result<bool, BoardError> {
+        self.arrange(&ids, |boxes| arrange::match_size(boxes, dimension))
+    }
+}
+
+impl Whiteboard {
+    /* Map each entity from its bounding box to the target box, recorded as one step. */
+    fn arrange(&mut self, ids: &[Id], targets: impl FnOnce(&[BoundingBox]) -> Vec<BoundingBox>) -> Result<bool, BoardError> {
+        self.ensure_allowed_all(ids)?;
+
+        let mut found: Vec<(&Id, BoundingBox)> = vec![];
+
+        for id in ids {
+            if let Some(bounding_box) = self.view_port.bounding_box(id).expect("Can't lock view port to arrange entities") {
+                found.push((id, bounding_box));
+            }
+        }
+
+        let boxes: Vec<BoundingBox> = found.iter().map(|(_, bounding_box)| *bounding_box).collect();
+        let mut step: Step = Step::new();
+
+        for ((id, from), to) in found.iter().zip(targets(&boxes)) {
+            if *from == to {
+                continue;
+            }
+
+            let transform: Transform = Transform::between(from, &to);
+
+            let change: Option<Change> = Change::transform(&mut self.view_port, id, &transform, self.text_editor.measure())
+                .expect("Can't arrange entity");
+
+            if let Some(change) = change {
+                step.push(change);
+            }
+        }
+
+        let changed: bool = !step.is_empty();
+        self.history.push(step).expect("Can't lock history to record arrangement");
+
+        Ok(changed)
+    }
+
+    /* Active tool, if the mode allows it. Selecting works in every mode, the select tool checks moves itself. */
+    fn interactive_tool(&mut self) -> Option<&mut Box<dyn Tool>> {
+        let allowed: bool = self.tool_allowed();
+
+        self.active_tool.as_mut().filter(|_| allowed)
+    }
+
+    fn tool_allowed(&self) -> bool {
+        match self.tool {
+            Some(("select", _)) => true,
+            Some((_, element_type)) => self.mode.get().allows(element_type),
+            None => false,
+        }
+    }
+
+    fn ensure_allowed(&self, element_type: Option<ElementType>) -> Result<(), BoardError> {
+        let mode: BoardMode = self.mode.get();
+
+        match mode.allows(element_type) {
+            true => Ok(()),
+            false => Err(BoardError::new(&format!("Board is in {} mode", mode.name()))),
+        }
+    }
+
+    fn ensure_allowed_all(&self, ids: &[Id]) -> Result<(), BoardError> {
+        ids.iter().try_for_each(|id| self.ensure_allowed(Some(*id.element_type())))
+    }
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+impl Whiteboard {
+    /*
+     * Call callback(payload) after the board changes. Events and their payloads:
+     * entity_added, entity_modified and entity_removed with the entity json {id, model, style},
+     * selection_changed with {ids}, tool_changed with {tool, element_type},
+     * camera_changed with {x, y, width, height} and history_changed with {can_undo, can_redo}.
+     * Returns false if there is no such event.
+     */
+    pub fn on(&mut self, event_name: &str, callback: js_sys::Function) -> bool {
+        let Some(kind) = EventKind::from_name(event_name) else {
+            return false;
+        };
+
+        self.events.on(kind, callback);
+
+        true
+    }
+
+    pub fn off(&mut self, event_name: &str, callback: &js_sys::Function) {
+        if let Some(kind) = EventKind::from_name(event_name) {
+            self.events.off(kind, callback);
+        }
+    }
+}
+
+impl Whiteboard {
+    /* Listener in Rust. Like host callbacks, it is called after the current call returns. */
+    pub fn subscribe(&self, kind: EventKind, callback: impl Fn(&BoardEvent) + 'static) {
+        self.events.subscribe(kind, callback);
+    }
+
+    /*
+     * Run work left after the calls, like delivering events or adding drawn entities.
+     * Browser runs it by itself, natively it is run after pointer and key input or when called.
+     */
+    pub fn run_pending(&self) {
+        platform::run_pending();
+    }
+
+    pub fn view_port(&self) -> &ViewPort {
+        &self.view_port
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    pub fn autosave(&self) -> Option<Autosave> {
+        self.autosave.borrow().clone()
+    }
+
+    /* Deliver the interaction like host pointer and key events do, pointer positions go through snapping and keys through modifier tracking. */
+    pub fn interact(&mut self, interaction: Interaction) {
+        match interaction {
+            Interaction::PointerDown(point, _) => self.mouse_down(point.x, point.y),
+            Interaction::PointerMove(point, _) => self.mouse_move(point.x, point.y),
+            Interaction::PointerUp(point, _) => self.mouse_up(point.x, point.y),
+            Interaction::KeyDown(key) => self.key_down(to_js_key(&key)),
+            Interaction::KeyUp(key) => self.key_up(to_js_key(&key)),
+            interaction => {
+                let Some(active_tool) = self.interactive_tool() else {
+                    return;
+                };
+
+                active_tool.interact(interaction);
+
+                platform::run_pending();
+            }
+        }
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Whiteboard {
+    pub fn set_selection(&mut self, ids: Vec<Id>) {
+        if self.selection == ids {
+            return;
+        }
+
+        self.selection = ids.clone();
+        self.events.emit(BoardEvent::SelectionChanged(ids));
+    }
+
+    pub fn get_selection(&self) -> Vec<Id> {
+        self.selection.clone()
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Whiteboard {
+    /* Board document json with all entities in z-order. */
+    pub fn to_json(&self) -> String {
+        Document::capture(&self.view_port)
+            .expect("Can't capture board")
+            .to_json()
+            .expect("Can't serialize board")
+    }
+
+    /* Replace the board with the document, history is cleared. Returns false if the json is invalid. */
+    pub fn load_json(&mut self, json: &str) -> Result<bool, BoardError> {
+        self.ensure_allowed(None)?;
+
+        let Ok(document) = Document::from_json(json) else {
+            return Ok(false);
+        };
+
+        Ok(self.load_document(document))
+    }
+
+    /* Board in the compact binary form, smaller and faster to parse than json. */
+    pub fn to_bytes(&self) -> Vec<u8> {
+        Document::capture(&self.view_port)
+            .expect("Can't capture board")
+            .to_bytes()
+            .expect("Can't serialize board")
+    }
+
+    /* Like load_json, for boards from to_bytes. */
+    pub fn load_bytes(&mut self, bytes: &[u8]) -> Result<bool, BoardError> {
+        self.ensure_allowed(None)?;
+
+        let Ok(document) = Document::from_bytes(bytes) else {
+            return Ok(false);
+        };
+
+        Ok(self.load_document(document))
+    }
+}
+
+impl Whiteboard {
+    fn load_document(&mut self, document: Document) -> bool {
+        self.text_editor.finish().expect("Can't finish text editing");
+
+        if document.restore(&mut self.view_port).is_err() {
+            return false;
+        }
+
+        self.history.clear().expect("Can't lock history to clear it");
+
+        true
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Whiteboard {
+    /*
+     * Add the elements of an Excalidraw scene to the board as one undoable step. Groups become containers.
+     * Returns the conversion report json {converted, skipped, approximated}, the last two list {id, kind, reason}
+     * of elements without an equivalent or drawn differently. None if the json isn't an Excalidraw scene.
+     */
+    pub fn import_excalidraw(&mut self, json: &str) -> Result<Option<String>, BoardError> {
+        self.ensure_allowed(None)?;
+
+        let Ok((json_entities, report)) = excalidraw::import(json, &self.owner_id, self.text_editor.measure()) else {
+            return Ok(None);
+        };
+
+        let mut step: Step = Step::new();
+
+        for json_entity in json_entities {
+            let entity: Entity<Id> = Build::from_json(json_entity.clone()).expect("Can't build imported entity");
+
+            self.view_port.add_entity(entity).expect("Can't lock view port to import entity");
+            step.push(Change::Add(json_entity));
+        }
+
+        self.history.push(step).expect("Can't lock history to record import");
+
+        Ok(Some(serde_json::to_string(&report).expect("Can't serialize report")))
+    }
+
+    /* Board as an Excalidraw scene, the content of an `.excalidraw` file. */
+    pub fn export_excalidraw(&self) -> String {
+        excalidraw::export(&Document::capture(&self.view_port).expect("Can't capture board")).0
+    }
+
+    /* Report json of export_excalidraw, in the same form as the import report. */
+    pub fn export_excalidraw_report(&self) -> String {
+        let (_, report) = excalidraw::export(&Document::capture(&self.view_port).expect("Can't capture board"));
+
+        serde_json::to_string(&report).expect("Can't serialize report")
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Whiteboard {
+    /* Keep revisions in memory only, for tests and hosts without persistent storage. */
+    pub fn enable_memory_autosave(&mut self, delay_ms: u32, max_revisions: u32) {
+        self.set_storage(Rc::new(MemoryStorage::new()), delay_ms, max_revisions);
+    }
+
+    pub fn disable_autosave(&mut self) {
+        *self.autosave.borrow_mut() = None;
+    }
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+impl Whiteboard {
+    /*
+     * Save the board to the host storage adapter delay_ms after the last change, keeping max_revisions latest revisions.
+     * Adapter has methods put(revision, saved_at, json), list(), get(revision) and delete(revision),
+     * they may return promises, e.g. when backed by IndexedDB.
+     */
+    pub fn enable_autosave(&mut self, adapter: js_sys::Object, delay_ms: u32, max_revisions: u32) {
+        self.set_storage(Rc::new(JsStorage::new(adapter)), delay_ms, max_revisions);
+    }
+
+    /* Resolves to the restored revision, undefined if there is nothing saved. History is cleared. */
+    pub fn restore_latest(&mut self) -> Result<js_sys::Promise, BoardError> {
+        self.ensure_allowed(None)?;
+
+        Ok(self.restore(None))
+    }
+
+    /* Resolves to the restored revision, undefined if there is no such revision. History is cleared. */
+    pub fn restore_revision(&mut self, revision: u32) -> Result<js_sys::Promise, BoardError> {
+        self.ensure_allowed(None)?;
+
+        Ok(self.restore(Some(revision)))
+    }
+
+    /* Resolves to [{revision, saved_at}], newest first. */
+    pub fn list_revisions(&self) -> js_sys::Promise {
+        let autosave: Option<Autosave> = self.autosave.borrow().clone();
+
+        future_to_promise(async move {
+            let Some(autosave) = autosave else {
+                return Ok(js_sys::Array::new().into());
+            };
+
+            let revisions: String = serde_json::to_string(&autosave.revisions().await.map_err(storage_error)?)
+                .map_err(|error| JsValue::from_str(&error.to_string()))?;
+
+            js_sys::JSON::parse(&revisions)
+        })
+    }
+}
+
+impl Whiteboard {
+    /* Every change of an entity is saved, also the ones not in the history like remote updates and text typing. */
+    fn save_on_change(events: &Events) -> Rc<RefCell<Option<Autosave>>> {
+        let autosave: Rc<RefCell<Option<Autosave>>> = Rc::new(RefCell::new(None));
+
+        for kind in [EventKind::EntityAdded, EventKind::EntityModified, EventKind::EntityRemoved] {
+            let scheduled_autosave: Rc<RefCell<Option<Autosave>>> = autosave.clone();
+
+            events.subscribe(kind, move |_| {
+                if let Some(autosave) = scheduled_autosave.borrow().as_ref() {
+                    autosave.schedule();
+                }
+            });
+        }
+
+        autosave
+    }
+
+    /* Replaces the previous storage. */
+    fn set_storage(&mut self, storage: Rc<dyn Storage>, delay_ms: u32, max_revisions: u32) {
+        let delay: i32 = delay_ms.min(i32::MAX as u32) as i32;
+
+        *self.autosave.borrow_mut() = Some(Autosave::new(storage, self.view_port.clone(), delay, max_revisions as usize));
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl Whiteboard {
+
+    fn restore(&mut self, revision: Option<u32>) -> js_sys::Promise {
+        self.text_editor.finish().expect("Can't finish text editing");
+
+        let autosave: Option<Autosave> = self.autosave.borrow().clone();
+        let mut history: History = self.history.clone();
+
+        future_to_promise(async move {
+            let Some(autosave) = autosave else {
+                return Ok(JsValue::UNDEFINED);
+            };
+
+            let Some(restored) = autosave.restore(revision).await.map_err(storage_error)? else {
+                return Ok(JsValue::UNDEFINED);
+            };
+
+            history.clear().map_err(|_| JsValue::from_str("Can't lock history to clear it"))?;
+
+            Ok(JsValue::from(restored))
+        })
+    }
+}
+
+#[cfg(feature = "wasm")]
+fn storage_error(error: StorageError) -> JsValue {
+    match error {
+        StorageError::Host(error) => error,
+        error => JsValue::from_str(&format!("{error:?}")),
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Whiteboard {
+    /*
+     * Merge concurrent edits of peers identified by their owner ids.
+     * Peers exchange binary updates through any relay, in any order, and converge to the same board.
+     * Entities already on the board are included in the first local update.
+     */
+    pub fn enable_collaboration(&mut self) {
+        self.view_port
+            .enable_replica(&self.owner_id)
+            .expect("Can't lock view port to enable collaboration");
+
+        for (owner_id, mode) in &self.owner_modes {
+            self.view_port.with_replica(|replica| replica.set_peer_mode(owner_id, *mode));
+        }
+    }
+
+    /* Local changes since the last call, empty if there are none or collaboration is disabled. */
+    pub fn take_local_update(&mut self) -> Vec<u8> {
+        self.view_port
+            .with_replica(|replica| replica.take_outgoing().expect("Can't encode update"))
+            .unwrap_or_default()
+    }
+
+    /* Summary of the received changes, a peer answers it with encode_update. */
+    pub fn encode_state_vector(&self) -> Vec<u8> {
+        self.view_port
+            .with_replica(|replica| replica.encode_state_vector().expect("Can't encode state vector"))
+            .unwrap_or_default()
+    }
+
+    /* Changes missing at the peer with the state vector, all changes for an empty one. None if the state vector is invalid. */
+    pub fn encode_update(&self, state_vector: &[u8]) -> Option<Vec<u8>> {
+        let state_vector: StateVector = match state_vector.is_empty() {
+            true => StateVector::new(),
+            false => crdt::decode_state_vector(state_vector).ok()?,
+        };
+
+        self.view_port
+            .with_replica(|replica| replica.encode_update(&state_vector).expect("Can't encode update"))
+    }
+
+    /* Returns false if the update is invalid or collaboration is disabled. */
+    pub fn apply_update(&mut self, update: &[u8]) -> bool {
+        let text_editor: TextEditor = self.text_editor.clone();
+
+        self.view_port
+            .apply_update(update, text_editor.measure())
+            .unwrap_or(false)
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Whiteboard {
+    /*
+     * Show what the collaborator is doing, presence json is the one returned by their local_presence.
+     * Returns false if the json is invalid or the owner is the local one.
+     */
+    pub fn update_presence(&mut self, owner_id: &str, presence_json: &str) -> bool {
+        if owner_id == self.owner_id {
+            return false;
+        }
+
+        let Ok(presence) = serde_json::from_str::<Presence>(presence_json) else {
+            return false;
+        };
+
+        self.presences.update(owner_id, presence);
+
+        true
+    }
+
+    /* Hide the collaborator, e.g. after they disconnect. */
+    pub fn remove_presence(&mut self, owner_id: &str) {
+        self.presences.remove(owner_id);
+    }
+
+    /* Pointer position, selection, active tool and the stroke being drawn, as json to send to collaborators. */
+    pub fn local_presence(&self) -> String {
+        let presence: Presence = Presence {
+            pointer: self.pointer.map(|point| [point.x, point.y]),
+            selection: self.selection.clone(),
+            tool: self.tool.map(|(name, _)| name.to_string()),
+            element_type: self.tool.and_then(|(_, element_type)| element_type),
+            stroke: self.stroke.iter().map(|point| [point.x, point.y]).collect(),
+        };
+
+        serde_json::to_string(&presence).expect("Can't serialize presence")
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Whiteboard {
+    /* Applies to frame tools activated after the call. */
+    pub fn set_frame_page_size(&mut self, page_size: PageSize) {
+        self.frame.page_size = page_size;
+        self.frame.resize(self.frame.width, self.frame.height);
+    }
+
+    /* Name shown above the frame, empty string shows the default one. Returns false if there is no such frame. */
+    pub fn rename_frame(&mut self, id: &Id, name: &str) -> Result<bool, BoardError> {
+        self.ensure_allowed(Some(ElementType::Frame))?;
+
+        if *id.element_type() != ElementType::Frame {
+            return Ok(false);
+        }
+
+        let change: Option<Change> = Change::modify(&mut self.view_port, id, |model: &mut FrameModel| model.name = name.to_string())
+            .expect("Can't rename frame");
+
+        let Some(change) = change else {
+            return Ok(false);
+        };
+
+        self.history.push(Step::from(change)).expect("Can't lock history to record frame name");
+
+        Ok(true)
+    }
+
+    /* Frames in presentation order. New frames are appended. */
+    pub fn get_frames(&self) -> Vec<Id> {
+        self.view_port.frames().expect("Can't lock view port to list frames")
+    }
+
+    /* Frames missing in the order follow the listed ones. */
+    pub fn set_frame_order(&mut self, ids: Vec<Id>) -> Result<(), BoardError> {
+        self.ensure_allowed(Some(ElementType::Frame))?;
+
+        self.view_port.set_frame_order(&ids).expect("Can't lock view port to order frames");
+
+        Ok(())
+    }
+
+    /* Entities with their center inside the frame, in z-order. */
+    pub fn frame_contents(&self, id: &Id) -> Vec<Id> {
+        self.view_port.frame_contents(id).expect("Can't lock view port to list frame contents")
+    }
+
+    /* Move the frame together with its content, recorded as one step. Returns false if nothing moved. */
+    pub fn move_frame(&mut self, id: &Id, dx: f64, dy: f64) -> Result<bool, BoardError> {
+        let mut ids: Vec<Id> = self.frame_contents(id);
+        ids.insert(0, id.clone());
+
+        self.arrange(&ids, |boxes| {
+            boxes
+                .iter()
+                .map(|bounding_box| BoundingBox::new(bounding_box.left + dx, bounding_box.top + dy, bounding_box.width(), bounding_box.height()))
+                .collect()
+        })
+    }
+
+    /* Animate the view box to the next frame, the first one when presenting starts. Returns the frame, None at the end. */
+    pub fn present_next(&mut self) -> Option<Id> {
+        self.presentation.next(&mut self.view_port).expect("Can't lock view port to present")
+    }
+
+    /* Returns the frame, None at the start. */
+    pub fn present_previous(&mut self) -> Option<Id> {
+        self.presentation.previous(&mut self.view_port).expect("Can't lock view port to present")
+    }
+
+    /* Returns false if there is no such frame. */
+    pub fn present_frame(&mut self, id: &Id) -> bool {
+        self.presentation.show(&mut self.view_port, id).expect("Can't lock view port to present")
+    }
+
+    /* Next present_next starts from the first frame again. */
+    pub fn stop_presenting(&mut self) {
+        self.presentation.stop();
     }
 }
 
+#[cfg(feature = "dom-renderers")]
 #[wasm_bindgen]
+impl Whiteboard {
+    /* Standalone svg document of the frame and its content. None if there is no such frame. */
+    pub fn export_frame_svg(&self, id: &Id, scale: f64) -> Option<String> {
+        export::frame_svg(&self.view_port, id, scale).expect("Can't lock view port to export frame")
+    }
+
+    /* Data url of the frame as png, scale is pixels per board unit. */
+    pub fn export_frame_png(&self, id: &Id, scale: f64) -> Option<String> {
+        export::frame_raster(&self.view_port, id, scale, "image/png").expect("Can't lock view port to export frame")
+    }
+
+    /* Single page pdf of the frame, the page has the frame size. */
+    pub fn export_frame_pdf(&self, id: &Id, scale: f64) -> Option<Vec<u8>> {
+        self.view_port.frame_box(id).expect("Can't lock view port to export frame")?;
+
+        Some(export::frames_pdf(&self.view_port, std::slice::from_ref(id), scale).expect("Can't lock view port to export frame"))
+    }
+
+    /* Every frame as a page, in presentation order. */
+    pub fn export_presentation_pdf(&self, scale: f64) -> Vec<u8> {
+        export::frames_pdf(&self.view_port, &self.get_frames(), scale).expect("Can't lock view port to export presentation")
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Whiteboard {
+    /*
+     * Limit what can be changed on the board. Viewing, panning, zooming and presence work in every mode.
+     * Refused calls throw. Active tool is dropped if the mode doesn't allow it.
+     */
+    pub fn set_mode(&mut self, mode: BoardMode) {
+        self.mode.set(mode);
+        self.text_editor.finish().expect("Can't finish text editing");
+
+        if self.active_tool.is_some() && !self.tool_allowed() {
+            self.active_tool = None;
+            self.tool = None;
+            self.stroke.clear();
+            self.events.emit(BoardEvent::ToolChanged { tool: "none", element_type: None });
+        }
+    }
+
+    pub fn get_mode(&self) -> BoardMode {
+        self.mode.get()
+    }
+
+    /*
+     * Mode enforced on remote changes of the owner, edit by default. Changes the mode doesn't allow are relayed
+     * to other peers but never merged into this board, changing the mode later doesn't bring them back.
+     */
+    pub fn set_owner_mode(&mut self, owner_id: &str, mode: BoardMode) {
+        self.owner_modes.insert(owner_id.to_string(), mode);
+        self.view_port.with_replica(|replica| replica.set_peer_mode(owner_id, mode));
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Whiteboard {
+    /* Returns false if there is nothing to undo. */
+    pub fn undo(&mut self) -> Result<bool, BoardError> {
+        self.ensure_allowed(None)?;
+
+        Ok(self.history
+            .undo(&mut self.view_port)
+            .expect("Can't undo last step"))
+    }
+
+    /* Returns false if there is nothing to redo. */
+    pub fn redo(&mut self) -> Result<bool, BoardError> {
+        self.ensure_allowed(None)?;
+
+        Ok(self.history
+            .redo(&mut self.view_port)
+            .expect("Can't redo last step"))
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl Whiteboard {
     pub fn mouse_down(&mut self, x: f64, y: f64) {
-        let Some(active_tool) = &mut self.active_tool else {
+        let point: Point2D = self.snapping
+            .pointer_down(Point2D::new(x, y), &self.view_port, self.modifiers.alt())
+            .expect("Can't lock view port to snap pointer");
+
+        self.pointer = Some(point);
+        if self.tool == Some(("move_draw", Some(ElementType::FreeHand))) {
+            self.stroke = vec![point];
+        }
+
+        let Some(active_tool) = self.interactive_tool() else {
             return;
         };
 
         active_tool.interact(
             Interaction::PointerDown(
-                (x, y).into(),
+                point,
                 PointingDevice::Mouse,
             )
         );
+
+        platform::run_pending();
     }
 
     pub fn mouse_move(&mut self, x: f64, y: f64) {
-        let Some(active_tool) = &mut self.active_tool else {
+        let point: Point2D = self.snapping
+            .pointer_move(Point2D::new(x, y), &self.view_port, self.modifiers.alt())
+            .expect("Can't lock view port to snap pointer");
+
+        self.pointer = Some(point);
+        if !self.stroke.is_empty() {
+            self.stroke.push(point);
+        }
+
+        let Some(active_tool) = self.interactive_tool() else {
             return;
         };
 
         active_tool.interact(
             Interaction::PointerMove(
-                (x, y).into(),
+                point,
                 PointingDevice::Mouse,
             )
         );
+
+        platform::run_pending();
     }
 
     pub fn mouse_up(&mut self, x: f64, y: f64) {
-        let Some(active_tool) = &mut self.active_tool else {
+        let point: Point2D = self.snapping
+            .pointer_up(Point2D::new(x, y), &self.view_port, self.modifiers.alt())
+            .expect("Can't lock view port to snap pointer");
+
+        self.pointer = Some(point);
+        self.stroke.clear();
+
+        let Some(active_tool) = self.interactive_tool() else {
             return;
         };
 
         active_tool.interact(
             Interaction::PointerUp(
-                Point2D::new(x, y),
+                point,
                 PointingDevice::Mouse,
             )
         );
+
+        platform::run_pending();
     }
 
     pub fn key_down(&mut self, key: &str) {
+        match key {
+            "Shift" => {
+                self.modifiers.set_shift(true);
+                self.text_editor.set_extend_selection(true);
+            }
+            "Alt" => self.modifiers.set_alt(true),
+            _ => {}
+        }
+
         let Some(key) = from_js_key(key) else {
             return;
         };
 
-        let Some(active_tool) = &mut self.active_tool else {
+        let Some(active_tool) = self.interactive_tool() else {
             return;
         };
 
         active_tool.interact(Interaction::KeyDown(key));
+
+        platform::run_pending();
     }
 
     pub fn key_up(&mut self, key: &str) {
+        match key {
+            "Shift" => {
+                self.modifiers.set_shift(false);
+                self.text_editor.set_extend_selection(false);
+            }
+            "Alt" => self.modifiers.set_alt(false),
+            _ => {}
+        }
+
         let Some(key) = from_js_key(key) else {
             return;
         };
 
-        let Some(active_tool) = &mut self.active_tool else {
+        let Some(active_tool) = self.interactive_tool() else {
             return;
         };
 
         active_tool.interact(Interaction::KeyUp(key));
+
+        platform::run_pending();
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Whiteboard {
+    /* Background grid is drawn over the view box, so it is shown only after the view box is set. */
+    pub fn set_grid(&mut self, kind: GridKind, spacing: f64, visible: bool) {
+        self.snapping.grid = Grid {
+            kind,
+            spacing,
+            visible,
+        };
+    }
+
+    /*
+     * Choose what the pointer snaps to. Entity edges and centres take precedence over the grid.
+     * Direction snaps to angle step while Alt is held, zero step disables it.
+     */
+    pub fn set_snapping(&mut self, to_grid: bool, to_entities: bool, angle_step_degrees: f64) {
+        self.snapping.to_grid = to_grid;
+        self.snapping.to_entities = to_entities;
+        self.snapping.angle_step = angle_step_degrees.max(0.0).to_radians();
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Whiteboard {
+    /* Set visible area of the board. Entities outside of it are skipped while rendering. */
+    pub fn set_view_box(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.presentation.interrupt();
+        self.view_port
+            .set_view_box(Some(BoundingBox::new(x, y, width, height)))
+            .expect("Can't lock view port to set view box");
+    }
+
+    pub fn entities_at(&self, x: f64, y: f64) -> Vec<Id> {
+        self.view_port
+            .entities_at(x, y)
+            .expect("Can't lock view port to query entities")
+    }
+
+    pub fn entities_in(&self, x: f64, y: f64, width: f64, height: f64) -> Vec<Id> {
+        self.view_port
+            .entities_in(&BoundingBox::new(x, y, width, height))
+            .expect("Can't lock view port to query entities")
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Whiteboard {
+    /* Start editing the text box. Returns false if the entity is not a text box. */
+    pub fn edit_text(&mut self, id: &Id) -> Result<bool, BoardError> {
+        self.activate_text_tool()?;
+
+        Ok(self.text_editor.begin(id).expect("Can't start text editing"))
+    }
+
+    /*
+     * Insert text at the caret of the edited text box, replacing selection.
+     * Hosts should pass both typed characters and IME composition results here.
+     */
+    pub fn text_input(&mut self, text: &str) -> Result<(), BoardError> {
+        self.ensure_allowed(Some(ElementType::Text))?;
+
+        self.text_editor.insert(text).expect("Can't insert text");
+
+        Ok(())
+    }
+
+    /* Select chars in range start..end of the edited text box. */
+    pub fn select_text(&mut self, start: usize, end: usize) -> Result<(), BoardError> {
+        self.ensure_allowed(Some(ElementType::Text))?;
+
+        self.text_editor.select(start, end).expect("Can't select text");
+
+        Ok(())
+    }
+
+    pub fn set_text_font(&mut self, family: &str, size: f64, weight: u16) -> Result<(), BoardError> {
+        self.ensure_allowed(Some(ElementType::Text))?;
+
+        self.text_editor
+            .set_font(Font::new(family, size, weight))
+            .expect("Can't set font");
+
+        Ok(())
+    }
+
+    pub fn finish_text_editing(&mut self) {
+        self.text_editor.finish().expect("Can't finish text editing");
     }
 }
 
+#[cfg(feature = "dom-renderers")]
 #[wasm_bindgen]
 impl Whiteboard {
     pub fn render_canvas(&self, renderer: &mut CanvasRenderer) {
@@ -170,12 +1531,61 @@ impl Whiteboard {
         self.render(renderer);
     }
 
-    fn render(&self, renderer: &mut dyn Renderer) {
+    /* Thumbnail of the whole board with the current view box outlined, drawn in canvas pixels. */
+    pub fn render_minimap(&self, renderer: &mut CanvasRenderer, width: f64, height: f64) {
+        self.render_minimap_with(renderer, width, height);
+    }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Whiteboard {
+    /*
+     * Center the view box at the board point under the minimap point, keeping the view box size.
+     * Returns the new view box as [x, y, width, height] for the host camera, None if there is no view box.
+     */
+    pub fn jump_to_minimap_point(&mut self, x: f64, y: f64, width: f64, height: f64) -> Option<Vec<f64>> {
+        let view_box: BoundingBox = self.view_port.view_box().expect("Can't lock view port to jump")?;
+        let minimap: Minimap = Minimap::new(&self.view_port, width, height).expect("Can't lock view port to jump")?;
+
+        let center: Point2D = minimap.to_board(&Point2D::new(x, y));
+        let left: f64 = center.x - view_box.width() / 2.0;
+        let top: f64 = center.y - view_box.height() / 2.0;
+
+        self.set_view_box(left, top, view_box.width(), view_box.height());
+
+        Some(vec![left, top, view_box.width(), view_box.height()])
+    }
+}
+
+impl Whiteboard {
+    /* Board with grid, remote presence, snapping guides and the active tool overlay. */
+    pub fn render(&self, renderer: &mut dyn BoardRenderer) {
         renderer.clear();
+
+        if let Some(view_box) = self.view_port.view_box().expect("Can't lock view port to render grid") {
+            self.snapping.grid.render(&view_box, renderer);
+        }
+
         self.view_port.render(renderer);
+        self.presences
+            .render(&self.view_port, renderer)
+            .expect("Can't lock view port to render presence");
+        self.snapping.render(renderer);
 
         if let Some(active_tool) = &self.active_tool {
             active_tool.render(renderer);
         }
     }
+
+    pub fn render_minimap_with(&self, renderer: &mut dyn BoardRenderer, width: f64, height: f64) {
+        renderer.clear();
+
+        let Some(minimap) = Minimap::new(&self.view_port, width, height).expect("Can't lock view port to render minimap") else {
+            return;
+        };
+
+        minimap
+            .render(&self.view_port, width, height, renderer)
+            .expect("Can't lock view port to render minimap");
+    }
 }
@@ -0,0 +1,52 @@
+use geometry::point::point_2d::Point2D;
+
+pub fn distance(a: &Point2D, b: &Point2D) -> f64 {
+    (b.x - a.x).hypot(b.y - a.y)
+}
+
+/* Point at the parameter t of the segment, start at 0 and end at 1. */
+pub fn lerp(start: &Point2D, end: &Point2D, t: f64) -> Point2D {
+    Point2D::new(start.x + (end.x - start.x) * t, start.y + (end.y - start.y) * t)
+}
+
+/* Closest point to the point on the segment from start to end. */
+pub fn project_on_segment(point: &Point2D, start: &Point2D, end: &Point2D) -> Point2D {
+    let (dx, dy) = (end.x - start.x, end.y - start.y);
+    let length_squared: f64 = dx * dx + dy * dy;
+
+    if length_squared == 0.0 {
+        return Point2D::new(start.x, start.y);
+    }
+
+    let t: f64 = (((point.x - start.x) * dx + (point.y - start.y) * dy) / length_squared).clamp(0.0, 1.0);
+
+    Point2D::new(start.x + t * dx, start.y + t * dy)
+}
+
+pub fn distance_to_segment(point: &Point2D, start: &Point2D, end: &Point2D) -> f64 {
+    distance(point, &project_on_segment(point, start, end))
+}
+
+pub fn segments_intersect(a_start: &Point2D, a_end: &Point2D, b_start: &Point2D, b_end: &Point2D) -> bool {
+    let orientation = |p: &Point2D, q: &Point2D, r: &Point2D| -> f64 {
+        (q.x - p.x) * (r.y - p.y) - (q.y - p.y) * (r.x - p.x)
+    };
+
+    let d1: f64 = orientation(b_start, b_end, a_start);
+    let d2: f64 = orientation(b_start, b_end, a_end);
+    let d3: f64 = orientation(a_start, a_end, b_start);
+    let d4: f64 = orientation(a_start, a_end, b_end);
+
+    (d1 * d2 < 0.0) && (d3 * d4 < 0.0)
+}
+
+pub fn distance_between_segments(a_start: &Point2D, a_end: &Point2D, b_start: &Point2D, b_end: &Point2D) -> f64 {
+    if segments_intersect(a_start, a_end, b_start, b_end) {
+        return 0.0;
+    }
+
+    distance_to_segment(a_start, b_start, b_end)
+        .min(distance_to_segment(a_end, b_start, b_end))
+        .min(distance_to_segment(b_start, a_start, a_end))
+        .min(distance_to_segment(b_end, a_start, a_end))
+}
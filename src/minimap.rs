@@ -0,0 +1,154 @@
+use crate::bounding_box::BoundingBox;
+use crate::element::free_hand;
+use crate::element::id::Id;
+use crate::element::{model_of, ElementType};
+use crate::renderer::board_renderer::BoardRenderer;
+use crate::style::Style;
+use crate::view_port::{LockError, ViewPort};
+use entity_model_feature::entity::Entity;
+use geometry::figure::path::command::line_to::LineTo;
+use geometry::figure::path::command::move_to::MoveTo;
+use geometry::figure::path::command::Command;
+use geometry::figure::path::Path;
+use geometry::point::point_2d::Point2D;
+use standard_entity_plugin::model::path_model::PathModel;
+use standard_rendering_plugin::style::shape_style::ShapeStyle;
+
+const BACKGROUND_COLOR: &str = "#f5f5f5";
+const ENTITY_COLOR: &str = "#b0bec5";
+const CAMERA_COLOR: &str = "#1e88e5";
+const CAMERA_FILL_COLOR: &str = "rgba(30, 136, 229, 0.1)";
+/* Space around the content in minimap pixels. */
+const PADDING: f64 = 8.0;
+/* Freehand strokes are drawn with at most this many points. */
+const MAX_STROKE_POINTS: usize = 16;
+/* Boxes smaller than this are enlarged so tiny entities stay visible. */
+const MIN_BOX_SIZE: f64 = 2.0;
+
+/*
+ * Thumbnail of the whole board fitted into the minimap size.
+ * Content covers all entities and the camera, so the camera rectangle is always visible.
+ */
+pub struct Minimap {
+    scale: f64,
+    offset_x: f64,
+    offset_y: f64,
+}
+
+impl Minimap {
+    /* None if there is nothing to show. */
+    pub fn new(view_port: &ViewPort, width: f64, height: f64) -> Result<Option<Self>, LockError> {
+        let content: Option<BoundingBox> = match (view_port.extent()?, view_port.view_box()?) {
+            (Some(extent), Some(view_box)) => Some(extent.union(&view_box)),
+            (extent, view_box) => extent.or(view_box),
+        };
+
+        let Some(content) = content else {
+            return Ok(None);
+        };
+
+        let available_width: f64 = (width - 2.0 * PADDING).max(1.0);
+        let available_height: f64 = (height - 2.0 * PADDING).max(1.0);
+        let scale: f64 = (available_width / content.width().max(1.0)).min(available_height / content.height().max(1.0));
+
+        /* Content is centered in the minimap. */
+        Ok(Some(Self {
+            scale,
+            offset_x: (width - content.width() * scale) / 2.0 - content.left * scale,
+            offset_y: (height - content.height() * scale) / 2.0 - content.top * scale,
+        }))
+    }
+
+    pub fn to_minimap(&self, point: &Point2D) -> Point2D {
+        Point2D::new(point.x * self.scale + self.offset_x, point.y * self.scale + self.offset_y)
+    }
+
+    pub fn to_board(&self, point: &Point2D) -> Point2D {
+        Point2D::new((point.x - self.offset_x) / self.scale, (point.y - self.offset_y) / self.scale)
+    }
+
+    fn box_to_minimap(&self, bounding_box: &BoundingBox) -> BoundingBox {
+        let top_left: Point2D = self.to_minimap(&Point2D::new(bounding_box.left, bounding_box.top));
+
+        BoundingBox::new(
+            top_left.x,
+            top_left.y,
+            (bounding_box.width() * self.scale).max(MIN_BOX_SIZE),
+            (bounding_box.height() * self.scale).max(MIN_BOX_SIZE),
+        )
+    }
+
+    /* Draw entities as boxes, freehand strokes as decimated polylines, and the camera rectangle on top. */
+    pub fn render(&self, view_port: &ViewPort, width: f64, height: f64, renderer: &mut dyn BoardRenderer) -> Result<(), LockError> {
+        renderer.fill_rectangle(&BoundingBox::new(0.0, 0.0, width, height), BACKGROUND_COLOR);
+
+        view_port.for_each_entity(|entity, bounding_box| {
+            if let Some(stroke) = self.stroke(entity) {
+                let style: Style = Style {
+                    stroke_color: ENTITY_COLOR.to_string(),
+                    ..Style::default()
+                };
+
+                renderer.set_style(Some(&style));
+                renderer.path(&stroke, &ShapeStyle::default(), None);
+                renderer.set_style(None);
+            } else {
+                renderer.fill_rectangle(&self.box_to_minimap(bounding_box), ENTITY_COLOR);
+            }
+        })?;
+
+        if let Some(view_box) = view_port.view_box()? {
+            let camera: BoundingBox = self.box_to_minimap(&view_box);
+
+            let corners: [Point2D; 5] = [
+                Point2D::new(camera.left, camera.top),
+                Point2D::new(camera.right, camera.top),
+                Point2D::new(camera.right, camera.bottom),
+                Point2D::new(camera.left, camera.bottom),
+                Point2D::new(camera.left, camera.top),
+            ];
+
+            let style: Style = Style {
+                fill_color: Some(CAMERA_FILL_COLOR.to_string()),
+                stroke_color: CAMERA_COLOR.to_string(),
+                stroke_width: 1.5,
+                ..Style::default()
+            };
+
+            renderer.set_style(Some(&style));
+            renderer.path(&Self::polyline(&corners), &ShapeStyle::default(), None);
+            renderer.set_style(None);
+        }
+
+        Ok(())
+    }
+
+    fn stroke(&self, entity: &Entity<Id>) -> Option<Path> {
+        if *entity.id().element_type() != ElementType::FreeHand {
+            return None;
+        }
+
+        let points: Vec<Point2D> = free_hand::points(model_of::<PathModel>(entity)?);
+        let step: usize = points.len().div_ceil(MAX_STROKE_POINTS).max(1);
+
+        let mut decimated: Vec<Point2D> = points.iter().step_by(step).map(|point| self.to_minimap(point)).collect();
+        if let Some(last) = points.last().filter(|_| (points.len() - 1) % step != 0) {
+            decimated.push(self.to_minimap(last));
+        }
+
+        (decimated.len() > 1).then(|| Self::polyline(&decimated))
+    }
+
+    fn polyline(points: &[Point2D]) -> Path {
+        let commands: Vec<Command> = points
+            .iter()
+            .enumerate()
+            .map(|(index, point)| match index {
+                0 => Command::MoveTo(MoveTo { to_point: *point }),
+                _ => Command::LineTo(LineTo { to_point: *point }),
+            })
+            .collect();
+
+        Path::new(commands)
+    }
+}
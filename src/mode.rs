@@ -0,0 +1,58 @@
+use crate::element::ElementType;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[derive(Copy, Clone, Serialize, Deserialize, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub enum BoardMode {
+    #[default]
+    Edit,
+    /* Only sticky notes can be added and changed. */
+    Comment,
+    /* Board can be viewed, panned and zoomed, nothing is changed. */
+    ReadOnly,
+}
+
+impl BoardMode {
+    /* Whether entities of the type can be changed. None stands for changes not tied to one element type, like undo. */
+    pub fn allows(&self, element_type: Option<ElementType>) -> bool {
+        match self {
+            BoardMode::Edit => true,
+            BoardMode::Comment => element_type == Some(ElementType::StickyNote),
+            BoardMode::ReadOnly => false,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BoardMode::Edit => "edit",
+            BoardMode::Comment => "comment",
+            BoardMode::ReadOnly => "read-only",
+        }
+    }
+}
+
+/* Mode of the board shared with its tools, so they check the mode in effect when they change something. */
+#[derive(Clone, Default)]
+pub struct CurrentMode {
+    mode: Arc<RwLock<BoardMode>>,
+}
+
+impl CurrentMode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /* Board that can't be read is read-only. */
+    pub fn get(&self) -> BoardMode {
+        self.mode.read().map(|mode| *mode).unwrap_or(BoardMode::ReadOnly)
+    }
+
+    pub fn set(&self, mode: BoardMode) {
+        if let Ok(mut current_mode) = self.mode.write() {
+            *current_mode = mode;
+        }
+    }
+}
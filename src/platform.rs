@@ -0,0 +1,15 @@
+/*
+ * What the board needs from its environment: clock, timers and a single threaded executor.
+ * In the browser spawned tasks run after the current call returns,
+ * natively they run when run_pending is called, along with the timers that are due.
+ * The runtime follows the target, so native tests of the wasm build run the native one.
+ */
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+mod browser;
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+mod native;
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub use browser::{now, run_pending, sleep, spawn_local};
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+pub use native::{now, run_pending, sleep, spawn_local};
@@ -0,0 +1,25 @@
+use js_sys::Promise;
+use std::future::Future;
+use wasm_bindgen_futures::JsFuture;
+
+/* Milliseconds since the unix epoch. */
+pub fn now() -> f64 {
+    js_sys::Date::now()
+}
+
+pub fn spawn_local(future: impl Future<Output = ()> + 'static) {
+    wasm_bindgen_futures::spawn_local(future);
+}
+
+/* Browser event loop runs spawned tasks itself. */
+pub fn run_pending() {}
+
+pub async fn sleep(milliseconds: i32) {
+    let promise: Promise = Promise::new(&mut |resolve, _| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, milliseconds);
+        }
+    });
+
+    let _ = JsFuture::from(promise).await;
+}
@@ -0,0 +1,87 @@
+use futures::executor::{LocalPool, LocalSpawner};
+use futures::task::LocalSpawnExt;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+thread_local! {
+    static POOL: RefCell<LocalPool> = RefCell::new(LocalPool::new());
+    static SPAWNER: LocalSpawner = POOL.with(|pool| pool.borrow().spawner());
+    /* Sleeping tasks with their deadlines, woken by run_pending. */
+    static TIMERS: RefCell<Vec<(f64, Waker)>> = const { RefCell::new(vec![]) };
+}
+
+/* Milliseconds since the unix epoch. */
+pub fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |duration| duration.as_secs_f64() * 1000.0)
+}
+
+pub fn spawn_local(future: impl Future<Output = ()> + 'static) {
+    SPAWNER.with(|spawner| {
+        spawner.spawn_local(future).expect("Can't spawn task on the local pool");
+    });
+}
+
+/*
+ * Run spawned tasks until all of them wait. Tasks spawned by a running task are picked up by the same run,
+ * so are sleeping tasks whose deadline has passed.
+ */
+pub fn run_pending() {
+    POOL.with(|pool| {
+        let Ok(mut pool) = pool.try_borrow_mut() else {
+            return;
+        };
+
+        loop {
+            pool.run_until_stalled();
+
+            if !wake_timers() {
+                return;
+            }
+        }
+    });
+}
+
+/* Completes on the first run_pending after the delay. */
+pub async fn sleep(milliseconds: i32) {
+    Sleep { deadline: now() + milliseconds.max(0) as f64 }.await
+}
+
+struct Sleep {
+    deadline: f64,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<()> {
+        if now() >= self.deadline {
+            return Poll::Ready(());
+        }
+
+        TIMERS.with(|timers| timers.borrow_mut().push((self.deadline, context.waker().clone())));
+
+        Poll::Pending
+    }
+}
+
+/* Returns whether any timer was due. */
+fn wake_timers() -> bool {
+    let now: f64 = now();
+    let due: Vec<Waker> = TIMERS.with(|timers| {
+        let mut timers = timers.borrow_mut();
+        let (due, waiting): (Vec<(f64, Waker)>, Vec<(f64, Waker)>) = timers.drain(..).partition(|(deadline, _)| *deadline <= now);
+        *timers = waiting;
+
+        due.into_iter().map(|(_, waker)| waker).collect()
+    });
+
+    let woken: bool = !due.is_empty();
+    due.into_iter().for_each(Waker::wake);
+
+    woken
+}
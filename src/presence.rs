@@ -0,0 +1,165 @@
+use crate::bounding_box::BoundingBox;
+use crate::element::id::Id;
+use crate::element::ElementType;
+use crate::renderer::board_renderer::BoardRenderer;
+use crate::style::Style;
+use crate::text::font::Font;
+use crate::text::layout::TextLine;
+use crate::view_port::{LockError, ViewPort};
+use geometry::figure::path::command::line_to::LineTo;
+use geometry::figure::path::command::move_to::MoveTo;
+use geometry::figure::path::command::Command;
+use geometry::figure::path::Path;
+use geometry::figure::polygon::Polygon;
+use geometry::point::point_2d::Point2D;
+use serde::{Deserialize, Serialize};
+use standard_rendering_plugin::style::shape_style::ShapeStyle;
+use std::collections::HashMap;
+
+const COLORS: [&str; 8] = ["#e53935", "#8e24aa", "#3949ab", "#039be5", "#00897b", "#7cb342", "#fb8c00", "#6d4c41"];
+const CURSOR_SIZE: f64 = 16.0;
+const SELECTION_PADDING: f64 = 4.0;
+const LABEL_FONT_SIZE: f64 = 12.0;
+
+/* What a collaborator is doing right now. Positions are in board units. */
+#[derive(Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct Presence {
+    pub pointer: Option<[f64; 2]>,
+    pub selection: Vec<Id>,
+    pub tool: Option<String>,
+    pub element_type: Option<ElementType>,
+    /* Points of the stroke being drawn. */
+    pub stroke: Vec<[f64; 2]>,
+}
+
+/* Same colour for the owner on every peer. */
+pub fn owner_color(owner_id: &str) -> &'static str {
+    /* FNV-1a, stable unlike the std hasher. */
+    let hash: u32 = owner_id
+        .bytes()
+        .fold(0x811c9dc5, |hash, byte| (hash ^ byte as u32).wrapping_mul(0x01000193));
+
+    COLORS[hash as usize % COLORS.len()]
+}
+
+/* Presences of remote collaborators by owner id, drawn over the board. */
+#[derive(Default)]
+pub struct Presences {
+    remote: HashMap<String, Presence>,
+}
+
+impl Presences {
+    pub fn update(&mut self, owner_id: &str, presence: Presence) {
+        self.remote.insert(owner_id.to_string(), presence);
+    }
+
+    pub fn remove(&mut self, owner_id: &str) {
+        self.remote.remove(owner_id);
+    }
+
+    /* Selection outlines and strokes first, so cursors stay on top. */
+    pub fn render(&self, view_port: &ViewPort, renderer: &mut dyn BoardRenderer) -> Result<(), LockError> {
+        for (owner_id, presence) in &self.remote {
+            let color: &str = owner_color(owner_id);
+
+            let selection: Vec<BoundingBox> = presence.selection
+                .iter()
+                .map(|id| view_port.bounding_box(id))
+                .collect::<Result<Vec<Option<BoundingBox>>, LockError>>()?
+                .into_iter()
+                .flatten()
+                .collect();
+
+            let outlines: Vec<Command> = selection
+                .iter()
+                .map(|bounding_box| bounding_box.inflate(SELECTION_PADDING))
+                .flat_map(|outline| {
+                    let corners: [Point2D; 5] = [
+                        Point2D::new(outline.left, outline.top),
+                        Point2D::new(outline.right, outline.top),
+                        Point2D::new(outline.right, outline.bottom),
+                        Point2D::new(outline.left, outline.bottom),
+                        Point2D::new(outline.left, outline.top),
+                    ];
+
+                    Self::polyline(&corners)
+                })
+                .collect();
+
+            Self::stroke(renderer, Path::new(outlines), color, vec![6.0, 4.0]);
+
+            let stroke: Vec<Point2D> = presence.stroke.iter().map(|[x, y]| Point2D::new(*x, *y)).collect();
+            Self::stroke(renderer, Path::new(Self::polyline(&stroke)), color, vec![]);
+        }
+
+        for (owner_id, presence) in &self.remote {
+            if let Some([x, y]) = presence.pointer {
+                Self::cursor(renderer, owner_id, x, y);
+            }
+        }
+
+        Ok(())
+    }
+
+    /* Arrow pointing to the position, labeled with the owner id. */
+    fn cursor(renderer: &mut dyn BoardRenderer, owner_id: &str, x: f64, y: f64) {
+        let color: &str = owner_color(owner_id);
+
+        let arrow: Polygon<Point2D> = Polygon::new(vec![
+            Point2D::new(x, y),
+            Point2D::new(x, y + CURSOR_SIZE),
+            Point2D::new(x + CURSOR_SIZE * 0.3, y + CURSOR_SIZE * 0.75),
+            Point2D::new(x + CURSOR_SIZE * 0.7, y + CURSOR_SIZE * 0.7),
+        ]);
+
+        let style: Style = Style {
+            fill_color: Some(color.to_string()),
+            stroke_color: "#ffffff".to_string(),
+            ..Style::default()
+        };
+
+        renderer.set_style(Some(&style));
+        renderer.polygon_2d(&arrow, &ShapeStyle::default(), None);
+        renderer.set_style(None);
+
+        let font: Font = Font::new("sans-serif", LABEL_FONT_SIZE, 400);
+        let label: TextLine = TextLine {
+            text: owner_id.to_string(),
+            start: 0,
+            x: x + CURSOR_SIZE * 0.7,
+            y: y + CURSOR_SIZE * 0.7,
+            width: 0.0,
+        };
+
+        renderer.text(&label, &font, color);
+    }
+
+    fn stroke(renderer: &mut dyn BoardRenderer, path: Path, color: &str, dash_array: Vec<f64>) {
+        if path.commands().is_empty() {
+            return;
+        }
+
+        let style: Style = Style {
+            stroke_color: color.to_string(),
+            stroke_width: 2.0,
+            dash_array,
+            ..Style::default()
+        };
+
+        renderer.set_style(Some(&style));
+        renderer.path(&path, &ShapeStyle::default(), None);
+        renderer.set_style(None);
+    }
+
+    fn polyline(points: &[Point2D]) -> Vec<Command> {
+        points
+            .iter()
+            .enumerate()
+            .map(|(index, point)| match index {
+                0 => Command::MoveTo(MoveTo { to_point: *point }),
+                _ => Command::LineTo(LineTo { to_point: *point }),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,165 @@
+use crate::bounding_box::BoundingBox;
+use crate::element::id::Id;
+use crate::view_port::{LockError, ViewPort};
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+use crate::platform;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+use js_sys::Promise;
+use std::cell::Cell;
+use std::rc::Rc;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+use wasm_bindgen_futures::JsFuture;
+
+/* In milliseconds. */
+const DURATION: f64 = 500.0;
+/* Space around the shown frame, as a part of its size. */
+const PADDING: f64 = 0.05;
+
+/* Steps through the frames in presentation order, animating the view box between them. */
+#[derive(Default)]
+pub struct Presentation {
+    /* Index of the shown frame in the presentation order. */
+    current: Option<usize>,
+    /* Incremented on every move, running animation stops when another one starts. */
+    generation: Rc<Cell<u64>>,
+}
+
+impl Presentation {
+    /* Show the next frame, the first one when the presentation starts. None if there is no frame to move to. */
+    pub fn next(&mut self, view_port: &mut ViewPort) -> Result<Option<Id>, LockError> {
+        self.step(view_port, 1)
+    }
+
+    pub fn previous(&mut self, view_port: &mut ViewPort) -> Result<Option<Id>, LockError> {
+        self.step(view_port, -1)
+    }
+
+    /* Returns false if the frame is not on the board. */
+    pub fn show(&mut self, view_port: &mut ViewPort, frame_id: &Id) -> Result<bool, LockError> {
+        let Some(index) = view_port.frames()?.iter().position(|id| id == frame_id) else {
+            return Ok(false);
+        };
+
+        self.move_to(view_port, index, frame_id)
+    }
+
+    pub fn stop(&mut self) {
+        self.current = None;
+        self.interrupt();
+    }
+
+    /* Stop the camera animation, like when the host moves the camera itself. */
+    pub fn interrupt(&self) {
+        self.generation.set(self.generation.get() + 1);
+    }
+
+    fn step(&mut self, view_port: &mut ViewPort, offset: isize) -> Result<Option<Id>, LockError> {
+        let frames: Vec<Id> = view_port.frames()?;
+
+        let index: Option<usize> = match self.current {
+            None => Some(0),
+            Some(current) => current.checked_add_signed(offset),
+        };
+
+        let Some((index, frame_id)) = index.and_then(|index| Some((index, frames.get(index)?))) else {
+            return Ok(None);
+        };
+
+        Ok(self.move_to(view_port, index, frame_id)?.then(|| frame_id.clone()))
+    }
+
+    fn move_to(&mut self, view_port: &mut ViewPort, index: usize, frame_id: &Id) -> Result<bool, LockError> {
+        let Some(frame_box) = view_port.frame_box(frame_id)? else {
+            return Ok(false);
+        };
+
+        self.current = Some(index);
+        self.interrupt();
+
+        let Some(from) = view_port.view_box()? else {
+            view_port.set_view_box(Some(Self::fit(&frame_box, &frame_box)))?;
+            return Ok(true);
+        };
+
+        let to: BoundingBox = Self::fit(&frame_box, &from);
+        self.animate(view_port, from, to);
+
+        Ok(true)
+    }
+
+    /* Without animation frames the view box is moved at once. */
+    #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+    fn animate(&self, view_port: &mut ViewPort, _from: BoundingBox, to: BoundingBox) {
+        let _ = view_port.set_view_box(Some(to));
+    }
+
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    fn animate(&self, view_port: &ViewPort, from: BoundingBox, to: BoundingBox) {
+        let generation: u64 = self.generation.get();
+        let current_generation: Rc<Cell<u64>> = self.generation.clone();
+        let mut view_port: ViewPort = view_port.clone();
+
+        /* Host follows the camera through camera_changed events. */
+        platform::spawn_local(async move {
+            let start: f64 = platform::now();
+
+            loop {
+                animation_frame().await;
+
+                if current_generation.get() != generation {
+                    return;
+                }
+
+                let progress: f64 = ((platform::now() - start) / DURATION).min(1.0);
+                let _ = view_port.set_view_box(Some(Self::interpolate(&from, &to, Self::ease(progress))));
+
+                if progress >= 1.0 {
+                    return;
+                }
+            }
+        });
+    }
+
+    /* View box with the proportions of the current one showing the whole frame in its center. */
+    fn fit(frame_box: &BoundingBox, view_box: &BoundingBox) -> BoundingBox {
+        let padded: BoundingBox = frame_box.inflate(frame_box.width().max(frame_box.height()) * PADDING);
+        let aspect_ratio: f64 = view_box.width() / view_box.height().max(f64::EPSILON);
+
+        let (width, height) = match padded.width() / padded.height().max(f64::EPSILON) > aspect_ratio {
+            true => (padded.width(), padded.width() / aspect_ratio),
+            false => (padded.height() * aspect_ratio, padded.height()),
+        };
+        let (x, y) = padded.center();
+
+        BoundingBox::new(x - width / 2.0, y - height / 2.0, width, height)
+    }
+
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    fn interpolate(from: &BoundingBox, to: &BoundingBox, progress: f64) -> BoundingBox {
+        let mix = |from: f64, to: f64| from + (to - from) * progress;
+
+        BoundingBox::new(
+            mix(from.left, to.left),
+            mix(from.top, to.top),
+            mix(from.width(), to.width()),
+            mix(from.height(), to.height()),
+        )
+    }
+
+    /* Slow start and end. */
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    fn ease(progress: f64) -> f64 {
+        progress * progress * (3.0 - 2.0 * progress)
+    }
+}
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+async fn animation_frame() {
+    let promise: Promise = Promise::new(&mut |resolve, _| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.request_animation_frame(&resolve);
+        }
+    });
+
+    let _ = JsFuture::from(promise).await;
+}
@@ -1 +1,2 @@
 pub mod renderer;
+pub mod board_renderer;
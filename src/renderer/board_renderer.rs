@@ -0,0 +1,44 @@
+use crate::bounding_box::BoundingBox;
+use crate::element::id::Id;
+use crate::element::image_model::Bitmap;
+use crate::style::{EntityStyle, Style};
+use crate::text::font::Font;
+use crate::text::layout::TextLine;
+use entity_model_feature::entity::Entity;
+use standard_rendering_plugin::renderer::renderer::Renderer;
+use standard_rendering_plugin::Render;
+
+/* Renderer with primitives whiteboard elements need in addition to standard figures. */
+pub trait BoardRenderer: Renderer {
+    /* Style of the entity being rendered, it overrides styles of the figures. None restores renderer defaults. */
+    fn set_style(&mut self, style: Option<&Style>);
+
+    /* Draw one laid out line. Line position is the top left corner of the line box. */
+    fn text(&mut self, line: &TextLine, font: &Font, color: &str);
+
+    /* Rectangle filled with the css color, without outline. */
+    fn fill_rectangle(&mut self, bounding_box: &BoundingBox, color: &str);
+
+    /* Draw the source part of the bitmap, in its natural pixels, stretched into the target box. */
+    fn image(&mut self, bitmap: &Bitmap, source: &BoundingBox, target: &BoundingBox);
+
+    /* Restrict drawing to the area until the next call, replacing the previous clip. None stops clipping. */
+    fn clip(&mut self, _area: Option<&BoundingBox>) {}
+}
+
+/* Render feature of the whiteboard elements. Takes precedence over the standard Render feature. */
+pub struct BoardRender {
+    pub render: fn(&Entity<Id>, &mut dyn BoardRenderer),
+}
+
+pub fn render_entity(entity: &Entity<Id>, renderer: &mut dyn BoardRenderer) {
+    renderer.set_style(entity.query::<EntityStyle>().map(|entity_style| &entity_style.style));
+
+    if let Some(board_render) = entity.query::<BoardRender>() {
+        (board_render.render)(entity, renderer);
+    } else if let Some(render) = entity.query::<Render<Id>>() {
+        (render.render)(entity, renderer);
+    }
+
+    renderer.set_style(None);
+}
@@ -1,3 +1,9 @@
+#[cfg(feature = "dom-renderers")]
 pub mod canvas_renderer;
+#[cfg(feature = "dom-renderers")]
 pub mod svg_renderer;
+#[cfg(feature = "dom-renderers")]
 pub mod incremental_svg_renderer;
+pub mod bounds_renderer;
+pub mod hit_test_renderer;
+pub mod recording_renderer;
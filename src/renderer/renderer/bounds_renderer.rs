@@ -0,0 +1,189 @@
+use crate::bounding_box::BoundingBox;
+use crate::element::id::Id;
+use crate::element::image_model::Bitmap;
+use crate::renderer::board_renderer::{self, BoardRenderer};
+use crate::style::Style;
+use crate::text::font::Font;
+use crate::text::layout::TextLine;
+use algebra::linear::matrix::Matrix;
+use entity_model_feature::entity::Entity;
+use geometry::figure::circle::Circle;
+use geometry::figure::ellipse::Ellipse;
+use geometry::figure::path::command::Command;
+use geometry::figure::path::Path;
+use geometry::figure::polygon::Polygon;
+use geometry::figure::rectangle::Rectangle;
+use geometry::figure::segment::Segment;
+use geometry::figure::triangle::Triangle;
+use geometry::point::point_2d::Point2D;
+use geometry::point::point_3d::Point3D;
+use standard_rendering_plugin::renderer::renderer::camera::Camera;
+use standard_rendering_plugin::renderer::renderer::light::Light;
+use standard_rendering_plugin::renderer::renderer::Renderer;
+use standard_rendering_plugin::style::shape_style::ShapeStyle;
+
+/*
+ * Renderer that draws nothing, but accumulates the bounding box of every figure passed to it.
+ * Used to measure entities through their render feature, so every element type is measured the same way it is drawn.
+ */
+#[derive(Default)]
+pub struct BoundsRenderer {
+    bounding_box: Option<BoundingBox>,
+    /* Stroke width of the entity style, overrides figure styles. */
+    stroke_width: Option<f64>,
+}
+
+impl BoundsRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        self.bounding_box
+    }
+
+    pub fn bounds_of(entity: &Entity<Id>) -> Option<BoundingBox> {
+        let mut bounds_renderer: BoundsRenderer = BoundsRenderer::new();
+        board_renderer::render_entity(entity, &mut bounds_renderer);
+
+        bounds_renderer.bounding_box()
+    }
+
+    /* Figure points are moved by the transform matrix first, so the box is where the figure is drawn. */
+    fn extend<'a>(&mut self, points: impl IntoIterator<Item = &'a Point2D>, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        let points: Vec<Point2D> = points
+            .into_iter()
+            .map(|point| match &transform_matrix {
+                Some(matrix) => transformed(point, matrix),
+                None => *point,
+            })
+            .collect();
+
+        let Some(bounding_box) = BoundingBox::from_points(&points) else {
+            return;
+        };
+
+        /* Half of the stroke is drawn outside the figure. */
+        let stroke_width: f64 = self.stroke_width.unwrap_or(style.stroke.width);
+        self.include(bounding_box.inflate(stroke_width / 2.0));
+    }
+
+    /* Transformed box, the figure inside it is inside the transformed box too. */
+    fn extend_box(&mut self, bounding_box: BoundingBox, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        let corners: [Point2D; 4] = [
+            Point2D::new(bounding_box.left, bounding_box.top),
+            Point2D::new(bounding_box.right, bounding_box.top),
+            Point2D::new(bounding_box.right, bounding_box.bottom),
+            Point2D::new(bounding_box.left, bounding_box.bottom),
+        ];
+
+        self.extend(&corners, style, transform_matrix);
+    }
+
+    fn include(&mut self, bounding_box: BoundingBox) {
+        self.bounding_box = Some(match self.bounding_box {
+            Some(current) => current.union(&bounding_box),
+            None => bounding_box,
+        });
+    }
+}
+
+impl Renderer for BoundsRenderer {
+    fn clear(&mut self) {
+        self.bounding_box = None;
+    }
+
+    fn path(&mut self, path: &Path, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        let points: Vec<&Point2D> = path.commands()
+            .iter()
+            .flat_map(|command| match command {
+                Command::MoveTo(move_to) => vec![&move_to.to_point],
+                Command::LineTo(line_to) => vec![&line_to.to_point],
+                /* Control points are included, so the box may be slightly larger than the curve itself. */
+                Command::BezierTo(bezier_to) => vec![&bezier_to.control_point_1, &bezier_to.control_point_2, &bezier_to.to_point],
+                Command::ArcTo(arc_to) => vec![&arc_to.to_point],
+                Command::HorizontalLineTo(_) | Command::VerticalLineTo(_) | Command::Close => vec![],
+            })
+            .collect();
+
+        self.extend(points, style, transform_matrix);
+    }
+
+    fn segment_2d(&mut self, segment: &Segment<Point2D>, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        self.extend([segment.start(), segment.end()], style, transform_matrix);
+    }
+
+    fn polygon_2d(&mut self, polygon: &Polygon<Point2D>, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        self.extend(polygon.vertices(), style, transform_matrix);
+    }
+
+    fn triangle_2d(&mut self, triangle: &Triangle<Point2D>, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        self.extend(triangle.vertices(), style, transform_matrix);
+    }
+
+    fn rectangle(&mut self, rectangle: &Rectangle, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        let bounding_box: BoundingBox = BoundingBox::new(
+            rectangle.top_left.x,
+            rectangle.top_left.y,
+            rectangle.width,
+            rectangle.height,
+        );
+
+        self.extend_box(bounding_box, style, transform_matrix);
+    }
+
+    fn circle(&mut self, circle: &Circle, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        let bounding_box: BoundingBox = BoundingBox::new(
+            circle.center.x - circle.radius,
+            circle.center.y - circle.radius,
+            circle.radius * 2.0,
+            circle.radius * 2.0,
+        );
+
+        self.extend_box(bounding_box, style, transform_matrix);
+    }
+
+    fn ellipse(&mut self, ellipse: &Ellipse, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        let bounding_box: BoundingBox = BoundingBox::new(
+            ellipse.center.x - ellipse.radius_x,
+            ellipse.center.y - ellipse.radius_y,
+            ellipse.radius_x * 2.0,
+            ellipse.radius_y * 2.0,
+        );
+
+        self.extend_box(bounding_box, style, transform_matrix);
+    }
+
+    /* Whiteboard is a 2D surface, 3D figures are not measured. */
+    fn segment_3d(&mut self, _segment: &Segment<Point3D>, _style: &ShapeStyle, _transform_matrix: Option<Matrix<3>>) {}
+
+    fn polygon_3d(&mut self, _polygon: &Polygon<Point3D>, _style: &ShapeStyle, _transform_matrix: Option<Matrix<3>>) {}
+
+    fn triangles_3d(&mut self, _triangles: &[(&Triangle<Point3D>, &ShapeStyle)], _camera: &Camera, _light: &Light, _transform_matrix: Option<Matrix<3>>) {}
+}
+
+impl BoardRenderer for BoundsRenderer {
+    fn set_style(&mut self, style: Option<&Style>) {
+        self.stroke_width = style.map(|style| style.stroke_width);
+    }
+
+    fn text(&mut self, line: &TextLine, font: &Font, _color: &str) {
+        self.include(BoundingBox::new(line.x, line.y, line.width, font.line_height()));
+    }
+
+    fn fill_rectangle(&mut self, bounding_box: &BoundingBox, _color: &str) {
+        self.include(*bounding_box);
+    }
+
+    fn image(&mut self, _bitmap: &Bitmap, _source: &BoundingBox, target: &BoundingBox) {
+        self.include(*target);
+    }
+}
+
+/* Point moved by the 2D affine transform in homogeneous coordinates. */
+fn transformed(point: &Point2D, matrix: &Matrix<3>) -> Point2D {
+    Point2D::new(
+        matrix[(0, 0)] * point.x + matrix[(0, 1)] * point.y + matrix[(0, 2)],
+        matrix[(1, 0)] * point.x + matrix[(1, 1)] * point.y + matrix[(1, 2)],
+    )
+}
@@ -1,3 +1,9 @@
+use crate::bounding_box::BoundingBox;
+use crate::element::image_model::Bitmap;
+use crate::renderer::board_renderer::BoardRenderer;
+use crate::style::Style;
+use crate::text::font::Font;
+use crate::text::layout::TextLine;
 use algebra::linear::matrix::Matrix;
 use geometry::figure::circle::Circle;
 use geometry::figure::ellipse::Ellipse;
@@ -16,11 +22,18 @@ use standard_rendering_plugin::renderer::renderer::Renderer;
 use standard_rendering_plugin::style::shape_style::ShapeStyle;
 use wasm_bindgen::prelude::wasm_bindgen;
 use wasm_bindgen::{JsCast, JsValue};
-use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use std::collections::HashMap;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement};
 
 #[wasm_bindgen]
 pub struct CanvasRenderer {
     context: CanvasRenderingContext2d,
+    /* Decoded images by href, kept between renders. */
+    images: HashMap<String, HtmlImageElement>,
+    /* Style of the entity being rendered. */
+    style: Option<Style>,
+    /* Context state is saved while clipping and restored when it ends. */
+    clipped: bool,
 }
 
 #[wasm_bindgen]
@@ -33,12 +46,41 @@ impl CanvasRenderer {
             .dyn_into::<CanvasRenderingContext2d>()
             .unwrap();
 
-        Self { context }
+        Self {
+            context,
+            images: HashMap::new(),
+            style: None,
+            clipped: false,
+        }
     }
 }
 
 impl CanvasRenderer {
+    /* Draw the area of the board scaled into the canvas, used by exports. Reset by clear. */
+    pub fn show_area(&mut self, area: &BoundingBox, scale: f64) {
+        let _ = self.context.set_transform(scale, 0.0, 0.0, scale, -area.left * scale, -area.top * scale);
+    }
+
     fn apply_style(&mut self, style: &ShapeStyle) {
+        if let Some(style) = &self.style {
+            self.context.set_fill_style_str(style.fill_color.as_deref().unwrap_or("transparent"));
+            self.context.set_stroke_style_str(&style.stroke_color);
+            self.context.set_line_width(style.stroke_width);
+            self.context.set_line_cap(style.line_cap.css());
+            self.context.set_line_join(style.line_join.css());
+
+            let array: js_sys::Array = style.dash_array
+                .iter()
+                .map(|x| JsValue::from_f64(*x))
+                .collect::<js_sys::Array>();
+
+            let _ = self.context.set_line_dash(&array);
+
+            return;
+        }
+
+        self.context.set_line_cap("butt");
+        self.context.set_line_join("miter");
         self.context.set_fill_style_str(&style.fill_color.to_hex());
         self.context
             .set_stroke_style_str(&style.stroke.color.to_hex());
@@ -52,11 +94,21 @@ impl CanvasRenderer {
 
         let _ = self.context.set_line_dash(&array);
     }
+
+    /* Open figures are only stroked, unless the entity style has a fill. */
+    fn fill_and_stroke(&mut self) {
+        if self.style.as_ref().is_some_and(|style| style.fill_color.is_some()) {
+            self.context.fill();
+        }
+
+        self.context.stroke();
+    }
 }
 
 impl Renderer for CanvasRenderer {
     fn clear(&mut self) {
         self.context.reset();
+        self.clipped = false;
     }
 
     fn path(&mut self, path: &Path, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
@@ -72,13 +124,22 @@ impl Renderer for CanvasRenderer {
                     self.context.line_to(line_to.to_point.x, line_to.to_point.y),
                 Command::HorizontalLineTo(horizontal_line_to) => {}
                 Command::VerticalLineTo(vertical_line_to) => {}
-                Command::BezierTo(bezier_to) => {}
+                Command::BezierTo(bezier_to) =>
+                    self.context.bezier_curve_to(
+                        bezier_to.control_point_1.x,
+                        bezier_to.control_point_1.y,
+                        bezier_to.control_point_2.x,
+                        bezier_to.control_point_2.y,
+                        bezier_to.to_point.x,
+                        bezier_to.to_point.y,
+                    ),
                 Command::ArcTo(arc_to) => {}
-                Command::Close => {}
+                Command::Close =>
+                    self.context.close_path(),
             }
         }
 
-        self.context.stroke();
+        self.fill_and_stroke();
     }
 
     fn segment_2d(&mut self, segment: &Segment<Point2D>, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
@@ -108,7 +169,7 @@ impl Renderer for CanvasRenderer {
 
         self.context.close_path();
 
-        self.context.stroke();
+        self.fill_and_stroke();
     }
 
     fn triangle_2d(&mut self, polygon: &Triangle<Point2D>, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
@@ -120,6 +181,22 @@ impl Renderer for CanvasRenderer {
 
         self.context.begin_path();
 
+        let corner_radius: f64 = self.style.as_ref().map_or(0.0, |style| style.corner_radius);
+        if corner_radius > 0.0 {
+            let _ = self.context.round_rect_with_f64(
+                rectangle.top_left.x,
+                rectangle.top_left.y,
+                rectangle.width,
+                rectangle.height,
+                corner_radius,
+            );
+
+            self.context.fill();
+            self.context.stroke();
+
+            return;
+        }
+
         self.context.fill_rect(
             rectangle.top_left.x,
             rectangle.top_left.y,
@@ -139,13 +216,31 @@ impl Renderer for CanvasRenderer {
     fn circle(&mut self, circle: &Circle, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
         self.apply_style(style);
 
-        todo!()
+        self.context.begin_path();
+
+        let _ = self.context.arc(circle.center.x, circle.center.y, circle.radius, 0.0, std::f64::consts::TAU);
+
+        self.context.fill();
+        self.context.stroke();
     }
 
     fn ellipse(&mut self, ellipse: &Ellipse, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
         self.apply_style(style);
 
-        todo!()
+        self.context.begin_path();
+
+        let _ = self.context.ellipse(
+            ellipse.center.x,
+            ellipse.center.y,
+            ellipse.radius_x,
+            ellipse.radius_y,
+            0.0,
+            0.0,
+            std::f64::consts::TAU,
+        );
+
+        self.context.fill();
+        self.context.stroke();
     }
 
     fn segment_3d(&mut self, segment: &Segment<Point3D>, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
@@ -160,3 +255,68 @@ impl Renderer for CanvasRenderer {
         todo!()
     }
 }
+
+impl BoardRenderer for CanvasRenderer {
+    fn set_style(&mut self, style: Option<&Style>) {
+        self.context.set_global_alpha(style.map_or(1.0, |style| style.opacity));
+        self.style = style.cloned();
+    }
+
+    fn text(&mut self, line: &TextLine, font: &Font, color: &str) {
+        self.context.set_font(&font.css());
+        self.context.set_fill_style_str(color);
+        self.context.set_text_baseline("top");
+
+        let _ = self.context.fill_text(&line.text, line.x, line.y);
+    }
+
+    fn fill_rectangle(&mut self, bounding_box: &BoundingBox, color: &str) {
+        self.context.set_fill_style_str(color);
+        self.context.fill_rect(bounding_box.left, bounding_box.top, bounding_box.width(), bounding_box.height());
+    }
+
+    /* Images load asynchronously, an image is skipped until it is decoded and drawn on the next render. */
+    fn image(&mut self, bitmap: &Bitmap, source: &BoundingBox, target: &BoundingBox) {
+        let href: &str = bitmap.source.href();
+
+        let image: &HtmlImageElement = self.images.entry(href.to_string()).or_insert_with(|| {
+            let image: HtmlImageElement = HtmlImageElement::new().expect("Can't create image element");
+            image.set_src(href);
+
+            image
+        });
+
+        if !image.complete() || image.natural_width() == 0 {
+            return;
+        }
+
+        let _ = self.context.draw_image_with_html_image_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+            image,
+            source.left,
+            source.top,
+            source.width(),
+            source.height(),
+            target.left,
+            target.top,
+            target.width(),
+            target.height(),
+        );
+    }
+
+    fn clip(&mut self, area: Option<&BoundingBox>) {
+        if self.clipped {
+            self.context.restore();
+            self.clipped = false;
+        }
+
+        let Some(area) = area else {
+            return;
+        };
+
+        self.context.save();
+        self.context.begin_path();
+        self.context.rect(area.left, area.top, area.width(), area.height());
+        self.context.clip();
+        self.clipped = true;
+    }
+}
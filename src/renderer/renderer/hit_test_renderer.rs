@@ -0,0 +1,226 @@
+use crate::bounding_box::BoundingBox;
+use crate::element::id::Id;
+use crate::element::image_model::Bitmap;
+use crate::math;
+use crate::renderer::board_renderer::{self, BoardRenderer};
+use crate::style::Style;
+use crate::text::font::Font;
+use crate::text::layout::TextLine;
+use algebra::linear::matrix::Matrix;
+use entity_model_feature::entity::Entity;
+use geometry::figure::circle::Circle;
+use geometry::figure::ellipse::Ellipse;
+use geometry::figure::path::command::Command;
+use geometry::figure::path::Path;
+use geometry::figure::polygon::Polygon;
+use geometry::figure::rectangle::Rectangle;
+use geometry::figure::segment::Segment;
+use geometry::figure::triangle::Triangle;
+use geometry::point::point_2d::Point2D;
+use geometry::point::point_3d::Point3D;
+use standard_rendering_plugin::renderer::renderer::camera::Camera;
+use standard_rendering_plugin::renderer::renderer::light::Light;
+use standard_rendering_plugin::renderer::renderer::Renderer;
+use standard_rendering_plugin::style::shape_style::ShapeStyle;
+use std::f64::consts::PI;
+
+const ELLIPSE_SEGMENTS: usize = 32;
+
+/*
+ * Renderer that draws nothing, but checks if any outline of the rendered figures
+ * comes closer than radius to the probe segment.
+ */
+pub struct HitTestRenderer {
+    probe_start: Point2D,
+    probe_end: Point2D,
+    radius: f64,
+    hit: bool,
+    /* Stroke width of the entity style, overrides figure styles. */
+    stroke_width: Option<f64>,
+}
+
+impl HitTestRenderer {
+    pub fn new(probe_start: Point2D, probe_end: Point2D, radius: f64) -> Self {
+        Self {
+            probe_start,
+            probe_end,
+            radius,
+            hit: false,
+            stroke_width: None,
+        }
+    }
+
+    pub fn is_hit(&self) -> bool {
+        self.hit
+    }
+
+    pub fn hits(entity: &Entity<Id>, probe_start: Point2D, probe_end: Point2D, radius: f64) -> bool {
+        let mut hit_test_renderer: HitTestRenderer = HitTestRenderer::new(probe_start, probe_end, radius);
+        board_renderer::render_entity(entity, &mut hit_test_renderer);
+
+        hit_test_renderer.is_hit()
+    }
+
+    fn test_polyline(&mut self, points: &[Point2D], closed: bool, style: &ShapeStyle) {
+        if self.hit {
+            return;
+        }
+
+        let tolerance: f64 = self.radius + self.stroke_width.unwrap_or(style.stroke.width) / 2.0;
+
+        if let [point] = points {
+            self.hit = math::distance_to_segment(point, &self.probe_start, &self.probe_end) <= tolerance;
+            return;
+        }
+
+        let closing_segment = match (closed, points.first(), points.last()) {
+            (true, Some(first), Some(last)) => Some((last, first)),
+            _ => None,
+        };
+
+        self.hit = points
+            .windows(2)
+            .map(|segment| (&segment[0], &segment[1]))
+            .chain(closing_segment)
+            .any(|(start, end)|
+                math::distance_between_segments(start, end, &self.probe_start, &self.probe_end) <= tolerance
+            );
+    }
+
+    /* Box without outline, e.g. text line or image, can be hit anywhere inside. */
+    fn test_box(&mut self, bounding_box: &BoundingBox) {
+        let inflated: BoundingBox = bounding_box.inflate(self.radius);
+
+        self.hit |= inflated.contains_point(self.probe_start.x, self.probe_start.y);
+        self.hit |= inflated.contains_point(self.probe_end.x, self.probe_end.y);
+
+        let outline: [Point2D; 4] = [
+            Point2D::new(bounding_box.left, bounding_box.top),
+            Point2D::new(bounding_box.right, bounding_box.top),
+            Point2D::new(bounding_box.right, bounding_box.bottom),
+            Point2D::new(bounding_box.left, bounding_box.bottom),
+        ];
+
+        self.test_polyline(&outline, true, &ShapeStyle::default());
+    }
+
+    fn ellipse_outline(center: &Point2D, radius_x: f64, radius_y: f64) -> Vec<Point2D> {
+        (0..ELLIPSE_SEGMENTS)
+            .map(|index| {
+                let angle: f64 = 2.0 * PI * index as f64 / ELLIPSE_SEGMENTS as f64;
+                Point2D::new(center.x + radius_x * angle.cos(), center.y + radius_y * angle.sin())
+            })
+            .collect()
+    }
+}
+
+impl Renderer for HitTestRenderer {
+    fn clear(&mut self) {
+        self.hit = false;
+    }
+
+    fn path(&mut self, path: &Path, style: &ShapeStyle, _transform_matrix: Option<Matrix<3>>) {
+        /* Each sub path is tested separately, curves are approximated by their end points. */
+        let mut sub_path: Vec<Point2D> = vec![];
+
+        for command in path.commands() {
+            match command {
+                Command::MoveTo(move_to) => {
+                    self.test_polyline(&sub_path, false, style);
+                    sub_path = vec![Point2D::new(move_to.to_point.x, move_to.to_point.y)];
+                }
+                Command::LineTo(line_to) =>
+                    sub_path.push(Point2D::new(line_to.to_point.x, line_to.to_point.y)),
+                Command::BezierTo(bezier_to) =>
+                    sub_path.push(Point2D::new(bezier_to.to_point.x, bezier_to.to_point.y)),
+                Command::ArcTo(arc_to) =>
+                    sub_path.push(Point2D::new(arc_to.to_point.x, arc_to.to_point.y)),
+                Command::Close => {
+                    self.test_polyline(&sub_path, true, style);
+                    sub_path.clear();
+                }
+                Command::HorizontalLineTo(_) | Command::VerticalLineTo(_) => {}
+            }
+        }
+
+        self.test_polyline(&sub_path, false, style);
+    }
+
+    fn segment_2d(&mut self, segment: &Segment<Point2D>, style: &ShapeStyle, _transform_matrix: Option<Matrix<3>>) {
+        let points: [Point2D; 2] = [
+            Point2D::new(segment.start().x, segment.start().y),
+            Point2D::new(segment.end().x, segment.end().y),
+        ];
+
+        self.test_polyline(&points, false, style);
+    }
+
+    fn polygon_2d(&mut self, polygon: &Polygon<Point2D>, style: &ShapeStyle, _transform_matrix: Option<Matrix<3>>) {
+        let points: Vec<Point2D> = polygon
+            .vertices()
+            .iter()
+            .map(|vertex| Point2D::new(vertex.x, vertex.y))
+            .collect();
+
+        self.test_polyline(&points, true, style);
+    }
+
+    fn triangle_2d(&mut self, triangle: &Triangle<Point2D>, style: &ShapeStyle, _transform_matrix: Option<Matrix<3>>) {
+        let points: Vec<Point2D> = triangle
+            .vertices()
+            .iter()
+            .map(|vertex| Point2D::new(vertex.x, vertex.y))
+            .collect();
+
+        self.test_polyline(&points, true, style);
+    }
+
+    fn rectangle(&mut self, rectangle: &Rectangle, style: &ShapeStyle, _transform_matrix: Option<Matrix<3>>) {
+        let (x, y) = (rectangle.top_left.x, rectangle.top_left.y);
+        let points: [Point2D; 4] = [
+            Point2D::new(x, y),
+            Point2D::new(x + rectangle.width, y),
+            Point2D::new(x + rectangle.width, y + rectangle.height),
+            Point2D::new(x, y + rectangle.height),
+        ];
+
+        self.test_polyline(&points, true, style);
+    }
+
+    fn circle(&mut self, circle: &Circle, style: &ShapeStyle, _transform_matrix: Option<Matrix<3>>) {
+        let points: Vec<Point2D> = Self::ellipse_outline(&circle.center, circle.radius, circle.radius);
+
+        self.test_polyline(&points, true, style);
+    }
+
+    fn ellipse(&mut self, ellipse: &Ellipse, style: &ShapeStyle, _transform_matrix: Option<Matrix<3>>) {
+        let points: Vec<Point2D> = Self::ellipse_outline(&ellipse.center, ellipse.radius_x, ellipse.radius_y);
+
+        self.test_polyline(&points, true, style);
+    }
+
+    /* Whiteboard is a 2D surface, 3D figures can't be hit. */
+    fn segment_3d(&mut self, _segment: &Segment<Point3D>, _style: &ShapeStyle, _transform_matrix: Option<Matrix<3>>) {}
+
+    fn polygon_3d(&mut self, _polygon: &Polygon<Point3D>, _style: &ShapeStyle, _transform_matrix: Option<Matrix<3>>) {}
+
+    fn triangles_3d(&mut self, _triangles: &[(&Triangle<Point3D>, &ShapeStyle)], _camera: &Camera, _light: &Light, _transform_matrix: Option<Matrix<3>>) {}
+}
+
+impl BoardRenderer for HitTestRenderer {
+    fn set_style(&mut self, style: Option<&Style>) {
+        self.stroke_width = style.map(|style| style.stroke_width);
+    }
+
+    fn text(&mut self, line: &TextLine, font: &Font, _color: &str) {
+        self.test_box(&BoundingBox::new(line.x, line.y, line.width, font.line_height()));
+    }
+
+    fn fill_rectangle(&mut self, bounding_box: &BoundingBox, _color: &str) {
+        self.test_box(bounding_box);
+    }
+
+    fn image(&mut self, _bitmap: &Bitmap, _source: &BoundingBox, target: &BoundingBox) {
+        self.test_box(target);
+    }
+}
@@ -2,15 +2,17 @@ mod update_svg;
 mod create_svg;
 
 use crate::element::id::Id;
+use crate::renderer::board_renderer::{render_entity, BoardRender};
 use crate::renderer::renderer::incremental_svg_renderer::create_svg::CreateSVG;
 use crate::renderer::renderer::incremental_svg_renderer::update_svg::UpdateSVG;
+use crate::renderer::renderer::svg_renderer::SVGRenderer;
 use entity_model_feature::entity::Entity;
 use standard_rendering_plugin::renderer::renderer_incremental::RendererIncremental;
 use standard_svg_plugin::svg_element::{SVGElement, SVG};
 use standard_svg_plugin::ToSVG;
 use wasm_bindgen::prelude::wasm_bindgen;
 use wasm_bindgen::JsCast;
-use web_sys::{Element, SvgCircleElement, SvgElement, SvgLineElement, SvgRectElement};
+use web_sys::{Element, SvgCircleElement, SvgElement, SvgImageElement, SvgLineElement, SvgRectElement, SvggElement};
 
 #[wasm_bindgen]
 pub struct IncrementalSvgRenderer {
@@ -31,8 +33,32 @@ impl IncrementalSvgRenderer {
     }
 }
 
+impl IncrementalSvgRenderer {
+    /* Whiteboard elements have no svg mapping of their own, they are drawn into a group by the svg renderer. */
+    fn render_board_entity(group: &Element, entity: &Entity<Id>) {
+        group.set_inner_html("");
+
+        let group: SvgElement = group.clone().dyn_into::<SvgElement>().expect("Can't cast group into svg element.");
+        render_entity(entity, &mut SVGRenderer::new(group));
+    }
+}
+
 impl RendererIncremental<Id> for IncrementalSvgRenderer {
     fn add(&mut self, entity: &Entity<Id>) {
+        if entity.query::<BoardRender>().is_some() {
+            let group: SvggElement = self.document
+                .create_element_ns(Some("http://www.w3.org/2000/svg"), "g")
+                .expect("Can't create svg g element.")
+                .dyn_into::<SvggElement>()
+                .expect("Can't cast JSValue into g node.");
+
+            group.set_id(&entity.id().as_html_id());
+            Self::render_board_entity(&group, entity);
+            self.svg.append_child(&group).expect("Can't append element do svg container.");
+
+            return;
+        }
+
         let Some(to_svg) = entity.query::<ToSVG<Id>>() else {
             return;
         };
@@ -58,7 +84,7 @@ impl RendererIncremental<Id> for IncrementalSvgRenderer {
             SVG::Text =>
                 todo!(),
             SVG::Image =>
-                todo!(),
+                &SvgImageElement::create_svg(&self.document, "image", &(), svg_element.attributes(), svg_element.css()),
             SVG::Path =>
                 todo!(),
             SVG::ForeignObject =>
@@ -70,6 +96,13 @@ impl RendererIncremental<Id> for IncrementalSvgRenderer {
     }
 
     fn modify(&mut self, entity: &Entity<Id>) {
+        if entity.query::<BoardRender>().is_some() {
+            let id: &str = &entity.id().as_html_id();
+            let group: Element = self.document.get_element_by_id(id).unwrap();
+
+            return Self::render_board_entity(&group, entity);
+        }
+
         let Some(to_svg) = entity.query::<ToSVG<Id>>() else {
             return;
         };
@@ -98,7 +131,7 @@ impl RendererIncremental<Id> for IncrementalSvgRenderer {
             SVG::Text =>
                 todo!(),
             SVG::Image =>
-                todo!(),
+                svg_node.dyn_into::<SvgImageElement>().unwrap().update_svg(&(), svg_element.attributes(), svg_element.css()),
             SVG::Path =>
                 todo!(),
             SVG::ForeignObject =>
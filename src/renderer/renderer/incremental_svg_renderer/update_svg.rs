@@ -2,7 +2,7 @@ use standard_svg_plugin::property_map::PropertyMap;
 use standard_svg_plugin::svg_element::svg_circle::SVGCircle;
 use standard_svg_plugin::svg_element::svg_line::SVGLine;
 use standard_svg_plugin::svg_element::svg_rectangle::SVGRectangle;
-use web_sys::{CssStyleDeclaration, SvgCircleElement, SvgElement, SvgLineElement, SvgRectElement};
+use web_sys::{CssStyleDeclaration, SvgCircleElement, SvgElement, SvgImageElement, SvgLineElement, SvgRectElement};
 
 trait Attributes {
     fn set_attributes(&self, attributes: &PropertyMap);
@@ -72,4 +72,17 @@ impl UpdateSVG<SVGLine> for SvgLineElement {
     }
 }
 
+/* Image elements carry href, x, y, width and height in their attributes. */
+impl UpdateSVG<()> for SvgImageElement {
+    fn update_svg(&mut self, _svg_element: &(), attributes: &PropertyMap, css: &PropertyMap) {
+        for name in ["href", "x", "y", "width", "height"] {
+            self.remove_attribute(name).expect(format!("Can't reset {name}").as_str());
+        }
+        self.set_attribute("preserveAspectRatio", "none").expect("Can't set preserveAspectRatio");
+
+        self.set_attributes(attributes);
+        self.set_css(css);
+    }
+}
+
 /* todo: Implement UpdateSVG for all supported svg element types */
@@ -0,0 +1,175 @@
+use crate::bounding_box::BoundingBox;
+use crate::element::image_model::Bitmap;
+use crate::renderer::board_renderer::BoardRenderer;
+use crate::style::Style;
+use crate::text::font::Font;
+use crate::text::layout::TextLine;
+use algebra::linear::matrix::Matrix;
+use geometry::figure::circle::Circle;
+use geometry::figure::ellipse::Ellipse;
+use geometry::figure::path::Path;
+use geometry::figure::polygon::Polygon;
+use geometry::figure::rectangle::Rectangle;
+use geometry::figure::segment::Segment;
+use geometry::figure::triangle::Triangle;
+use geometry::point::point_2d::Point2D;
+use geometry::point::point_3d::Point3D;
+use standard_rendering_plugin::renderer::renderer::camera::Camera;
+use standard_rendering_plugin::renderer::renderer::light::Light;
+use standard_rendering_plugin::renderer::renderer::Renderer;
+use standard_rendering_plugin::style::shape_style::ShapeStyle;
+
+pub enum Figure {
+    Path(Path),
+    Segment(Segment<Point2D>),
+    Polygon(Polygon<Point2D>),
+    Triangle(Triangle<Point2D>),
+    Rectangle(Rectangle),
+    Circle(Circle),
+    Ellipse(Ellipse),
+    Segment3D(Segment<Point3D>),
+    Polygon3D(Polygon<Point3D>),
+    /* One call per triangle of the mesh. */
+    Triangle3D(Triangle<Point3D>),
+}
+
+pub enum DrawCall {
+    Clear,
+    Figure {
+        figure: Figure,
+        style: ShapeStyle,
+        transform_matrix: Option<Matrix<3>>,
+        /* Style of the entity being rendered when the figure was drawn. */
+        entity_style: Option<Style>,
+    },
+    Text {
+        line: TextLine,
+        font: Font,
+        color: String,
+    },
+    FillRectangle {
+        bounding_box: BoundingBox,
+        color: String,
+    },
+    Image {
+        bitmap: Bitmap,
+        source: BoundingBox,
+        target: BoundingBox,
+    },
+    Clip(Option<BoundingBox>),
+}
+
+/* Renderer that draws nothing, but keeps every call in order. Lets the board be rendered and inspected without a browser. */
+#[derive(Default)]
+pub struct RecordingRenderer {
+    calls: Vec<DrawCall>,
+    entity_style: Option<Style>,
+}
+
+impl RecordingRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /* Calls since the last clear, clear itself included. */
+    pub fn calls(&self) -> &[DrawCall] {
+        &self.calls
+    }
+
+    pub fn figures(&self) -> impl Iterator<Item = &Figure> {
+        self.calls.iter().filter_map(|call| match call {
+            DrawCall::Figure { figure, .. } => Some(figure),
+            _ => None,
+        })
+    }
+
+    fn figure(&mut self, figure: Figure, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        self.calls.push(DrawCall::Figure {
+            figure,
+            style: style.clone(),
+            transform_matrix,
+            entity_style: self.entity_style.clone(),
+        });
+    }
+}
+
+impl Renderer for RecordingRenderer {
+    fn clear(&mut self) {
+        self.calls = vec![DrawCall::Clear];
+    }
+
+    fn path(&mut self, path: &Path, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        self.figure(Figure::Path(path.clone()), style, transform_matrix);
+    }
+
+    fn segment_2d(&mut self, segment: &Segment<Point2D>, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        self.figure(Figure::Segment(segment.clone()), style, transform_matrix);
+    }
+
+    fn polygon_2d(&mut self, polygon: &Polygon<Point2D>, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        self.figure(Figure::Polygon(polygon.clone()), style, transform_matrix);
+    }
+
+    fn triangle_2d(&mut self, triangle: &Triangle<Point2D>, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        self.figure(Figure::Triangle(triangle.clone()), style, transform_matrix);
+    }
+
+    fn rectangle(&mut self, rectangle: &Rectangle, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        self.figure(Figure::Rectangle(rectangle.clone()), style, transform_matrix);
+    }
+
+    fn circle(&mut self, circle: &Circle, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        self.figure(Figure::Circle(circle.clone()), style, transform_matrix);
+    }
+
+    fn ellipse(&mut self, ellipse: &Ellipse, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        self.figure(Figure::Ellipse(ellipse.clone()), style, transform_matrix);
+    }
+
+    fn segment_3d(&mut self, segment: &Segment<Point3D>, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        self.figure(Figure::Segment3D(segment.clone()), style, transform_matrix);
+    }
+
+    fn polygon_3d(&mut self, polygon: &Polygon<Point3D>, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
+        self.figure(Figure::Polygon3D(polygon.clone()), style, transform_matrix);
+    }
+
+    fn triangles_3d(&mut self, triangles: &[(&Triangle<Point3D>, &ShapeStyle)], _camera: &Camera, _light: &Light, transform_matrix: Option<Matrix<3>>) {
+        for (triangle, style) in triangles {
+            self.figure(Figure::Triangle3D((*triangle).clone()), style, transform_matrix.clone());
+        }
+    }
+}
+
+impl BoardRenderer for RecordingRenderer {
+    fn set_style(&mut self, style: Option<&Style>) {
+        self.entity_style = style.cloned();
+    }
+
+    fn text(&mut self, line: &TextLine, font: &Font, color: &str) {
+        self.calls.push(DrawCall::Text {
+            line: line.clone(),
+            font: font.clone(),
+            color: color.to_string(),
+        });
+    }
+
+    fn fill_rectangle(&mut self, bounding_box: &BoundingBox, color: &str) {
+        self.calls.push(DrawCall::FillRectangle {
+            bounding_box: *bounding_box,
+            color: color.to_string(),
+        });
+    }
+
+    fn image(&mut self, bitmap: &Bitmap, source: &BoundingBox, target: &BoundingBox) {
+        self.calls.push(DrawCall::Image {
+            bitmap: bitmap.clone(),
+            source: *source,
+            target: *target,
+        });
+    }
+
+    fn clip(&mut self, area: Option<&BoundingBox>) {
+        self.calls.push(DrawCall::Clip(area.copied()));
+    }
+}
@@ -1,3 +1,9 @@
+use crate::bounding_box::BoundingBox;
+use crate::element::image_model::Bitmap;
+use crate::renderer::board_renderer::BoardRenderer;
+use crate::style::Style;
+use crate::text::font::Font;
+use crate::text::layout::TextLine;
 use algebra::linear::matrix::Matrix;
 use geometry::figure::circle::Circle;
 use geometry::figure::ellipse::Ellipse;
@@ -14,7 +20,7 @@ use standard_rendering_plugin::renderer::renderer::Renderer;
 use standard_rendering_plugin::style::shape_style::ShapeStyle;
 use wasm_bindgen::prelude::wasm_bindgen;
 use wasm_bindgen::JsCast;
-use web_sys::{Node, SvgElement, SvgLineElement, SvgPathElement, SvgPolygonElement, SvgRectElement};
+use web_sys::{Element, Node, SvgCircleElement, SvgElement, SvgEllipseElement, SvgImageElement, SvgLineElement, SvgPathElement, SvgPolygonElement, SvgRectElement, SvgTextElement, SvggElement, SvgsvgElement};
 
 #[wasm_bindgen]
 extern "C" {
@@ -26,6 +32,11 @@ extern "C" {
 pub struct SVGRenderer {
     svg: SvgElement,
     document: web_sys::Document,
+    /* Style of the entity being rendered. */
+    style: Option<Style>,
+    /* Element the renderer draws into while figures go into a clipped group. */
+    root: Option<SvgElement>,
+    clip_paths: usize,
 }
 
 #[wasm_bindgen]
@@ -36,14 +47,79 @@ impl SVGRenderer {
 
         Self {
             svg,
-            document
+            document,
+            style: None,
+            root: None,
+            clip_paths: 0,
         }
     }
 }
 
+impl SVGRenderer {
+    /* Override default attributes of the figure with the entity style. */
+    fn apply_style(&self, element: &Element) {
+        let Some(style) = &self.style else {
+            return;
+        };
+
+        element
+            .set_attribute("fill", style.fill_color.as_deref().unwrap_or("none"))
+            .expect("Can't set fill");
+        element
+            .set_attribute("stroke", &style.stroke_color)
+            .expect("Can't set stroke");
+        element
+            .set_attribute("stroke-width", &format!("{}", style.stroke_width))
+            .expect("Can't set stroke-width");
+        element
+            .set_attribute("stroke-linecap", style.line_cap.css())
+            .expect("Can't set stroke-linecap");
+        element
+            .set_attribute("stroke-linejoin", style.line_join.css())
+            .expect("Can't set stroke-linejoin");
+
+        if style.dash_array.is_empty() {
+            element
+                .remove_attribute("stroke-dasharray")
+                .expect("Can't remove stroke-dasharray");
+        } else {
+            let dash_array: String = style.dash_array
+                .iter()
+                .map(|dash| format!("{dash}"))
+                .collect::<Vec<String>>()
+                .join(" ");
+
+            element
+                .set_attribute("stroke-dasharray", &dash_array)
+                .expect("Can't set stroke-dasharray");
+        }
+
+        if element.tag_name() == "rect" && style.corner_radius > 0.0 {
+            element
+                .set_attribute("rx", &format!("{}", style.corner_radius))
+                .expect("Can't set rx");
+        }
+
+        self.apply_opacity(element);
+    }
+
+    /* Elements with their own colors only take opacity of the entity style. */
+    fn apply_opacity(&self, element: &Element) {
+        let Some(style) = &self.style else {
+            return;
+        };
+
+        element
+            .set_attribute("opacity", &format!("{}", style.opacity))
+            .expect("Can't set opacity");
+    }
+}
+
 impl Renderer for SVGRenderer {
     fn clear(&mut self) {
+        self.clip(None);
         self.svg.set_inner_html("");
+        self.clip_paths = 0;
     }
 
     fn path(&mut self, path: &Path, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
@@ -64,6 +140,8 @@ impl Renderer for SVGRenderer {
             .set_attribute("stroke", "black")
             .expect("TODO: panic message");
 
+        self.apply_style(&svg_path);
+
         self.svg
             .append_child(&svg_path.dyn_into::<Node>().expect(""))
             .expect("");
@@ -97,6 +175,8 @@ impl Renderer for SVGRenderer {
             .set_attribute("y2", &format!("{}", segment.end().y()))
             .expect("TODO: panic message");
 
+        self.apply_style(&svg_line);
+
         self.svg
             .append_child(&svg_line.dyn_into::<Node>().expect(""))
             .expect("");
@@ -125,6 +205,8 @@ impl Renderer for SVGRenderer {
 
         svg_polygon.set_attribute("points", &points).expect("");
 
+        self.apply_style(&svg_polygon);
+
         self.svg
             .append_child(&svg_polygon.dyn_into::<Node>().expect(""))
             .expect("");
@@ -163,17 +245,76 @@ impl Renderer for SVGRenderer {
             .set_attribute("stroke", "black")
             .expect("TODO: panic message");
 
+        self.apply_style(&svg_rectangle);
+
         self.svg
             .append_child(&svg_rectangle.dyn_into::<Node>().expect(""))
             .expect("");
     }
 
     fn circle(&mut self, circle: &Circle, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
-        todo!()
+        let svg_circle = self.document
+            .create_element_ns(Some("http://www.w3.org/2000/svg"), "circle")
+            .expect("can't create svg circle element")
+            .dyn_into::<SvgCircleElement>()
+            .expect("can't create svg circle element");
+
+        svg_circle
+            .set_attribute("cx", &format!("{}", circle.center.x))
+            .expect("Can't set cx");
+        svg_circle
+            .set_attribute("cy", &format!("{}", circle.center.y))
+            .expect("Can't set cy");
+        svg_circle
+            .set_attribute("r", &format!("{}", circle.radius))
+            .expect("Can't set r");
+
+        svg_circle
+            .set_attribute("fill", "none")
+            .expect("Can't set fill");
+        svg_circle
+            .set_attribute("stroke", "black")
+            .expect("Can't set stroke");
+
+        self.apply_style(&svg_circle);
+
+        self.svg
+            .append_child(&svg_circle.dyn_into::<Node>().expect(""))
+            .expect("");
     }
 
     fn ellipse(&mut self, ellipse: &Ellipse, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
-        todo!()
+        let svg_ellipse = self.document
+            .create_element_ns(Some("http://www.w3.org/2000/svg"), "ellipse")
+            .expect("can't create svg ellipse element")
+            .dyn_into::<SvgEllipseElement>()
+            .expect("can't create svg ellipse element");
+
+        svg_ellipse
+            .set_attribute("cx", &format!("{}", ellipse.center.x))
+            .expect("Can't set cx");
+        svg_ellipse
+            .set_attribute("cy", &format!("{}", ellipse.center.y))
+            .expect("Can't set cy");
+        svg_ellipse
+            .set_attribute("rx", &format!("{}", ellipse.radius_x))
+            .expect("Can't set rx");
+        svg_ellipse
+            .set_attribute("ry", &format!("{}", ellipse.radius_y))
+            .expect("Can't set ry");
+
+        svg_ellipse
+            .set_attribute("fill", "none")
+            .expect("Can't set fill");
+        svg_ellipse
+            .set_attribute("stroke", "black")
+            .expect("Can't set stroke");
+
+        self.apply_style(&svg_ellipse);
+
+        self.svg
+            .append_child(&svg_ellipse.dyn_into::<Node>().expect(""))
+            .expect("");
     }
 
     fn segment_3d(&mut self, segment: &Segment<Point3D>, style: &ShapeStyle, transform_matrix: Option<Matrix<3>>) {
@@ -188,3 +329,190 @@ impl Renderer for SVGRenderer {
         todo!()
     }
 }
+
+impl BoardRenderer for SVGRenderer {
+    fn set_style(&mut self, style: Option<&Style>) {
+        self.style = style.cloned();
+    }
+
+    fn text(&mut self, line: &TextLine, font: &Font, color: &str) {
+        let svg_text = self.document
+            .create_element_ns(Some("http://www.w3.org/2000/svg"), "text")
+            .expect("can't create svg text element")
+            .dyn_into::<SvgTextElement>()
+            .expect("can't create svg text element");
+
+        svg_text
+            .set_attribute("x", &format!("{}", line.x))
+            .expect("Can't set x");
+        svg_text
+            .set_attribute("y", &format!("{}", line.y))
+            .expect("Can't set y");
+
+        /* Same baseline and font shorthand as canvas, so lines are placed identically. */
+        svg_text
+            .set_attribute("dominant-baseline", "text-before-edge")
+            .expect("Can't set dominant-baseline");
+        svg_text
+            .set_attribute("style", &format!("font: {}; white-space: pre", font.css()))
+            .expect("Can't set style");
+        svg_text
+            .set_attribute("fill", color)
+            .expect("Can't set fill");
+
+        svg_text.set_text_content(Some(&line.text));
+
+        self.apply_opacity(&svg_text);
+
+        self.svg
+            .append_child(&svg_text.dyn_into::<Node>().expect(""))
+            .expect("");
+    }
+
+    fn fill_rectangle(&mut self, bounding_box: &BoundingBox, color: &str) {
+        let svg_rectangle = self.document
+            .create_element_ns(Some("http://www.w3.org/2000/svg"), "rect")
+            .expect("can't create svg rectangle element")
+            .dyn_into::<SvgRectElement>()
+            .expect("can't create svg rectangle element");
+
+        svg_rectangle
+            .set_attribute("x", &format!("{}", bounding_box.left))
+            .expect("Can't set x");
+        svg_rectangle
+            .set_attribute("y", &format!("{}", bounding_box.top))
+            .expect("Can't set y");
+        svg_rectangle
+            .set_attribute("width", &format!("{}", bounding_box.width()))
+            .expect("Can't set width");
+        svg_rectangle
+            .set_attribute("height", &format!("{}", bounding_box.height()))
+            .expect("Can't set height");
+        svg_rectangle
+            .set_attribute("fill", color)
+            .expect("Can't set fill");
+
+        self.apply_opacity(&svg_rectangle);
+
+        self.svg
+            .append_child(&svg_rectangle.dyn_into::<Node>().expect(""))
+            .expect("");
+    }
+
+    /* Nested svg viewport shows the source part of the image, stretched into the target box. */
+    fn image(&mut self, bitmap: &Bitmap, source: &BoundingBox, target: &BoundingBox) {
+        let svg_viewport = self.document
+            .create_element_ns(Some("http://www.w3.org/2000/svg"), "svg")
+            .expect("can't create svg element")
+            .dyn_into::<SvgsvgElement>()
+            .expect("can't create svg element");
+
+        svg_viewport
+            .set_attribute("x", &format!("{}", target.left))
+            .expect("Can't set x");
+        svg_viewport
+            .set_attribute("y", &format!("{}", target.top))
+            .expect("Can't set y");
+        svg_viewport
+            .set_attribute("width", &format!("{}", target.width()))
+            .expect("Can't set width");
+        svg_viewport
+            .set_attribute("height", &format!("{}", target.height()))
+            .expect("Can't set height");
+        svg_viewport
+            .set_attribute("viewBox", &format!("{} {} {} {}", source.left, source.top, source.width(), source.height()))
+            .expect("Can't set viewBox");
+        svg_viewport
+            .set_attribute("preserveAspectRatio", "none")
+            .expect("Can't set preserveAspectRatio");
+
+        let svg_image = self.document
+            .create_element_ns(Some("http://www.w3.org/2000/svg"), "image")
+            .expect("can't create svg image element")
+            .dyn_into::<SvgImageElement>()
+            .expect("can't create svg image element");
+
+        svg_image
+            .set_attribute("href", bitmap.source.href())
+            .expect("Can't set href");
+        svg_image
+            .set_attribute("width", &format!("{}", bitmap.natural_width))
+            .expect("Can't set width");
+        svg_image
+            .set_attribute("height", &format!("{}", bitmap.natural_height))
+            .expect("Can't set height");
+        svg_image
+            .set_attribute("preserveAspectRatio", "none")
+            .expect("Can't set preserveAspectRatio");
+
+        svg_viewport
+            .append_child(&svg_image.dyn_into::<Node>().expect(""))
+            .expect("");
+
+        self.apply_opacity(&svg_viewport);
+
+        self.svg
+            .append_child(&svg_viewport.dyn_into::<Node>().expect(""))
+            .expect("");
+    }
+
+    /* Following figures go into a group clipped by a rectangle. */
+    fn clip(&mut self, area: Option<&BoundingBox>) {
+        if let Some(root) = self.root.take() {
+            self.svg = root;
+        }
+
+        let Some(area) = area else {
+            return;
+        };
+
+        self.clip_paths += 1;
+        let clip_path_id: String = format!("board-clip-{}", self.clip_paths);
+
+        let clip_path: Element = self.document
+            .create_element_ns(Some("http://www.w3.org/2000/svg"), "clipPath")
+            .expect("can't create svg clipPath element");
+        clip_path.set_id(&clip_path_id);
+
+        let svg_rectangle = self.document
+            .create_element_ns(Some("http://www.w3.org/2000/svg"), "rect")
+            .expect("can't create svg rect element")
+            .dyn_into::<SvgRectElement>()
+            .expect("can't create svg rect element");
+
+        svg_rectangle
+            .set_attribute("x", &format!("{}", area.left))
+            .expect("Can't set x");
+        svg_rectangle
+            .set_attribute("y", &format!("{}", area.top))
+            .expect("Can't set y");
+        svg_rectangle
+            .set_attribute("width", &format!("{}", area.width()))
+            .expect("Can't set width");
+        svg_rectangle
+            .set_attribute("height", &format!("{}", area.height()))
+            .expect("Can't set height");
+
+        clip_path
+            .append_child(&svg_rectangle.dyn_into::<Node>().expect(""))
+            .expect("");
+
+        let group = self.document
+            .create_element_ns(Some("http://www.w3.org/2000/svg"), "g")
+            .expect("can't create svg g element")
+            .dyn_into::<SvggElement>()
+            .expect("can't create svg g element");
+
+        group
+            .set_attribute("clip-path", &format!("url(#{clip_path_id})"))
+            .expect("Can't set clip-path");
+
+        self.svg.append_child(&clip_path).expect("");
+        self.svg
+            .append_child(&group.clone().dyn_into::<Node>().expect(""))
+            .expect("");
+
+        let group: SvgElement = group.dyn_into::<SvgElement>().expect("Can't cast group into svg element");
+        self.root = Some(std::mem::replace(&mut self.svg, group));
+    }
+}
@@ -0,0 +1,131 @@
+use crate::element::text_box_model::TextBoxModel;
+use crate::text::font::Font;
+use crate::text::measure::ApproximateTextMeasure;
+use serde::de::Error;
+use serde_json::{Map, Value};
+
+/* Schema version of boards and entities written by this build. Bump it with a new migration for every change of the saved json. */
+pub const VERSION: u32 = 1;
+
+/* Upgrades json of one entity from the version `from` to the next one. Container children are migrated on their own. */
+pub struct Migration {
+    pub from: u32,
+    pub migrate: fn(&mut Map<String, Value>) -> serde_json::Result<()>,
+}
+
+/* Migrations in version order, the one at index n upgrades version n. */
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 0,
+        migrate: unversioned,
+    },
+];
+
+/* Board json of any earlier version in the current layout. Entities without their own version have the version of the board. */
+pub fn upgrade_document(document: Value) -> serde_json::Result<Value> {
+    upgrade_document_with(document, MIGRATIONS)
+}
+
+/* Entity json of any earlier version in the current layout. Entity without version was saved before versioning. */
+pub fn upgrade_entity(entity: Value) -> serde_json::Result<Value> {
+    upgrade_entity_with(entity, 0, MIGRATIONS)
+}
+
+pub fn upgrade_document_with(mut document: Value, migrations: &[Migration]) -> serde_json::Result<Value> {
+    let Value::Object(fields) = &mut document else {
+        return Err(serde_json::Error::custom("Board is not a json object"));
+    };
+
+    let version: u32 = version_of(fields, 0, target(migrations))?;
+
+    if let Some(Value::Array(entities)) = fields.get_mut("entities") {
+        for entity in entities.iter_mut() {
+            *entity = upgrade_entity_with(entity.take(), version, migrations)?;
+        }
+    }
+
+    fields.insert("version".to_string(), Value::from(target(migrations)));
+
+    Ok(document)
+}
+
+pub fn upgrade_entity_with(mut entity: Value, default_version: u32, migrations: &[Migration]) -> serde_json::Result<Value> {
+    let Value::Object(fields) = &mut entity else {
+        return Err(serde_json::Error::custom("Entity is not a json object"));
+    };
+
+    let version: u32 = version_of(fields, default_version, target(migrations))?;
+
+    for migration in migrations.iter().skip_while(|migration| migration.from < version) {
+        (migration.migrate)(fields)?;
+    }
+
+    /* Children are saved inside the model of the container and were saved with its version. */
+    if let Some(Value::Array(children)) = fields.get_mut("model").and_then(|model| model.get_mut("children")) {
+        for child in children.iter_mut() {
+            *child = upgrade_entity_with(child.take(), version, migrations)?;
+        }
+    }
+
+    fields.insert("version".to_string(), Value::from(target(migrations)));
+
+    Ok(entity)
+}
+
+/* Version the migrations upgrade to. */
+fn target(migrations: &[Migration]) -> u32 {
+    migrations.last().map_or(0, |migration| migration.from + 1)
+}
+
+fn version_of(fields: &Map<String, Value>, default_version: u32, latest: u32) -> serde_json::Result<u32> {
+    let Some(version) = fields.get("version") else {
+        return Ok(default_version);
+    };
+
+    let version: u32 = version
+        .as_u64()
+        .and_then(|version| u32::try_from(version).ok())
+        .ok_or_else(|| serde_json::Error::custom("Schema version is not a number"))?;
+
+    if version > latest {
+        return Err(serde_json::Error::custom(format!("Saved with schema version {version}, this build reads up to {latest}")));
+    }
+
+    Ok(version)
+}
+
+/*
+ * Boards saved before versioning have the layout of version 1, except text. Text was the plain text model, with
+ * the font in separate fields and no layout. It becomes a text box laid out without font metrics, like imported text.
+ */
+fn unversioned(entity: &mut Map<String, Value>) -> serde_json::Result<()> {
+    if entity.get("id").and_then(|id| id.get("element_type")).and_then(Value::as_str) != Some("Text") {
+        return Ok(());
+    }
+
+    let Some(Value::Object(model)) = entity.get("model") else {
+        return Err(serde_json::Error::custom("Text entity has no model"));
+    };
+
+    let number = |field: &str| model.get(field).and_then(Value::as_f64);
+    let default_font: Font = Font::default();
+    let mut text_box: TextBoxModel = TextBoxModel::default();
+
+    text_box.x = number("x").unwrap_or(text_box.x);
+    text_box.y = number("y").unwrap_or(text_box.y);
+    text_box.width = number("width").unwrap_or(text_box.width);
+    text_box.text = model.get("text").and_then(Value::as_str).unwrap_or_default().to_string();
+    text_box.font = Font::new(
+        model.get("font_family").and_then(Value::as_str).unwrap_or(default_font.family.as_str()),
+        number("font_size").unwrap_or(default_font.size),
+        default_font.weight,
+    );
+    if let Some(color) = model.get("color").and_then(Value::as_str) {
+        text_box.color = color.to_string();
+    }
+    text_box.relayout(&ApproximateTextMeasure);
+
+    entity.insert("model".to_string(), serde_json::to_value(text_box)?);
+
+    Ok(())
+}
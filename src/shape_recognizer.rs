@@ -0,0 +1,57 @@
+use crate::element::free_hand;
+use crate::element::free_hand::recognition::{self, Recognition};
+use crate::element::id::Id;
+use crate::element::json_entity::JsonEntity;
+use entity_model_feature::entity::Entity;
+use standard_entity_plugin::model::path_model::PathModel;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::{Arc, RwLock};
+
+/*
+ * Recognizes finished freehand strokes and offers their replacement to the host.
+ * Offers are kept until the host accepts or rejects them.
+ */
+#[derive(Clone)]
+pub struct ShapeRecognizer {
+    threshold: f64,
+    /* Called as callback(stroke_id, shape_name, confidence). */
+    callback: Rc<dyn Fn(&Id, &str, f64)>,
+    offers: Arc<RwLock<HashMap<Id, Recognition>>>,
+}
+
+impl ShapeRecognizer {
+    pub fn new(threshold: f64, callback: impl Fn(&Id, &str, f64) + 'static) -> Self {
+        Self {
+            threshold,
+            callback: Rc::new(callback),
+            offers: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /* Recognition of the raw stroke, if it is confident enough. */
+    pub fn recognize(&self, stroke: &Entity<Id>) -> Option<Recognition> {
+        let json_entity: JsonEntity = JsonEntity::try_from(stroke).ok()?;
+        let path_model: PathModel = serde_json::from_value(json_entity.model).ok()?;
+
+        recognition::recognize(&free_hand::points(&path_model))
+            .filter(|recognition| recognition.confidence >= self.threshold)
+    }
+
+    pub fn offer(&self, stroke_id: &Id, recognition: Recognition) {
+        let shape_name: &str = recognition.shape.name();
+        let confidence: f64 = recognition.confidence;
+
+        let Ok(mut offers) = self.offers.write() else {
+            return;
+        };
+        offers.insert(stroke_id.clone(), recognition);
+        drop(offers);
+
+        (self.callback)(stroke_id, shape_name, confidence);
+    }
+
+    pub fn take(&self, stroke_id: &Id) -> Option<Recognition> {
+        self.offers.write().ok()?.remove(stroke_id)
+    }
+}
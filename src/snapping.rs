@@ -0,0 +1,190 @@
+pub mod grid;
+
+use crate::bounding_box::BoundingBox;
+use crate::element::id::Id;
+use crate::math;
+use crate::renderer::board_renderer::BoardRenderer;
+use crate::snapping::grid::Grid;
+use crate::style::Style;
+use crate::view_port::{LockError, ViewPort};
+use geometry::figure::path::command::line_to::LineTo;
+use geometry::figure::path::command::move_to::MoveTo;
+use geometry::figure::path::command::Command;
+use geometry::figure::path::Path;
+use geometry::point::point_2d::Point2D;
+use standard_rendering_plugin::style::shape_style::ShapeStyle;
+
+const GUIDE_COLOR: &str = "#ff4081";
+/* Entities are looked up this far around the pointer when there is no view box. */
+const SEARCH_DISTANCE: f64 = 1000.0;
+
+/* Line through the snapped point and the entity it is aligned to. */
+#[derive(Copy, Clone)]
+pub struct Guide {
+    pub start: Point2D,
+    pub end: Point2D,
+}
+
+/*
+ * Adjusts pointer positions before they reach the active tool.
+ * Pointer snaps to edges and centres of other entities, otherwise to the grid.
+ * While the angle modifier is held, direction from the pointer down position snaps to angle increments instead.
+ */
+pub struct Snapping {
+    pub grid: Grid,
+    pub to_grid: bool,
+    pub to_entities: bool,
+    /* In radians, zero disables angle snapping. */
+    pub angle_step: f64,
+    /* Distance in board units the pointer is pulled from. */
+    pub threshold: f64,
+    origin: Option<Point2D>,
+    /* Entity under the pointer when dragging started, it is usually the one being moved. */
+    dragged: Option<Id>,
+    guides: Vec<Guide>,
+}
+
+impl Snapping {
+    pub fn new() -> Self {
+        Self {
+            grid: Grid::default(),
+            to_grid: false,
+            to_entities: false,
+            angle_step: std::f64::consts::PI / 12.0,
+            threshold: 8.0,
+            origin: None,
+            dragged: None,
+            guides: vec![],
+        }
+    }
+
+    pub fn pointer_down(&mut self, point: Point2D, view_port: &ViewPort, angle_modifier: bool) -> Result<Point2D, LockError> {
+        self.dragged = view_port.entities_at(point.x, point.y)?.into_iter().next();
+
+        let point: Point2D = self.snap(point, view_port, angle_modifier)?;
+        self.origin = Some(point);
+
+        Ok(point)
+    }
+
+    pub fn pointer_move(&mut self, point: Point2D, view_port: &ViewPort, angle_modifier: bool) -> Result<Point2D, LockError> {
+        self.snap(point, view_port, angle_modifier)
+    }
+
+    pub fn pointer_up(&mut self, point: Point2D, view_port: &ViewPort, angle_modifier: bool) -> Result<Point2D, LockError> {
+        let point: Point2D = self.snap(point, view_port, angle_modifier)?;
+
+        self.origin = None;
+        self.dragged = None;
+        self.guides.clear();
+
+        Ok(point)
+    }
+
+    /* Smart guides are shown only while dragging. */
+    pub fn render(&self, renderer: &mut dyn BoardRenderer) {
+        if self.origin.is_none() || self.guides.is_empty() {
+            return;
+        }
+
+        let commands: Vec<Command> = self.guides
+            .iter()
+            .flat_map(|guide| [
+                Command::MoveTo(MoveTo { to_point: guide.start }),
+                Command::LineTo(LineTo { to_point: guide.end }),
+            ])
+            .collect();
+
+        let style: Style = Style {
+            stroke_color: GUIDE_COLOR.to_string(),
+            dash_array: vec![4.0, 4.0],
+            ..Style::default()
+        };
+
+        renderer.set_style(Some(&style));
+        renderer.path(&Path::new(commands), &ShapeStyle::default(), None);
+        renderer.set_style(None);
+    }
+
+    fn snap(&mut self, point: Point2D, view_port: &ViewPort, angle_modifier: bool) -> Result<Point2D, LockError> {
+        self.guides.clear();
+
+        if let (true, Some(origin)) = (angle_modifier && self.angle_step > 0.0, self.origin) {
+            let angle: f64 = ((point.y - origin.y).atan2(point.x - origin.x) / self.angle_step).round() * self.angle_step;
+            let length: f64 = math::distance(&origin, &point);
+
+            return Ok(Point2D::new(origin.x + length * angle.cos(), origin.y + length * angle.sin()));
+        }
+
+        let (mut x, mut y) = (None, None);
+
+        if self.to_entities {
+            (x, y) = self.snap_to_entities(&point, view_port)?;
+        }
+
+        if self.to_grid && self.grid.spacing > 0.0 {
+            x = x.or(Some(self.grid.snap(point.x)));
+            y = y.or(Some(self.grid.snap(point.y)));
+        }
+
+        Ok(Point2D::new(x.unwrap_or(point.x), y.unwrap_or(point.y)))
+    }
+
+    /* Closest edge or centre within threshold on each axis, guides are added for both. */
+    fn snap_to_entities(&mut self, point: &Point2D, view_port: &ViewPort) -> Result<(Option<f64>, Option<f64>), LockError> {
+        let area: BoundingBox = view_port
+            .view_box()?
+            .unwrap_or(BoundingBox::from_point(point.x, point.y).inflate(SEARCH_DISTANCE));
+
+        let mut best_x: Option<(f64, BoundingBox)> = None;
+        let mut best_y: Option<(f64, BoundingBox)> = None;
+
+        for id in view_port.entities_in(&area)? {
+            if self.dragged.as_ref() == Some(&id) {
+                continue;
+            }
+
+            let Some(bounding_box) = view_port.bounding_box(&id)? else {
+                continue;
+            };
+            let (center_x, center_y) = bounding_box.center();
+
+            for x in [bounding_box.left, center_x, bounding_box.right] {
+                if Self::is_closer(point.x, x, best_x.map(|(best, _)| best), self.threshold) {
+                    best_x = Some((x, bounding_box));
+                }
+            }
+
+            for y in [bounding_box.top, center_y, bounding_box.bottom] {
+                if Self::is_closer(point.y, y, best_y.map(|(best, _)| best), self.threshold) {
+                    best_y = Some((y, bounding_box));
+                }
+            }
+        }
+
+        let snapped_x: f64 = best_x.map_or(point.x, |(x, _)| x);
+        let snapped_y: f64 = best_y.map_or(point.y, |(y, _)| y);
+
+        if let Some((x, bounding_box)) = best_x {
+            self.guides.push(Guide {
+                start: Point2D::new(x, snapped_y.min(bounding_box.top)),
+                end: Point2D::new(x, snapped_y.max(bounding_box.bottom)),
+            });
+        }
+
+        if let Some((y, bounding_box)) = best_y {
+            self.guides.push(Guide {
+                start: Point2D::new(snapped_x.min(bounding_box.left), y),
+                end: Point2D::new(snapped_x.max(bounding_box.right), y),
+            });
+        }
+
+        Ok((best_x.map(|(x, _)| x), best_y.map(|(y, _)| y)))
+    }
+
+    fn is_closer(coordinate: f64, candidate: f64, best: Option<f64>, threshold: f64) -> bool {
+        let distance: f64 = (coordinate - candidate).abs();
+
+        distance <= threshold && best.is_none_or(|best| distance < (coordinate - best).abs())
+    }
+}
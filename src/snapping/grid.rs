@@ -0,0 +1,104 @@
+use crate::bounding_box::BoundingBox;
+use crate::renderer::board_renderer::BoardRenderer;
+use crate::style::Style;
+use geometry::figure::path::command::line_to::LineTo;
+use geometry::figure::path::command::move_to::MoveTo;
+use geometry::figure::path::command::Command;
+use geometry::figure::path::Path;
+use geometry::point::point_2d::Point2D;
+use serde::{Deserialize, Serialize};
+use standard_rendering_plugin::style::shape_style::ShapeStyle;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+const GRID_COLOR: &str = "#e0e0e0";
+const DOT_SIZE: f64 = 2.0;
+/* Grid denser than this on screen is not drawn. */
+const MAX_CELLS: f64 = 200.0;
+
+#[derive(Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub enum GridKind {
+    Lines,
+    Dots,
+}
+
+#[derive(Copy, Clone)]
+pub struct Grid {
+    pub kind: GridKind,
+    pub spacing: f64,
+    pub visible: bool,
+}
+
+impl Grid {
+    /* Nearest grid coordinate. */
+    pub fn snap(&self, coordinate: f64) -> f64 {
+        (coordinate / self.spacing).round() * self.spacing
+    }
+
+    /* Draw grid over the area. */
+    pub fn render(&self, area: &BoundingBox, renderer: &mut dyn BoardRenderer) {
+        if !self.visible || self.spacing <= 0.0 {
+            return;
+        }
+
+        if area.width() / self.spacing > MAX_CELLS || area.height() / self.spacing > MAX_CELLS {
+            return;
+        }
+
+        let first_x: f64 = (area.left / self.spacing).floor() * self.spacing;
+        let first_y: f64 = (area.top / self.spacing).floor() * self.spacing;
+
+        let xs: Vec<f64> = (0..)
+            .map(|index| first_x + index as f64 * self.spacing)
+            .take_while(|x| *x <= area.right)
+            .collect();
+        let ys: Vec<f64> = (0..)
+            .map(|index| first_y + index as f64 * self.spacing)
+            .take_while(|y| *y <= area.bottom)
+            .collect();
+
+        match self.kind {
+            GridKind::Lines => {
+                let vertical = xs.iter().flat_map(|x| [Point2D::new(*x, area.top), Point2D::new(*x, area.bottom)]);
+                let horizontal = ys.iter().flat_map(|y| [Point2D::new(area.left, *y), Point2D::new(area.right, *y)]);
+
+                let commands: Vec<Command> = vertical
+                    .chain(horizontal)
+                    .enumerate()
+                    .map(|(index, point)| match index % 2 {
+                        0 => Command::MoveTo(MoveTo { to_point: point }),
+                        _ => Command::LineTo(LineTo { to_point: point }),
+                    })
+                    .collect();
+
+                let style: Style = Style {
+                    stroke_color: GRID_COLOR.to_string(),
+                    ..Style::default()
+                };
+
+                renderer.set_style(Some(&style));
+                renderer.path(&Path::new(commands), &ShapeStyle::default(), None);
+                renderer.set_style(None);
+            }
+            GridKind::Dots => {
+                for x in &xs {
+                    for y in &ys {
+                        let dot: BoundingBox = BoundingBox::new(x - DOT_SIZE / 2.0, y - DOT_SIZE / 2.0, DOT_SIZE, DOT_SIZE);
+                        renderer.fill_rectangle(&dot, GRID_COLOR);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self {
+            kind: GridKind::Lines,
+            spacing: 20.0,
+            visible: false,
+        }
+    }
+}
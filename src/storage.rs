@@ -0,0 +1,71 @@
+pub mod memory_storage;
+#[cfg(feature = "wasm")]
+pub mod js_storage;
+pub mod autosave;
+
+use crate::document::DocumentError;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::JsValue;
+
+#[derive(Debug)]
+pub enum StorageError {
+    /* Rejected or thrown by the host adapter. */
+    #[cfg(feature = "wasm")]
+    Host(JsValue),
+    Json(serde_json::Error),
+    Document(DocumentError),
+}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(error: serde_json::Error) -> Self {
+        StorageError::Json(error)
+    }
+}
+
+impl From<DocumentError> for StorageError {
+    fn from(error: DocumentError) -> Self {
+        StorageError::Document(error)
+    }
+}
+
+pub type StorageFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, StorageError>> + 'a>>;
+
+/* Saved revision of the board. */
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub revision: u32,
+    /* Milliseconds since the epoch. */
+    pub saved_at: f64,
+    /* Board document json. */
+    pub json: String,
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub struct SnapshotInfo {
+    pub revision: u32,
+    pub saved_at: f64,
+}
+
+impl From<&Snapshot> for SnapshotInfo {
+    fn from(snapshot: &Snapshot) -> Self {
+        Self {
+            revision: snapshot.revision,
+            saved_at: snapshot.saved_at,
+        }
+    }
+}
+
+/* Persistent store of board snapshots. Operations are asynchronous, as storages in browsers are. */
+pub trait Storage {
+    fn put(&self, snapshot: Snapshot) -> StorageFuture<'_, ()>;
+
+    /* Saved revisions in any order. */
+    fn revisions(&self) -> StorageFuture<'_, Vec<SnapshotInfo>>;
+
+    fn get(&self, revision: u32) -> StorageFuture<'_, Option<Snapshot>>;
+
+    fn delete(&self, revision: u32) -> StorageFuture<'_, ()>;
+}
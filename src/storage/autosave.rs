@@ -0,0 +1,99 @@
+use crate::document::Document;
+use crate::platform;
+use crate::storage::{Snapshot, SnapshotInfo, Storage, StorageError};
+use crate::view_port::ViewPort;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+/*
+ * Saves the board a while after the last change, keeping only the latest revisions.
+ * Unchanged board is not saved again.
+ */
+#[derive(Clone)]
+pub struct Autosave {
+    storage: Rc<dyn Storage>,
+    view_port: ViewPort,
+    /* In milliseconds. */
+    delay: i32,
+    max_revisions: usize,
+    /* Incremented on every change, pending save is dropped if another change comes before it runs. */
+    generation: Rc<Cell<u64>>,
+    last_saved: Rc<RefCell<Option<String>>>,
+}
+
+impl Autosave {
+    pub fn new(storage: Rc<dyn Storage>, view_port: ViewPort, delay: i32, max_revisions: usize) -> Self {
+        Self {
+            storage,
+            view_port,
+            delay,
+            max_revisions: max_revisions.max(1),
+            generation: Rc::new(Cell::new(0)),
+            last_saved: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    /* Save after the delay unless the board changes again meanwhile. */
+    pub fn schedule(&self) {
+        let generation: u64 = self.generation.get() + 1;
+        self.generation.set(generation);
+
+        let autosave: Autosave = self.clone();
+
+        platform::spawn_local(async move {
+            platform::sleep(autosave.delay).await;
+
+            if autosave.generation.get() == generation {
+                let _ = autosave.save().await;
+            }
+        });
+    }
+
+    /* Returns the saved revision, None if the board didn't change since the last save. */
+    pub async fn save(&self) -> Result<Option<u32>, StorageError> {
+        let json: String = Document::capture(&self.view_port)?.to_json()?;
+
+        if self.last_saved.borrow().as_deref() == Some(json.as_str()) {
+            return Ok(None);
+        }
+
+        let revision: u32 = self.revisions().await?.first().map_or(1, |latest| latest.revision + 1);
+
+        self.storage.put(Snapshot { revision, saved_at: platform::now(), json: json.clone() }).await?;
+        *self.last_saved.borrow_mut() = Some(json);
+
+        for outdated in self.revisions().await?.iter().skip(self.max_revisions) {
+            self.storage.delete(outdated.revision).await?;
+        }
+
+        Ok(Some(revision))
+    }
+
+    /* Newest first. */
+    pub async fn revisions(&self) -> Result<Vec<SnapshotInfo>, StorageError> {
+        let mut revisions: Vec<SnapshotInfo> = self.storage.revisions().await?;
+        revisions.sort_by(|a, b| b.revision.cmp(&a.revision));
+
+        Ok(revisions)
+    }
+
+    /* Load the revision, the latest one if None. Returns the restored revision, None if there is no such. */
+    pub async fn restore(&self, revision: Option<u32>) -> Result<Option<u32>, StorageError> {
+        let revision: Option<u32> = match revision {
+            Some(revision) => Some(revision),
+            None => self.revisions().await?.first().map(|latest| latest.revision),
+        };
+
+        let Some(revision) = revision else {
+            return Ok(None);
+        };
+        let Some(snapshot) = self.storage.get(revision).await? else {
+            return Ok(None);
+        };
+
+        Document::from_json(&snapshot.json)?.restore(&mut self.view_port.clone())?;
+        *self.last_saved.borrow_mut() = Some(snapshot.json);
+
+        Ok(Some(revision))
+    }
+}
@@ -0,0 +1,79 @@
+use crate::storage::{Snapshot, SnapshotInfo, Storage, StorageError, StorageFuture};
+use js_sys::{Array, Function, Object, Promise, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/*
+ * Storage implemented by the host, e.g. on top of IndexedDB or localStorage.
+ * Adapter object has methods, each of them may return a value or a promise of it:
+ * put(revision, saved_at, json), list() returning [{revision, saved_at}],
+ * get(revision) returning json string or null, and delete(revision).
+ */
+pub struct JsStorage {
+    adapter: Object,
+}
+
+impl JsStorage {
+    pub fn new(adapter: Object) -> Self {
+        Self { adapter }
+    }
+
+    async fn call(&self, method: &str, arguments: &[JsValue]) -> Result<JsValue, StorageError> {
+        let function: Function = Reflect::get(&self.adapter, &JsValue::from_str(method))
+            .map_err(StorageError::Host)?
+            .dyn_into()
+            .map_err(StorageError::Host)?;
+
+        let result: JsValue = function
+            .apply(&self.adapter, &arguments.iter().collect::<Array>())
+            .map_err(StorageError::Host)?;
+
+        JsFuture::from(Promise::resolve(&result)).await.map_err(StorageError::Host)
+    }
+}
+
+impl Storage for JsStorage {
+    fn put(&self, snapshot: Snapshot) -> StorageFuture<'_, ()> {
+        Box::pin(async move {
+            let arguments: [JsValue; 3] = [
+                JsValue::from(snapshot.revision),
+                JsValue::from_f64(snapshot.saved_at),
+                JsValue::from_str(&snapshot.json),
+            ];
+
+            self.call("put", &arguments).await?;
+
+            Ok(())
+        })
+    }
+
+    fn revisions(&self) -> StorageFuture<'_, Vec<SnapshotInfo>> {
+        Box::pin(async move {
+            let revisions: JsValue = self.call("list", &[]).await?;
+            let json: String = js_sys::JSON::stringify(&revisions).map_err(StorageError::Host)?.into();
+
+            Ok(serde_json::from_str(&json)?)
+        })
+    }
+
+    fn get(&self, revision: u32) -> StorageFuture<'_, Option<Snapshot>> {
+        Box::pin(async move {
+            let json: JsValue = self.call("get", &[JsValue::from(revision)]).await?;
+
+            Ok(json.as_string().map(|json| Snapshot {
+                revision,
+                /* Time is only needed for listing. */
+                saved_at: 0.0,
+                json,
+            }))
+        })
+    }
+
+    fn delete(&self, revision: u32) -> StorageFuture<'_, ()> {
+        Box::pin(async move {
+            self.call("delete", &[JsValue::from(revision)]).await?;
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,43 @@
+use crate::storage::{Snapshot, SnapshotInfo, Storage, StorageFuture};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::future;
+use std::rc::Rc;
+
+/* Storage kept in memory, it is lost on reload. Useful for tests and hosts without persistent storage. */
+#[derive(Clone, Default)]
+pub struct MemoryStorage {
+    snapshots: Rc<RefCell<BTreeMap<u32, Snapshot>>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn put(&self, snapshot: Snapshot) -> StorageFuture<'_, ()> {
+        self.snapshots.borrow_mut().insert(snapshot.revision, snapshot);
+
+        Box::pin(future::ready(Ok(())))
+    }
+
+    fn revisions(&self) -> StorageFuture<'_, Vec<SnapshotInfo>> {
+        let revisions: Vec<SnapshotInfo> = self.snapshots.borrow().values().map(SnapshotInfo::from).collect();
+
+        Box::pin(future::ready(Ok(revisions)))
+    }
+
+    fn get(&self, revision: u32) -> StorageFuture<'_, Option<Snapshot>> {
+        let snapshot: Option<Snapshot> = self.snapshots.borrow().get(&revision).cloned();
+
+        Box::pin(future::ready(Ok(snapshot)))
+    }
+
+    fn delete(&self, revision: u32) -> StorageFuture<'_, ()> {
+        self.snapshots.borrow_mut().remove(&revision);
+
+        Box::pin(future::ready(Ok(())))
+    }
+}
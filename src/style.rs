@@ -0,0 +1,128 @@
+use crate::element::id::Id;
+use entity_model_feature::feature_set::FeatureSet;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+
+#[derive(Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LineCap {
+    Butt,
+    Round,
+    Square,
+}
+
+impl LineCap {
+    pub fn css(&self) -> &'static str {
+        match self {
+            LineCap::Butt => "butt",
+            LineCap::Round => "round",
+            LineCap::Square => "square",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+impl LineJoin {
+    pub fn css(&self) -> &'static str {
+        match self {
+            LineJoin::Miter => "miter",
+            LineJoin::Round => "round",
+            LineJoin::Bevel => "bevel",
+        }
+    }
+}
+
+/*
+ * Appearance of an entity, applied by renderers on top of the figures the entity draws.
+ * Colors are css colors. Missing json fields take default values.
+ */
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Style {
+    /* None leaves figures unfilled. */
+    pub fill_color: Option<String>,
+    pub stroke_color: String,
+    pub stroke_width: f64,
+    pub dash_array: Vec<f64>,
+    /* In range 0..=1. */
+    pub opacity: f64,
+    pub line_cap: LineCap,
+    pub line_join: LineJoin,
+    /* Applies to rectangles. */
+    pub corner_radius: f64,
+}
+
+impl Style {
+    /* Copy of the style with fields present in the json object replaced. */
+    pub fn patched(&self, patch: &str) -> serde_json::Result<Style> {
+        let mut style: serde_json::Value = serde_json::to_value(self)?;
+        let patch: serde_json::Map<String, serde_json::Value> = serde_json::from_str(patch)?;
+
+        if let serde_json::Value::Object(fields) = &mut style {
+            fields.extend(patch);
+        }
+
+        let mut style: Style = serde_json::from_value(style)?;
+        style.opacity = style.opacity.clamp(0.0, 1.0);
+        style.stroke_width = style.stroke_width.max(0.0);
+        style.corner_radius = style.corner_radius.max(0.0);
+
+        Ok(style)
+    }
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            fill_color: None,
+            stroke_color: "#000000".to_string(),
+            stroke_width: 1.0,
+            dash_array: vec![],
+            opacity: 1.0,
+            line_cap: LineCap::Butt,
+            line_join: LineJoin::Miter,
+            corner_radius: 0.0,
+        }
+    }
+}
+
+/* Entity feature holding the entity style. Entities without it are drawn with renderer defaults. */
+pub struct EntityStyle {
+    pub style: Style,
+}
+
+pub fn style_feature_set(style: Style) -> FeatureSet<Id> {
+    let mut feature_set: FeatureSet<Id> = FeatureSet::empty();
+    feature_set.add_feature(EntityStyle { style });
+
+    feature_set
+}
+
+/* Style newly built entities get, shared by the board with its tools. None keeps renderer defaults. */
+#[derive(Clone, Default)]
+pub struct CurrentStyle {
+    style: Arc<RwLock<Option<Style>>>,
+}
+
+impl CurrentStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self) -> Option<Style> {
+        self.style.read().ok().and_then(|style| style.clone())
+    }
+
+    pub fn set(&self, style: Option<Style>) {
+        if let Ok(mut current_style) = self.style.write() {
+            *current_style = style;
+        }
+    }
+}
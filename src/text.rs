@@ -0,0 +1,4 @@
+pub mod font;
+pub mod layout;
+pub mod measure;
+pub mod text_editor;
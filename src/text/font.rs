@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+const LINE_HEIGHT: f64 = 1.2;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct Font {
+    pub family: String,
+    pub size: f64,
+    /* CSS font weight, 400 is normal and 700 is bold. */
+    pub weight: u16,
+}
+
+impl Font {
+    pub fn new(family: &str, size: f64, weight: u16) -> Self {
+        Self {
+            family: family.to_string(),
+            size,
+            weight,
+        }
+    }
+
+    /* Font shorthand understood by both canvas and css. */
+    pub fn css(&self) -> String {
+        format!("{} {}px {}", self.weight, self.size, self.family)
+    }
+
+    pub fn line_height(&self) -> f64 {
+        self.size * LINE_HEIGHT
+    }
+}
+
+impl Default for Font {
+    fn default() -> Self {
+        Self::new("sans-serif", 16.0, 400)
+    }
+}
@@ -0,0 +1,129 @@
+use crate::text::font::Font;
+use crate::text::measure::TextMeasure;
+use serde::{Deserialize, Serialize};
+
+/* One visual line of the text. Position is the top left corner of the line box. */
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct TextLine {
+    pub text: String,
+    /* Index of the first line character in the whole text, in chars. */
+    pub start: usize,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+}
+
+impl TextLine {
+    pub fn end(&self) -> usize {
+        self.start + self.text.chars().count()
+    }
+}
+
+/*
+ * Break text into lines not wider than max_width.
+ * Lines are broken on explicit new lines, then between words, and inside words only if a word doesn't fit alone.
+ */
+pub fn layout(text: &str, font: &Font, x: f64, y: f64, max_width: f64, measure: &dyn TextMeasure) -> Vec<TextLine> {
+    let mut lines: Vec<(String, usize)> = vec![];
+    let mut paragraph_start: usize = 0;
+
+    for paragraph in text.split('\n') {
+        wrap_paragraph(paragraph, paragraph_start, font, max_width, measure, &mut lines);
+
+        /* Skip the new line character itself. */
+        paragraph_start += paragraph.chars().count() + 1;
+    }
+
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(index, (text, start))| TextLine {
+            width: measure.width(text.trim_end(), font),
+            text,
+            start,
+            x,
+            y: y + index as f64 * font.line_height(),
+        })
+        .collect()
+}
+
+fn wrap_paragraph(paragraph: &str, start: usize, font: &Font, max_width: f64, measure: &dyn TextMeasure, lines: &mut Vec<(String, usize)>) {
+    let mut line: String = String::new();
+    let mut line_start: usize = start;
+
+    /* Words keep their trailing spaces, so char offsets stay continuous. */
+    for word in paragraph.split_inclusive(' ') {
+        let candidate: String = format!("{line}{word}");
+
+        if line.is_empty() || measure.width(candidate.trim_end(), font) <= max_width {
+            line = candidate;
+        } else {
+            let length: usize = line.chars().count();
+            lines.push((std::mem::take(&mut line), line_start));
+            line_start += length;
+            line = word.to_string();
+        }
+
+        /* Break the word itself if it doesn't fit even on its own line. */
+        while measure.width(line.trim_end(), font) > max_width && line.trim_end().chars().count() > 1 {
+            let fitting: usize = fitting_chars(&line, font, max_width, measure);
+            let rest: String = line.chars().skip(fitting).collect();
+
+            lines.push((line.chars().take(fitting).collect(), line_start));
+            line_start += fitting;
+            line = rest;
+        }
+    }
+
+    lines.push((line, line_start));
+}
+
+/* Number of leading chars fitting into the width, at least one. */
+fn fitting_chars(text: &str, font: &Font, max_width: f64, measure: &dyn TextMeasure) -> usize {
+    let chars: Vec<char> = text.chars().collect();
+
+    (1..chars.len())
+        .take_while(|count| measure.width(&chars[..=*count].iter().collect::<String>(), font) <= max_width)
+        .last()
+        .map_or(1, |count| count + 1)
+}
+
+/* Line containing the caret. Caret at the end of a line belongs to that line. */
+pub fn line_of(lines: &[TextLine], index: usize) -> usize {
+    lines
+        .iter()
+        .rposition(|line| line.start <= index)
+        .unwrap_or(0)
+}
+
+/* Top of the caret placed before the char with the index. */
+pub fn caret_position(lines: &[TextLine], index: usize, font: &Font, measure: &dyn TextMeasure) -> Option<(f64, f64)> {
+    let line: &TextLine = lines.get(line_of(lines, index))?;
+    let prefix: String = line.text.chars().take(index.saturating_sub(line.start)).collect();
+
+    Some((line.x + measure.width(&prefix, font), line.y))
+}
+
+/* Caret index closest to the point. */
+pub fn index_at(lines: &[TextLine], x: f64, y: f64, font: &Font, measure: &dyn TextMeasure) -> usize {
+    let Some(first) = lines.first() else {
+        return 0;
+    };
+
+    let line_index: usize = (((y - first.y) / font.line_height()).floor().max(0.0) as usize).min(lines.len() - 1);
+    let line: &TextLine = &lines[line_index];
+
+    /* Trailing space of the wrapped line is not a caret position, the next line starts there. */
+    let is_wrapped: bool = lines.get(line_index + 1).is_some_and(|next| next.start == line.end());
+    let length: usize = line.text.chars().count() - usize::from(is_wrapped && line.text.ends_with(' '));
+
+    let offset: usize = (0..=length)
+        .map(|count| {
+            let prefix: String = line.text.chars().take(count).collect();
+            (count, (line.x + measure.width(&prefix, font) - x).abs())
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map_or(0, |(count, _)| count);
+
+    line.start + offset
+}
@@ -0,0 +1,63 @@
+use crate::text::font::Font;
+#[cfg(feature = "dom-renderers")]
+use wasm_bindgen::JsCast;
+#[cfg(feature = "dom-renderers")]
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+/*
+ * Text is measured once while laying it out, renderers only draw already broken lines.
+ * That's what keeps canvas and svg output identical.
+ */
+pub trait TextMeasure {
+    fn width(&self, text: &str, font: &Font) -> f64;
+}
+
+/* Measures text with browser font metrics using a detached canvas. */
+#[cfg(feature = "dom-renderers")]
+pub struct CanvasTextMeasure {
+    context: CanvasRenderingContext2d,
+}
+
+#[cfg(feature = "dom-renderers")]
+impl CanvasTextMeasure {
+    pub fn new() -> Self {
+        let window = web_sys::window().expect("global window does not exists");
+        let document = window.document().expect("global document does not exists");
+
+        let context = document
+            .create_element("canvas")
+            .expect("Can't create canvas for text measurement")
+            .dyn_into::<HtmlCanvasElement>()
+            .expect("Can't cast element into canvas")
+            .get_context("2d")
+            .unwrap()
+            .unwrap()
+            .dyn_into::<CanvasRenderingContext2d>()
+            .unwrap();
+
+        Self { context }
+    }
+}
+
+#[cfg(feature = "dom-renderers")]
+impl TextMeasure for CanvasTextMeasure {
+    fn width(&self, text: &str, font: &Font) -> f64 {
+        self.context.set_font(&font.css());
+
+        self.context
+            .measure_text(text)
+            .map(|metrics| metrics.width())
+            .unwrap_or(0.0)
+    }
+}
+
+/* Font independent estimation, for environments without font metrics. */
+pub struct ApproximateTextMeasure;
+
+impl TextMeasure for ApproximateTextMeasure {
+    fn width(&self, text: &str, font: &Font) -> f64 {
+        let advance: f64 = if font.weight >= 600 { 0.6 } else { 0.55 };
+
+        text.chars().count() as f64 * font.size * advance
+    }
+}
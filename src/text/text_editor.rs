@@ -0,0 +1,311 @@
+use crate::element::id::Id;
+use crate::element::json_entity::JsonEntity;
+use crate::element::text_box_model::TextBoxModel;
+use crate::element::Build;
+use crate::history::{Change, History, HistoryError, Step};
+use crate::text::font::Font;
+use crate::text::layout::{self, TextLine};
+use crate::text::measure::TextMeasure;
+use crate::view_port::ViewPort;
+use geometry::figure::segment::Segment;
+use geometry::point::point_2d::Point2D;
+use standard_rendering_plugin::renderer::renderer::Renderer;
+use standard_rendering_plugin::style::shape_style::ShapeStyle;
+use std::sync::{Arc, RwLock};
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum CaretMove {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/* Text box being edited. Caret and anchor are char indices, selection lies between them. */
+struct Editing {
+    id: Id,
+    before: JsonEntity,
+    model: TextBoxModel,
+    caret: usize,
+    anchor: usize,
+}
+
+impl Editing {
+    fn selection(&self) -> (usize, usize) {
+        (self.caret.min(self.anchor), self.caret.max(self.anchor))
+    }
+
+    fn collapse(&mut self, index: usize) {
+        self.caret = index;
+        self.anchor = index;
+    }
+
+    fn replace_selection(&mut self, text: &str) {
+        let (start, end) = self.selection();
+        let (start_byte, end_byte) = (byte_index(&self.model.text, start), byte_index(&self.model.text, end));
+
+        self.model.text.replace_range(start_byte..end_byte, text);
+        self.collapse(start + text.chars().count());
+    }
+}
+
+#[derive(Default)]
+struct State {
+    editing: Option<Editing>,
+    /* Caret moves extend the selection, usually while shift is held. */
+    extend_selection: bool,
+}
+
+/*
+ * In-place editor of text boxes.
+ * Every edit is applied to the view port immediately, the whole editing session is recorded as one history step.
+ */
+#[derive(Clone)]
+pub struct TextEditor {
+    state: Arc<RwLock<State>>,
+    measure: Arc<dyn TextMeasure>,
+    view_port: ViewPort,
+    history: History,
+}
+
+impl TextEditor {
+    pub fn new(measure: Arc<dyn TextMeasure>, view_port: ViewPort, history: History) -> Self {
+        Self {
+            state: Arc::new(RwLock::new(State::default())),
+            measure,
+            view_port,
+            history,
+        }
+    }
+
+    pub fn measure(&self) -> &dyn TextMeasure {
+        self.measure.as_ref()
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.state.read().is_ok_and(|state| state.editing.is_some())
+    }
+
+    /* Whether the point is inside of the text box being edited. */
+    pub fn contains(&self, point: &Point2D) -> bool {
+        self.state.read().is_ok_and(|state| state.editing.as_ref().is_some_and(|editing| {
+            let model: &TextBoxModel = &editing.model;
+
+            model.x <= point.x && point.x <= model.x + model.width &&
+            model.y <= point.y && point.y <= model.y + model.height
+        }))
+    }
+
+    /* Start editing the text box with caret at the end. Returns false if the entity is not a text box. */
+    pub fn begin(&self, id: &Id) -> Result<bool, HistoryError> {
+        self.finish()?;
+
+        let Some(before) = self.view_port.with_entity(id, JsonEntity::try_from)? else {
+            return Ok(false);
+        };
+        let before: JsonEntity = before?;
+
+        let Ok(model) = serde_json::from_value::<TextBoxModel>(before.model.clone()) else {
+            return Ok(false);
+        };
+
+        let end: usize = model.text.chars().count();
+        self.state.write().map_err(|_| HistoryError::Lock)?.editing = Some(Editing {
+            id: id.clone(),
+            before,
+            model,
+            caret: end,
+            anchor: end,
+        });
+
+        Ok(true)
+    }
+
+    /* Stop editing and record changes. Text box left empty is removed. */
+    pub fn finish(&self) -> Result<(), HistoryError> {
+        let Some(editing) = self.state.write().map_err(|_| HistoryError::Lock)?.editing.take() else {
+            return Ok(());
+        };
+
+        let mut view_port: ViewPort = self.view_port.clone();
+        let mut history: History = self.history.clone();
+
+        if editing.model.text.trim().is_empty() {
+            view_port.remove_entity(&editing.id)?;
+            history.push(Step::from(Change::Remove(editing.before)))?;
+
+            return Ok(());
+        }
+
+        let after: JsonEntity = editing.before.with_model(serde_json::to_value(&editing.model)?);
+
+        if after.model != editing.before.model {
+            history.push(Step::from(Change::modified(&editing.before, &after)))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn set_extend_selection(&self, extend_selection: bool) {
+        if let Ok(mut state) = self.state.write() {
+            state.extend_selection = extend_selection;
+        }
+    }
+
+    /* Replace selection with the text. Used for typed and IME composed input. */
+    pub fn insert(&self, text: &str) -> Result<(), HistoryError> {
+        self.edit(|editing, _| editing.replace_selection(text))
+    }
+
+    pub fn delete_backward(&self) -> Result<(), HistoryError> {
+        self.edit(|editing, _| {
+            if editing.caret == editing.anchor && editing.caret > 0 {
+                editing.anchor = editing.caret - 1;
+            }
+
+            editing.replace_selection("");
+        })
+    }
+
+    pub fn delete_forward(&self) -> Result<(), HistoryError> {
+        self.edit(|editing, _| {
+            if editing.caret == editing.anchor && editing.caret < editing.model.text.chars().count() {
+                editing.anchor = editing.caret + 1;
+            }
+
+            editing.replace_selection("");
+        })
+    }
+
+    pub fn set_font(&self, font: Font) -> Result<(), HistoryError> {
+        self.edit(|editing, _| editing.model.font = font)
+    }
+
+    /* Select chars in range start..end. */
+    pub fn select(&self, start: usize, end: usize) -> Result<(), HistoryError> {
+        self.navigate(|editing, _, _| {
+            let length: usize = editing.model.text.chars().count();
+
+            editing.anchor = start.min(length);
+            editing.caret = end.min(length);
+        })
+    }
+
+    pub fn move_caret(&self, caret_move: CaretMove) -> Result<(), HistoryError> {
+        self.navigate(|editing, measure, extend_selection| {
+            let (start, end) = editing.selection();
+            let length: usize = editing.model.text.chars().count();
+            let model: &TextBoxModel = &editing.model;
+
+            let caret: usize = match caret_move {
+                /* Collapse selection to its side instead of moving. */
+                CaretMove::Left if !extend_selection && start != end => start,
+                CaretMove::Right if !extend_selection && start != end => end,
+                CaretMove::Left => editing.caret.saturating_sub(1),
+                CaretMove::Right => (editing.caret + 1).min(length),
+                CaretMove::Up | CaretMove::Down => {
+                    let Some((x, y)) = layout::caret_position(&model.lines, editing.caret, &model.font, measure) else {
+                        return;
+                    };
+
+                    let line_height: f64 = model.font.line_height();
+                    let y: f64 = match caret_move {
+                        CaretMove::Up => y - line_height / 2.0,
+                        _ => y + line_height * 1.5,
+                    };
+
+                    layout::index_at(&model.lines, x, y, &model.font, measure)
+                }
+            };
+
+            editing.caret = caret;
+            if !extend_selection {
+                editing.anchor = caret;
+            }
+        })
+    }
+
+    /* Move caret to the point, optionally extending selection, e.g. while dragging. */
+    pub fn place_caret(&self, point: &Point2D, extend_selection: bool) -> Result<(), HistoryError> {
+        self.navigate(|editing, measure, _| {
+            let model: &TextBoxModel = &editing.model;
+            let caret: usize = layout::index_at(&model.lines, point.x, point.y, &model.font, measure);
+
+            editing.caret = caret;
+            if !extend_selection {
+                editing.anchor = caret;
+            }
+        })
+    }
+
+    pub fn render(&self, renderer: &mut dyn Renderer) {
+        let Ok(state) = self.state.try_read() else {
+            return;
+        };
+        let Some(editing) = &state.editing else {
+            return;
+        };
+
+        let model: &TextBoxModel = &editing.model;
+        let measure: &dyn TextMeasure = self.measure();
+        let line_height: f64 = model.font.line_height();
+        let style: ShapeStyle = ShapeStyle::default();
+
+        let (x, y) = layout::caret_position(&model.lines, editing.caret, &model.font, measure)
+            .unwrap_or((model.x, model.y));
+        renderer.segment_2d(&Segment::new(Point2D::new(x, y), Point2D::new(x, y + line_height)), &style, None);
+
+        /* Selection is underlined on every line it spans. */
+        let (start, end) = editing.selection();
+        for line in model.lines.iter().filter(|line| line.start < end && start < line.end()) {
+            let from: f64 = prefix_width(line, start, &model.font, measure);
+            let to: f64 = prefix_width(line, end, &model.font, measure);
+            let bottom: f64 = line.y + line_height;
+
+            renderer.segment_2d(&Segment::new(Point2D::new(from, bottom), Point2D::new(to, bottom)), &style, None);
+        }
+    }
+
+    /* Change text or font, then lay the text out again and update the entity. */
+    fn edit(&self, change: impl FnOnce(&mut Editing, &dyn TextMeasure)) -> Result<(), HistoryError> {
+        let mut state = self.state.write().map_err(|_| HistoryError::Lock)?;
+        let Some(editing) = &mut state.editing else {
+            return Ok(());
+        };
+
+        change(editing, self.measure());
+        editing.model.relayout(self.measure());
+
+        let entity = Build::from_json(editing.before.with_model(serde_json::to_value(&editing.model)?))?;
+
+        self.view_port.clone().modify_entity(entity)?;
+
+        Ok(())
+    }
+
+    /* Move caret or selection without changing the text. */
+    fn navigate(&self, change: impl FnOnce(&mut Editing, &dyn TextMeasure, bool)) -> Result<(), HistoryError> {
+        let mut state = self.state.write().map_err(|_| HistoryError::Lock)?;
+        let extend_selection: bool = state.extend_selection;
+
+        if let Some(editing) = &mut state.editing {
+            change(editing, self.measure(), extend_selection);
+        }
+
+        Ok(())
+    }
+}
+
+fn byte_index(text: &str, char_index: usize) -> usize {
+    text.char_indices()
+        .nth(char_index)
+        .map_or(text.len(), |(byte_index, _)| byte_index)
+}
+
+/* X coordinate of the char index clamped into the line. */
+fn prefix_width(line: &TextLine, index: usize, font: &Font, measure: &dyn TextMeasure) -> f64 {
+    let count: usize = index.clamp(line.start, line.end()) - line.start;
+    let prefix: String = line.text.chars().take(count).collect();
+
+    line.x + measure.width(&prefix, font)
+}
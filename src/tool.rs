@@ -0,0 +1,6 @@
+pub mod eraser_tool;
+pub mod text_tool;
+pub mod connector_tool;
+pub mod modifiers;
+pub mod shape_tool;
+pub mod select_tool;
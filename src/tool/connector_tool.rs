@@ -0,0 +1,133 @@
+use crate::element::connector_model::{Arrowhead, ConnectorModel, Endpoint, Routing};
+use crate::element::id::Id;
+use crate::element::json_entity::JsonEntity;
+use crate::element::{Build, ElementType};
+use crate::history::{Change, History, HistoryError, Step};
+use crate::math;
+use crate::style::CurrentStyle;
+use crate::view_port::ViewPort;
+use entity_model_feature::entity::Entity;
+use geometry::figure::segment::Segment;
+use geometry::point::point_2d::Point2D;
+use standard_rendering_plugin::renderable::Renderable;
+use standard_rendering_plugin::renderer::renderer::Renderer;
+use standard_rendering_plugin::style::shape_style::ShapeStyle;
+use standard_tool_plugin::tool::{Interaction, Tool};
+
+/* Connectors shorter than this between two free points are treated as accidental clicks. */
+const MIN_LENGTH: f64 = 2.0;
+
+#[derive(Copy, Clone)]
+pub struct ConnectorOptions {
+    pub routing: Routing,
+    pub start_arrowhead: Arrowhead,
+    pub end_arrowhead: Arrowhead,
+}
+
+impl Default for ConnectorOptions {
+    fn default() -> Self {
+        Self {
+            routing: Routing::Straight,
+            start_arrowhead: Arrowhead::None,
+            end_arrowhead: Arrowhead::Arrow,
+        }
+    }
+}
+
+/* Drag from one entity to another to connect them. Ends released on empty space stay free. */
+pub struct ConnectorTool {
+    owner_id: String,
+    style: CurrentStyle,
+    options: ConnectorOptions,
+    view_port: ViewPort,
+    history: History,
+    start: Option<(Endpoint, Point2D)>,
+    current: Option<Point2D>,
+}
+
+impl ConnectorTool {
+    pub fn new(owner_id: &str, style: CurrentStyle, options: ConnectorOptions, view_port: ViewPort, history: History) -> Self {
+        Self {
+            owner_id: owner_id.to_string(),
+            style,
+            options,
+            view_port,
+            history,
+            start: None,
+            current: None,
+        }
+    }
+
+    /* Topmost entity under the point. Connectors can't be bound to other connectors. */
+    fn endpoint_at(&self, point: &Point2D) -> Result<Endpoint, HistoryError> {
+        let bound_id: Option<Id> = self.view_port
+            .entities_at(point.x, point.y)?
+            .into_iter()
+            .find(|id| *id.element_type() != ElementType::Connector);
+
+        Ok(match bound_id {
+            Some(id) => Endpoint::Bound { id },
+            None => Endpoint::Free { x: point.x, y: point.y },
+        })
+    }
+
+    fn finish(&mut self, point: &Point2D) -> Result<(), HistoryError> {
+        let Some((start, start_point)) = self.start.take() else {
+            return Ok(());
+        };
+        self.current = None;
+
+        let end: Endpoint = self.endpoint_at(point)?;
+
+        let is_click: bool = matches!((&start, &end), (Endpoint::Free { .. }, Endpoint::Free { .. }))
+            && math::distance(&start_point, point) < MIN_LENGTH;
+        let is_loop: bool = start.bound_id().is_some() && start.bound_id() == end.bound_id();
+
+        if is_click || is_loop {
+            return Ok(());
+        }
+
+        let options: ConnectorOptions = self.options;
+        let entity: Entity<Id> = Build::default_with(&self.owner_id, ElementType::Connector, self.style.get(), |model: &mut ConnectorModel| {
+            model.start = start;
+            model.end = end;
+            model.routing = options.routing;
+            model.start_arrowhead = options.start_arrowhead;
+            model.end_arrowhead = options.end_arrowhead;
+        })?;
+
+        let json_entity: JsonEntity = JsonEntity::try_from(&entity)?;
+
+        /* View port resolves the route while adding. */
+        self.view_port.add_entity(entity)?;
+        self.history.push(Step::from(Change::Add(json_entity)))?;
+
+        Ok(())
+    }
+}
+
+impl Tool for ConnectorTool {
+    fn interact(&mut self, interaction: Interaction) {
+        match interaction {
+            Interaction::PointerDown(point, _) => {
+                let start: Endpoint = self.endpoint_at(&point).expect("Can't lock view port to find connector start");
+                self.start = Some((start, point));
+            }
+            Interaction::PointerMove(point, _) if self.start.is_some() =>
+                self.current = Some(point),
+            Interaction::PointerUp(point, _) =>
+                self.finish(&point).expect("Can't add connector"),
+            _ => {}
+        }
+    }
+}
+
+impl Renderable for ConnectorTool {
+    fn render(&self, renderer: &mut dyn Renderer) {
+        let (Some((_, start)), Some(current)) = (&self.start, &self.current) else {
+            return;
+        };
+
+        renderer.segment_2d(&Segment::new(*start, *current), &ShapeStyle::default(), None);
+    }
+}
@@ -0,0 +1,296 @@
+use crate::bounding_box::BoundingBox;
+use crate::element::free_hand::{self, Segment};
+use crate::element::id::Id;
+use crate::element::json_entity::JsonEntity;
+use crate::element::{Build, ElementType};
+use crate::history::{Change, History, HistoryError, Step};
+use crate::math;
+use crate::renderer::renderer::hit_test_renderer::HitTestRenderer;
+use crate::schema;
+use crate::view_port::ViewPort;
+use entity_model_feature::entity::Entity;
+use geometry::point::point_2d::Point2D;
+use serde::{Deserialize, Serialize};
+use standard_entity_plugin::model::path_model::PathModel;
+use standard_rendering_plugin::renderable::Renderable;
+use standard_rendering_plugin::renderer::renderer::Renderer;
+use standard_tool_plugin::tool::{Interaction, Tool};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[derive(Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub enum EraserMode {
+    /* Remove every entity touched by the eraser. */
+    Stroke,
+    /* Cut touched freehand strokes, leaving parts outside of the eraser. */
+    Pixel,
+}
+
+/* Iterations of the searches for the covered part of a stroke segment, enough for sub-pixel precision. */
+const SEARCH_STEPS: usize = 40;
+/* Bounds of the number of samples looking for the covered parts of a curve. */
+const MIN_CURVE_SAMPLES: f64 = 8.0;
+const MAX_CURVE_SAMPLES: f64 = 256.0;
+
+/*
+ * Erases entities along the pointer path.
+ * All changes made between pointer down and pointer up are recorded as a single history step.
+ */
+pub struct EraserTool {
+    owner_id: String,
+    mode: EraserMode,
+    radius: f64,
+    view_port: ViewPort,
+    history: History,
+    last_point: Option<Point2D>,
+    step: Step,
+}
+
+impl EraserTool {
+    pub fn new(owner_id: &str, mode: EraserMode, radius: f64, view_port: ViewPort, history: History) -> Self {
+        Self {
+            owner_id: owner_id.to_string(),
+            mode,
+            radius,
+            view_port,
+            history,
+            last_point: None,
+            step: Step::new(),
+        }
+    }
+
+    fn erase(&mut self, start: Point2D, end: Point2D) -> Result<(), HistoryError> {
+        let area: BoundingBox = BoundingBox::from_points([&start, &end])
+            .expect("Two points always have a bounding box")
+            .inflate(self.radius);
+
+        for id in self.view_port.entities_in(&area)? {
+            let Some(hit) = self.view_port.with_entity(&id, |entity| HitTestRenderer::hits(entity, start, end, self.radius))? else {
+                continue;
+            };
+
+            if !hit {
+                continue;
+            }
+
+            match self.mode {
+                EraserMode::Stroke =>
+                    self.remove(&id)?,
+                EraserMode::Pixel if *id.element_type() == ElementType::FreeHand =>
+                    self.split(&id, start, end)?,
+                EraserMode::Pixel => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn remove(&mut self, id: &Id) -> Result<(), HistoryError> {
+        let Some(entity) = self.view_port.remove_entity(id)? else {
+            return Ok(());
+        };
+
+        self.step.push(Change::Remove(JsonEntity::try_from(&entity)?));
+
+        Ok(())
+    }
+
+    /* Replace the stroke with its pieces lying outside of the eraser. */
+    fn split(&mut self, id: &Id, start: Point2D, end: Point2D) -> Result<(), HistoryError> {
+        let Some(json_entity) = self.view_port.with_entity(id, JsonEntity::try_from)? else {
+            return Ok(());
+        };
+        let json_entity: JsonEntity = json_entity?;
+
+        let path_model: PathModel = serde_json::from_value(json_entity.model.clone())?;
+        let segments: Vec<Segment> = free_hand::segments(&path_model);
+
+        /* Eraser touched only the stroke outline, nothing is cut. */
+        let Some(pieces) = self.cut(&segments, &start, &end) else {
+            return Ok(());
+        };
+
+        self.view_port.remove_entity(id)?;
+        self.step.push(Change::Remove(json_entity.clone()));
+
+        for piece in pieces {
+            let piece_model: PathModel = free_hand::with_path(&path_model, free_hand::path(&piece));
+            let piece_entity: JsonEntity = JsonEntity {
+                id: Id::generate(&self.owner_id, ElementType::FreeHand),
+                model: serde_json::to_value(&piece_model)?,
+                style: json_entity.style.clone(),
+                version: schema::VERSION,
+            };
+
+            let entity: Entity<Id> = Build::from_json(piece_entity.clone())?;
+            self.view_port.add_entity(entity)?;
+            self.step.push(Change::Add(piece_entity));
+        }
+
+        Ok(())
+    }
+
+    /*
+     * Split the stroke at every run covered by the eraser. Pieces keep the original segments, segments the eraser
+     * covers partly are cut where the stroke enters and leaves the eraser, curves stay curves. None if nothing is covered.
+     */
+    fn cut(&self, segments: &[Segment], start: &Point2D, end: &Point2D) -> Option<Vec<Vec<Segment>>> {
+        let mut pieces: Vec<Vec<Segment>> = vec![];
+        let mut piece: Vec<Segment> = vec![];
+        let mut cut: bool = false;
+
+        for segment in segments {
+            let kept: Vec<(f64, f64)> = self.uncovered_parts(segment, start, end);
+
+            if kept == [(0.0, 1.0)] {
+                piece.push(*segment);
+                continue;
+            }
+            cut = true;
+
+            for (from, to) in &kept {
+                if *from > 0.0 && !piece.is_empty() {
+                    pieces.push(std::mem::take(&mut piece));
+                }
+                piece.push(segment.part(*from, *to));
+            }
+
+            if kept.last().is_none_or(|(_, to)| *to < 1.0) && !piece.is_empty() {
+                pieces.push(std::mem::take(&mut piece));
+            }
+        }
+
+        if !piece.is_empty() {
+            pieces.push(piece);
+        }
+
+        cut.then_some(pieces)
+    }
+
+    /* Parts of the segment outside of the eraser moving from start to end, as ranges of the segment parameter. */
+    fn uncovered_parts(&self, segment: &Segment, start: &Point2D, end: &Point2D) -> Vec<(f64, f64)> {
+        let outside = |t: f64| math::distance_to_segment(&segment.point_at(t), start, end) - self.radius;
+
+        if let Segment::Line { from, to } = segment {
+            return match self.covered_interval(from, to, start, end) {
+                Some((enter, leave)) => [(0.0, enter), (leave, 1.0)].into_iter().filter(|(low, high)| low < high).collect(),
+                None => vec![(0.0, 1.0)],
+            };
+        }
+
+        /* Curves are sampled closer than the eraser is wide, so the eraser can't pass between two samples. */
+        let samples: usize = (segment.hull_length() / self.radius).ceil().clamp(MIN_CURVE_SAMPLES, MAX_CURVE_SAMPLES) as usize;
+        let mut parts: Vec<(f64, f64)> = vec![];
+        let mut part_start: Option<f64> = (outside(0.0) > 0.0).then_some(0.0);
+
+        for sample in 1..=samples {
+            let (previous, t) = ((sample - 1) as f64 / samples as f64, sample as f64 / samples as f64);
+
+            match (part_start, outside(t) > 0.0) {
+                (Some(from), false) => {
+                    parts.push((from, boundary(&outside, t, previous)));
+                    part_start = None;
+                }
+                (None, true) => part_start = Some(boundary(&outside, previous, t)),
+                _ => {}
+            }
+        }
+
+        if let Some(from) = part_start {
+            parts.push((from, 1.0));
+        }
+
+        parts
+    }
+
+    /*
+     * Part of the segment from..to covered by the eraser moving from start to end, as a range of the segment parameter.
+     * Distance to the eraser path is convex along the segment, so the covered part is a single range around its minimum.
+     */
+    fn covered_interval(&self, from: &Point2D, to: &Point2D, start: &Point2D, end: &Point2D) -> Option<(f64, f64)> {
+        let outside = |t: f64| math::distance_to_segment(&math::lerp(from, to, t), start, end) - self.radius;
+
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..SEARCH_STEPS {
+            let (left, right) = (low + (high - low) / 3.0, high - (high - low) / 3.0);
+
+            match outside(left) < outside(right) {
+                true => high = right,
+                false => low = left,
+            }
+        }
+        let closest: f64 = (low + high) / 2.0;
+
+        if outside(closest) > 0.0 {
+            return None;
+        }
+
+        let enter: f64 = if outside(0.0) > 0.0 { boundary(&outside, closest, 0.0) } else { 0.0 };
+        let leave: f64 = if outside(1.0) > 0.0 { boundary(&outside, closest, 1.0) } else { 1.0 };
+
+        Some((enter, leave))
+    }
+
+    fn finish(&mut self) -> Result<(), HistoryError> {
+        let step: Step = std::mem::take(&mut self.step);
+
+        self.history.push(step)
+    }
+
+    fn handle(&mut self, interaction: Interaction) -> Result<(), HistoryError> {
+        match interaction {
+            Interaction::PointerDown(point, _) => {
+                self.last_point = Some(point);
+                self.erase(point, point)?;
+            }
+            Interaction::PointerMove(point, _) => {
+                let Some(last_point) = self.last_point else {
+                    return Ok(());
+                };
+
+                self.last_point = Some(point);
+                self.erase(last_point, point)?;
+            }
+            Interaction::PointerUp(point, _) => {
+                let Some(last_point) = self.last_point.take() else {
+                    return Ok(());
+                };
+
+                self.erase(last_point, point)?;
+                self.finish()?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+/* Boundary between a covered and an uncovered parameter of a segment, outside is positive where it isn't covered. */
+fn boundary(outside: &impl Fn(f64) -> f64, mut covered: f64, mut uncovered: f64) -> f64 {
+    for _ in 0..SEARCH_STEPS {
+        let middle: f64 = (covered + uncovered) / 2.0;
+
+        match outside(middle) > 0.0 {
+            true => uncovered = middle,
+            false => covered = middle,
+        }
+    }
+
+    (covered + uncovered) / 2.0
+}
+
+impl Tool for EraserTool {
+    /* Tools can't report errors to the host. Failed erasing ends the gesture, keeping what was erased undoable if history can still record it. */
+    fn interact(&mut self, interaction: Interaction) {
+        if self.handle(interaction).is_err() {
+            self.last_point = None;
+            let _ = self.finish();
+        }
+    }
+}
+
+impl Renderable for EraserTool {
+    fn render(&self, _renderer: &mut dyn Renderer) {}
+}
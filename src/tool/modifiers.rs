@@ -0,0 +1,31 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/* State of modifier keys shared with the tools. Interaction keys don't include modifiers. */
+#[derive(Clone, Default)]
+pub struct Modifiers {
+    shift: Arc<AtomicBool>,
+    alt: Arc<AtomicBool>,
+}
+
+impl Modifiers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_shift(&self, pressed: bool) {
+        self.shift.store(pressed, Ordering::Relaxed);
+    }
+
+    pub fn shift(&self) -> bool {
+        self.shift.load(Ordering::Relaxed)
+    }
+
+    pub fn set_alt(&self, pressed: bool) {
+        self.alt.store(pressed, Ordering::Relaxed);
+    }
+
+    pub fn alt(&self) -> bool {
+        self.alt.load(Ordering::Relaxed)
+    }
+}
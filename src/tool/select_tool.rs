@@ -0,0 +1,147 @@
+use crate::element::id::Id;
+use crate::element::json_entity::JsonEntity;
+use crate::element::patch::Patch;
+use crate::element::transform::{self, Transform};
+use crate::element::Build;
+use crate::history::{Change, History, HistoryError, Step};
+use crate::mode::CurrentMode;
+use crate::text::text_editor::TextEditor;
+use crate::view_port::ViewPort;
+use geometry::point::point_2d::Point2D;
+use standard_rendering_plugin::renderable::Renderable;
+use standard_rendering_plugin::renderer::renderer::Renderer;
+use standard_tool_plugin::tool::select_tool;
+use standard_tool_plugin::tool::{Interaction, Tool};
+
+/* Entity being dragged, as it was when the drag started. */
+struct Drag {
+    before: JsonEntity,
+    start: Point2D,
+    transform: Transform,
+}
+
+/*
+ * Selects like the standard select tool. Dragging started on an entity moves the topmost one under the pointer,
+ * the move is recorded as a single history step when the pointer is released. Entities the board mode doesn't
+ * allow to change are only selected.
+ */
+pub struct SelectTool {
+    select_tool: select_tool::SelectTool<Id>,
+    mode: CurrentMode,
+    view_port: ViewPort,
+    history: History,
+    text_editor: TextEditor,
+    drag: Option<Drag>,
+}
+
+impl SelectTool {
+    pub fn new(mode: CurrentMode, view_port: ViewPort, history: History, text_editor: TextEditor) -> Self {
+        Self {
+            select_tool: select_tool::SelectTool::<Id>::new(),
+            mode,
+            view_port,
+            history,
+            text_editor,
+            drag: None,
+        }
+    }
+
+    fn start(&mut self, point: Point2D) -> Result<(), HistoryError> {
+        let Some(id) = self.view_port.entities_at(point.x, point.y)?.into_iter().next() else {
+            return Ok(());
+        };
+        if !self.mode.get().allows(Some(*id.element_type())) {
+            return Ok(());
+        }
+        let Some(before) = self.view_port.with_entity(&id, JsonEntity::try_from)? else {
+            return Ok(());
+        };
+
+        self.drag = Some(Drag {
+            before: before?,
+            start: point,
+            transform: Self::translation(0.0, 0.0),
+        });
+
+        Ok(())
+    }
+
+    /* Entity follows the pointer, nothing is recorded until the drag finishes. */
+    fn drag_to(&mut self, point: Point2D) -> Result<(), HistoryError> {
+        let Some(drag) = &mut self.drag else {
+            return Ok(());
+        };
+
+        /* Mode changed during the drag, the entity stays where it got. */
+        if !self.mode.get().allows(Some(*drag.before.id.element_type())) {
+            return Ok(());
+        }
+        /* Entity removed by a collaborator meanwhile, it isn't brought back. */
+        if self.view_port.with_entity(&drag.before.id, |_| ())?.is_none() {
+            return Ok(());
+        }
+
+        drag.transform = Self::translation(point.x - drag.start.x, point.y - drag.start.y);
+
+        let moved: JsonEntity = transform::transformed(&drag.before, &drag.transform, self.text_editor.measure())?;
+        self.view_port.modify_entity(Build::from_json(moved)?)?;
+
+        Ok(())
+    }
+
+    fn finish(&mut self, point: Point2D) -> Result<(), HistoryError> {
+        self.drag_to(point)?;
+
+        let Some(drag) = self.drag.take() else {
+            return Ok(());
+        };
+
+        let exists: bool = self.view_port.with_entity(&drag.before.id, |_| ())?.is_some();
+        if !exists || drag.transform == Self::translation(0.0, 0.0) {
+            return Ok(());
+        }
+
+        let redo: Patch = Patch::transform(drag.transform);
+        let undo: Patch = redo.inverse().expect("Moves can always be undone");
+
+        self.history.push(Step::from(Change::Modify { id: drag.before.id, redo, undo }))?;
+
+        Ok(())
+    }
+
+    fn translation(x: f64, y: f64) -> Transform {
+        Transform {
+            scale_x: 1.0,
+            scale_y: 1.0,
+            translate_x: x,
+            translate_y: y,
+        }
+    }
+}
+
+impl Tool for SelectTool {
+    fn interact(&mut self, interaction: Interaction) {
+        let interaction: Interaction = match (interaction, self.drag.as_ref().map(|drag| drag.start)) {
+            (Interaction::PointerDown(point, device), _) => {
+                self.start(point).expect("Can't lock view port to find dragged entity");
+                Interaction::PointerDown(point, device)
+            }
+            (Interaction::PointerMove(point, _), Some(_)) =>
+                return self.drag_to(point).expect("Can't move entity"),
+            /* Select tool sees the drag as a click on the moved entity. */
+            (Interaction::PointerUp(point, device), Some(start)) => {
+                self.finish(point).expect("Can't record move");
+                Interaction::PointerUp(start, device)
+            }
+            (interaction, _) => interaction,
+        };
+
+        self.select_tool.interact(interaction);
+    }
+}
+
+impl Renderable for SelectTool {
+    fn render(&self, renderer: &mut dyn Renderer) {
+        self.select_tool.render(renderer);
+    }
+}
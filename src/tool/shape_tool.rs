@@ -0,0 +1,214 @@
+use crate::element::ellipse_model::EllipseModel;
+use crate::element::frame_model::FrameModel;
+use crate::element::id::Id;
+use crate::element::json_entity::JsonEntity;
+use crate::element::line_model::LineModel;
+use crate::element::regular_polygon_model::RegularPolygonModel;
+use crate::element::sticky_note_model::StickyNoteModel;
+use crate::element::{Build, ElementType};
+use crate::history::{Change, History, HistoryError, Step};
+use crate::math;
+use crate::style::CurrentStyle;
+use crate::tool::modifiers::Modifiers;
+use crate::view_port::ViewPort;
+use entity_model_feature::entity::Entity;
+use geometry::point::point_2d::Point2D;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use standard_rendering_plugin::renderable::Renderable;
+use standard_rendering_plugin::renderer::renderer::Renderer;
+use standard_tool_plugin::tool::{Interaction, Tool};
+
+/* Size of the shape created by a click without dragging. */
+const CLICK_SIZE: f64 = 100.0;
+/* Pointer moves shorter than this are clicks. */
+const MIN_DRAG: f64 = 2.0;
+
+/* Shape drawn by dragging from one point to another. */
+pub trait DrawnShape: Clone + Serialize + DeserializeOwned {
+    fn drag(&mut self, start: &Point2D, end: &Point2D);
+
+    /* End of the drag while shift is held. Box shapes are kept square. */
+    fn constrain(start: &Point2D, end: &Point2D) -> Point2D {
+        let (dx, dy) = (end.x - start.x, end.y - start.y);
+        let size: f64 = dx.abs().max(dy.abs());
+
+        Point2D::new(start.x + size.copysign(dx), start.y + size.copysign(dy))
+    }
+
+    /* Shape created by a click without dragging. */
+    fn click(&mut self, point: &Point2D) {
+        let end: Point2D = Self::constrain(point, &Point2D::new(point.x + CLICK_SIZE, point.y));
+
+        self.drag(point, &end);
+    }
+}
+
+fn fit_box(start: &Point2D, end: &Point2D) -> (f64, f64, f64, f64) {
+    (start.x.min(end.x), start.y.min(end.y), (end.x - start.x).abs(), (end.y - start.y).abs())
+}
+
+impl DrawnShape for EllipseModel {
+    fn drag(&mut self, start: &Point2D, end: &Point2D) {
+        (self.x, self.y, self.width, self.height) = fit_box(start, end);
+    }
+}
+
+impl DrawnShape for RegularPolygonModel {
+    fn drag(&mut self, start: &Point2D, end: &Point2D) {
+        (self.x, self.y, self.width, self.height) = fit_box(start, end);
+    }
+}
+
+impl DrawnShape for LineModel {
+    fn drag(&mut self, start: &Point2D, end: &Point2D) {
+        (self.x1, self.y1) = (start.x, start.y);
+        (self.x2, self.y2) = (end.x, end.y);
+    }
+
+    /* Lines snap to multiples of 45 degrees. */
+    fn constrain(start: &Point2D, end: &Point2D) -> Point2D {
+        let step: f64 = std::f64::consts::FRAC_PI_4;
+        let angle: f64 = ((end.y - start.y).atan2(end.x - start.x) / step).round() * step;
+        let length: f64 = math::distance(start, end);
+
+        Point2D::new(start.x + length * angle.cos(), start.y + length * angle.sin())
+    }
+}
+
+/* Notes are always square, click keeps the default size. */
+impl DrawnShape for StickyNoteModel {
+    fn drag(&mut self, start: &Point2D, end: &Point2D) {
+        let (x, y, width, height) = fit_box(start, end);
+        (self.x, self.y, self.size) = (x, y, width.max(height));
+    }
+
+    fn click(&mut self, point: &Point2D) {
+        (self.x, self.y) = (point.x, point.y);
+    }
+}
+
+/* Frames of a fixed page size follow the dragged width, click creates a frame of the default width. */
+impl DrawnShape for FrameModel {
+    fn drag(&mut self, start: &Point2D, end: &Point2D) {
+        let (x, y, width, height) = fit_box(start, end);
+        (self.x, self.y) = (x, y);
+        self.resize(width, height);
+    }
+
+    fn click(&mut self, point: &Point2D) {
+        (self.x, self.y) = (point.x, point.y);
+    }
+}
+
+/*
+ * Draws shapes by dragging, shift constrains the shape. Click without dragging creates a shape of default size.
+ * Shape is added to the view port while dragging and recorded in history on pointer up.
+ */
+pub struct ShapeTool<M: DrawnShape> {
+    owner_id: String,
+    style: CurrentStyle,
+    element_type: ElementType,
+    /* Model every new shape starts from, holds properties not set by dragging. */
+    template: M,
+    modifiers: Modifiers,
+    view_port: ViewPort,
+    history: History,
+    start: Option<Point2D>,
+    drawing: Option<JsonEntity>,
+}
+
+impl<M: DrawnShape> ShapeTool<M> {
+    pub fn new(owner_id: &str, style: CurrentStyle, element_type: ElementType, template: M, modifiers: Modifiers, view_port: ViewPort, history: History) -> Self {
+        Self {
+            owner_id: owner_id.to_string(),
+            style,
+            element_type,
+            template,
+            modifiers,
+            view_port,
+            history,
+            start: None,
+            drawing: None,
+        }
+    }
+
+    fn update(&mut self, start: &Point2D, end: &Point2D) -> Result<(), HistoryError> {
+        let end: Point2D = if self.modifiers.shift() { M::constrain(start, end) } else { *end };
+
+        let mut model: M = self.template.clone();
+        model.drag(start, &end);
+
+        self.show(model)
+    }
+
+    /* Add the shape to the view port or replace the one being drawn. */
+    fn show(&mut self, model: M) -> Result<(), HistoryError> {
+        let json_entity: JsonEntity = match &self.drawing {
+            Some(json_entity) => {
+                let json_entity: JsonEntity = json_entity.with_model(serde_json::to_value(&model)?);
+                self.view_port.modify_entity(Build::from_json(json_entity.clone())?)?;
+
+                json_entity
+            }
+            None => {
+                let entity: Entity<Id> = Build::default_with(&self.owner_id, self.element_type, self.style.get(), |default: &mut M| *default = model)?;
+                let json_entity: JsonEntity = JsonEntity::try_from(&entity)?;
+                self.view_port.add_entity(entity)?;
+
+                json_entity
+            }
+        };
+
+        self.drawing = Some(json_entity);
+
+        Ok(())
+    }
+
+    fn finish(&mut self, point: &Point2D) -> Result<(), HistoryError> {
+        let Some(start) = self.start.take() else {
+            return Ok(());
+        };
+
+        if self.drawing.is_none() && math::distance(&start, point) < MIN_DRAG {
+            let mut model: M = self.template.clone();
+            model.click(&start);
+
+            self.show(model)?;
+        } else {
+            self.update(&start, point)?;
+        }
+
+        let Some(json_entity) = self.drawing.take() else {
+            return Ok(());
+        };
+
+        self.history.push(Step::from(Change::Add(json_entity)))
+    }
+}
+
+impl<M: DrawnShape> Tool for ShapeTool<M> {
+    fn interact(&mut self, interaction: Interaction) {
+        match interaction {
+            Interaction::PointerDown(point, _) =>
+                self.start = Some(point),
+            Interaction::PointerMove(point, _) => {
+                let Some(start) = self.start else {
+                    return;
+                };
+
+                if self.drawing.is_some() || math::distance(&start, &point) >= MIN_DRAG {
+                    self.update(&start, &point).expect("Can't draw shape");
+                }
+            }
+            Interaction::PointerUp(point, _) =>
+                self.finish(&point).expect("Can't add shape"),
+            _ => {}
+        }
+    }
+}
+
+/* Shape being drawn is already in the view port. */
+impl<M: DrawnShape> Renderable for ShapeTool<M> {
+    fn render(&self, _renderer: &mut dyn Renderer) {}
+}
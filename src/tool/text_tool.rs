@@ -0,0 +1,116 @@
+use crate::element::id::Id;
+use crate::element::json_entity::JsonEntity;
+use crate::element::text_box_model::TextBoxModel;
+use crate::element::{Build, ElementType};
+use crate::history::{Change, History, HistoryError, Step};
+use crate::style::CurrentStyle;
+use crate::text::text_editor::{CaretMove, TextEditor};
+use crate::view_port::ViewPort;
+use entity_model_feature::entity::Entity;
+use geometry::point::point_2d::Point2D;
+use standard_rendering_plugin::renderable::Renderable;
+use standard_rendering_plugin::renderer::renderer::Renderer;
+use standard_tool_plugin::tool::{Interaction, Key, Tool};
+
+/*
+ * Click on empty space creates a text box, click on an existing one starts editing it.
+ * Dragging inside of the edited box selects text.
+ */
+pub struct TextTool {
+    owner_id: String,
+    style: CurrentStyle,
+    view_port: ViewPort,
+    history: History,
+    text_editor: TextEditor,
+    selecting: bool,
+}
+
+impl TextTool {
+    pub fn new(owner_id: &str, style: CurrentStyle, view_port: ViewPort, history: History, text_editor: TextEditor) -> Self {
+        Self {
+            owner_id: owner_id.to_string(),
+            style,
+            view_port,
+            history,
+            text_editor,
+            selecting: false,
+        }
+    }
+
+    fn pointer_down(&mut self, point: &Point2D) -> Result<(), HistoryError> {
+        self.selecting = true;
+
+        if self.text_editor.contains(point) {
+            return self.text_editor.place_caret(point, false);
+        }
+
+        self.text_editor.finish()?;
+
+        let text_box: Option<Id> = self.view_port
+            .entities_at(point.x, point.y)?
+            .into_iter()
+            .find(|id| *id.element_type() == ElementType::Text);
+
+        let id: Id = match text_box {
+            Some(id) => id,
+            None => self.create(point)?,
+        };
+
+        self.text_editor.begin(&id)?;
+        self.text_editor.place_caret(point, false)
+    }
+
+    fn create(&mut self, point: &Point2D) -> Result<Id, HistoryError> {
+        let measure = self.text_editor.measure();
+
+        let entity: Entity<Id> = Build::default_with(&self.owner_id, ElementType::Text, self.style.get(), |model: &mut TextBoxModel| {
+            model.x = point.x;
+            model.y = point.y;
+            model.relayout(measure);
+        })?;
+
+        let id: Id = entity.id().clone();
+        let json_entity: JsonEntity = JsonEntity::try_from(&entity)?;
+
+        self.view_port.add_entity(entity)?;
+        self.history.push(Step::from(Change::Add(json_entity)))?;
+
+        Ok(id)
+    }
+
+    fn key_down(&mut self, key: Key) -> Result<(), HistoryError> {
+        match key {
+            Key::ArrowLeft => self.text_editor.move_caret(CaretMove::Left),
+            Key::ArrowRight => self.text_editor.move_caret(CaretMove::Right),
+            Key::ArrowUp => self.text_editor.move_caret(CaretMove::Up),
+            Key::ArrowDown => self.text_editor.move_caret(CaretMove::Down),
+            Key::Backspace => self.text_editor.delete_backward(),
+            Key::Delete => self.text_editor.delete_forward(),
+            Key::Enter => self.text_editor.insert("\n"),
+            Key::Esc => self.text_editor.finish(),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Tool for TextTool {
+    fn interact(&mut self, interaction: Interaction) {
+        match interaction {
+            Interaction::PointerDown(point, _) =>
+                self.pointer_down(&point).expect("Can't start text editing"),
+            Interaction::PointerMove(point, _) if self.selecting =>
+                self.text_editor.place_caret(&point, true).expect("Can't select text"),
+            Interaction::PointerUp(..) =>
+                self.selecting = false,
+            Interaction::KeyDown(key) =>
+                self.key_down(key).expect("Can't edit text"),
+            _ => {}
+        }
+    }
+}
+
+impl Renderable for TextTool {
+    fn render(&self, renderer: &mut dyn Renderer) {
+        self.text_editor.render(renderer);
+    }
+}
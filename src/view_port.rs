@@ -1,46 +1,653 @@
+mod spatial_index;
+
+use crate::bounding_box::BoundingBox;
+use crate::crdt::Replica;
+use crate::document::DocumentError;
+use crate::element::connector_model::{ConnectorModel, Endpoint};
+use crate::element::frame_model::FrameModel;
 use crate::element::id::Id;
+use crate::element::json_entity::JsonEntity;
+use crate::element::{model_of, Build, ElementType};
+use crate::events::{BoardEvent, EventKind, Events};
+use crate::history::Change;
+use crate::renderer::board_renderer::{self, BoardRenderer};
+use crate::renderer::renderer::bounds_renderer::BoundsRenderer;
+use crate::text::measure::TextMeasure;
+use crate::view_port::spatial_index::SpatialIndex;
 use entity_model_feature::entity::Entity;
-use standard_rendering_plugin::renderable::Renderable;
-use standard_rendering_plugin::renderer::renderer::Renderer;
-use standard_rendering_plugin::Render;
-use std::sync::{Arc, RwLock};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 #[derive(Debug)]
 pub struct LockError<'a>(&'a str);
 
 #[derive(Clone)]
 pub struct ViewPort {
-    entities: Arc<RwLock<Vec<Entity<Id>>>>,
+    scene: Arc<RwLock<Scene>>,
+    events: Events,
+    /* Records local changes for other peers when collaboration is enabled. */
+    replica: Arc<RwLock<Option<Replica>>>,
+}
+
+/* Entities with their z-order and spatial index, guarded together so they never go out of sync. */
+struct Scene {
+    entities: HashMap<Id, Entity<Id>>,
+    z_order: BTreeMap<usize, Id>,
+    z_indices: HashMap<Id, usize>,
+    next_z_index: usize,
+    /* Places of removed entities in the z-order, so undoing a removal puts the entity back where it was. */
+    removed_z_indices: HashMap<Id, usize>,
+    spatial_index: SpatialIndex,
+    /* Connectors bound to each entity. */
+    bindings: HashMap<Id, HashSet<Id>>,
+    /* Connectors rerouted because entities they are bound to changed, as they were before. Taken into history steps. */
+    rerouted: Vec<JsonEntity>,
+    /* Visible area of the board. Entities outside of it are not rendered. None disables culling. */
+    view_box: Option<BoundingBox>,
+    /* Frames in presentation order. */
+    frames: Vec<Id>,
 }
 
 impl ViewPort {
-    pub fn new() -> Self {
+    pub fn new(events: Events) -> Self {
         Self {
-            entities: Arc::new(RwLock::new(vec![])),
+            scene: Arc::new(RwLock::new(Scene::new())),
+            events,
+            replica: Arc::new(RwLock::new(None)),
         }
     }
 
     pub fn add_entity(&mut self, entity: Entity<Id>) -> Result<(), LockError> {
-        self.entities
-            .write()
-            .map_err(|_| LockError("Failed to acquire lock"))?
-            .push(entity);
+        let id: Id = entity.id().clone();
+
+        let mut scene = self.write()?;
+        let existed: bool = scene.entities.contains_key(&id);
+        scene.add(entity);
+        drop(scene);
+
+        self.record_changed(&id)?;
+        self.emit_changed(&id, existed)
+    }
+
+    /* Replace entity with the same id keeping its z-order. Adds the entity if it doesn't exist yet. */
+    pub fn modify_entity(&mut self, entity: Entity<Id>) -> Result<(), LockError> {
+        let id: Id = entity.id().clone();
+
+        let mut scene = self.write()?;
+        let existed: bool = scene.entities.contains_key(&id);
+        scene.modify(entity);
+        drop(scene);
+
+        self.record_changed(&id)?;
+        self.emit_changed(&id, existed)
+    }
+
+    /* Modifications of the connectors rerouted since the last call, recorded in the step of the change that moved them. */
+    pub fn take_reroutes(&self) -> Result<Vec<Change>, LockError> {
+        let mut scene = self.write()?;
+        let rerouted: Vec<JsonEntity> = std::mem::take(&mut scene.rerouted);
+
+        Ok(rerouted
+            .into_iter()
+            .filter_map(|before| {
+                let after: JsonEntity = scene.entities.get(&before.id).map(JsonEntity::try_from)?.ok()?;
+
+                (after.model != before.model).then(|| Change::modified(&before, &after))
+            })
+            .collect())
+    }
+
+    pub fn remove_entity(&mut self, id: &Id) -> Result<Option<Entity<Id>>, LockError> {
+        let entity: Option<Entity<Id>> = self.write()?.remove(id);
+
+        if let (Some(_), Some(replica)) = (&entity, self.replica()?.as_mut()) {
+            replica.record_removal(id);
+        }
+
+        if let Some(entity) = &entity {
+            if self.events.listens(EventKind::EntityRemoved) {
+                if let Ok(json_entity) = JsonEntity::try_from(entity) {
+                    self.events.emit(BoardEvent::EntityRemoved(json_entity));
+                }
+            }
+        }
+
+        Ok(entity)
+    }
+
+    /* Remove all entities, the view box is kept. */
+    pub fn clear(&mut self) -> Result<(), LockError> {
+        let ids: Vec<Id> = self.read()?.z_order.values().cloned().collect();
+
+        for id in &ids {
+            self.remove_entity(id)?;
+        }
+
+        /* Entities added later start a new z-order. */
+        self.write()?.removed_z_indices.clear();
+
+        Ok(())
+    }
+
+    /* Back the board with a replica of the peer. Entities already on the board become its first changes. */
+    pub fn enable_replica(&mut self, peer: &str) -> Result<(), LockError> {
+        let mut replica: Replica = Replica::new(peer);
+        let mut below: Option<Id> = None;
+
+        self.for_all_entities(|entity| {
+            if let Ok(json_entity) = JsonEntity::try_from(entity) {
+                replica.record(&json_entity, below.as_ref());
+            }
+            below = Some(entity.id().clone());
+        })?;
+
+        *self.replica()? = Some(replica);
+
+        Ok(())
+    }
+
+    /* None if collaboration is not enabled. */
+    pub fn with_replica<R>(&self, callback: impl FnOnce(&mut Replica) -> R) -> Option<R> {
+        self.replica().ok()?.as_mut().map(callback)
+    }
+
+    /* Merge remote update into the board. Returns false if collaboration is not enabled. */
+    pub fn apply_update(&mut self, update: &[u8], measure: &dyn TextMeasure) -> Result<bool, DocumentError> {
+        /* Taken out while the board follows the replica, so the changes are not recorded back. */
+        let Some(mut replica) = self.replica()?.take() else {
+            return Ok(false);
+        };
+
+        let result: Result<(), DocumentError> = self.follow_replica(&mut replica, update, measure);
+        *self.replica()? = Some(replica);
+
+        result.map(|_| true)
+    }
+
+    fn follow_replica(&mut self, replica: &mut Replica, update: &[u8], measure: &dyn TextMeasure) -> Result<(), DocumentError> {
+        for id in replica.apply(update)? {
+            match replica.materialize(&id, measure) {
+                Ok(Some(json_entity)) => {
+                    /* Entity may be incomplete until all of its fields arrive. */
+                    if let Ok(entity) = Build::from_json(json_entity) {
+                        self.modify_entity(entity)?;
+                    }
+                }
+                Ok(None) => {
+                    self.remove_entity(&id)?;
+                }
+                Err(_) => {}
+            }
+        }
+
+        let mut scene = self.write()?;
+        scene.reorder(&replica.order());
+        /* Peers reroute their connectors themselves, these are not local changes. */
+        scene.rerouted.clear();
+
+        Ok(())
+    }
+
+    /* Changed entity and the connectors rerouted with it. */
+    fn record_changed(&self, id: &Id) -> Result<(), LockError> {
+        let mut replica = self.replica()?;
+        let Some(replica) = replica.as_mut() else {
+            return Ok(());
+        };
+
+        let scene = self.read()?;
+        let connector_ids = scene.bindings.get(id).into_iter().flatten().filter(|connector_id| *connector_id != id);
+
+        for changed_id in std::iter::once(id).chain(connector_ids) {
+            if let Some(Ok(json_entity)) = scene.entities.get(changed_id).map(JsonEntity::try_from) {
+                replica.record(&json_entity, scene.below(changed_id));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn with_entity<R>(&self, id: &Id, callback: impl FnOnce(&Entity<Id>) -> R) -> Result<Option<R>, LockError> {
+        Ok(self.read()?.entities.get(id).map(callback))
+    }
+
+    pub fn set_view_box(&mut self, view_box: Option<BoundingBox>) -> Result<(), LockError> {
+        self.write()?.view_box = view_box;
+
+        if let Some(view_box) = view_box {
+            self.events.emit(BoardEvent::CameraChanged(view_box));
+        }
+
+        Ok(())
+    }
+
+    pub fn view_box(&self) -> Result<Option<BoundingBox>, LockError> {
+        Ok(self.read()?.view_box)
+    }
+
+    pub fn bounding_box(&self, id: &Id) -> Result<Option<BoundingBox>, LockError> {
+        Ok(self.read()?.spatial_index.bounding_box(id))
+    }
+
+    /* Ids of entities whose bounding box contains the point, topmost first. */
+    pub fn entities_at(&self, x: f64, y: f64) -> Result<Vec<Id>, LockError> {
+        let scene = self.read()?;
+        let mut ids: Vec<Id> = scene.sorted_by_z_index(scene.spatial_index.query_point(x, y));
+        ids.reverse();
+
+        Ok(ids)
+    }
+
+    /* Ids of entities whose bounding box intersects the area, in z-order. */
+    pub fn entities_in(&self, area: &BoundingBox) -> Result<Vec<Id>, LockError> {
+        let scene = self.read()?;
+
+        Ok(scene.sorted_by_z_index(scene.spatial_index.query(area)))
+    }
+
+    /* Bounding box of all entities. None if the board is empty. */
+    pub fn extent(&self) -> Result<Option<BoundingBox>, LockError> {
+        Ok(self.read()?.spatial_index.extent())
+    }
+
+    /* Visit all entities in z-order, also the ones without bounds like empty text boxes. */
+    pub fn for_all_entities(&self, mut callback: impl FnMut(&Entity<Id>)) -> Result<(), LockError> {
+        let scene = self.read()?;
+
+        for entity in scene.z_order.values().filter_map(|id| scene.entities.get(id)) {
+            callback(entity);
+        }
+
+        Ok(())
+    }
+
+    /* Visit all entities with bounds in z-order together with their bounding boxes. */
+    pub fn for_each_entity(&self, mut callback: impl FnMut(&Entity<Id>, &BoundingBox)) -> Result<(), LockError> {
+        let scene = self.read()?;
+
+        for id in scene.z_order.values() {
+            if let (Some(entity), Some(bounding_box)) = (scene.entities.get(id), scene.spatial_index.bounding_box(id)) {
+                callback(entity, &bounding_box);
+            }
+        }
+
+        Ok(())
+    }
+
+    /* Frames in presentation order. */
+    pub fn frames(&self) -> Result<Vec<Id>, LockError> {
+        Ok(self.read()?.frames.clone())
+    }
+
+    /* Put frames into the presentation order. Frames missing in it follow in their current order. */
+    pub fn set_frame_order(&mut self, ids: &[Id]) -> Result<(), LockError> {
+        let mut scene = self.write()?;
+
+        let mut frames: Vec<Id> = ids.iter().filter(|id| scene.frames.contains(id)).cloned().collect();
+        frames.dedup();
+        let missing: Vec<Id> = scene.frames.iter().filter(|id| !frames.contains(id)).cloned().collect();
+        frames.extend(missing);
+        scene.frames = frames;
+
+        Ok(())
+    }
+
+    pub fn frame_box(&self, frame_id: &Id) -> Result<Option<BoundingBox>, LockError> {
+        Ok(self.read()?.frame_box(frame_id))
+    }
+
+    /* Entities belonging to the frame, in z-order. */
+    pub fn frame_contents(&self, frame_id: &Id) -> Result<Vec<Id>, LockError> {
+        let scene = self.read()?;
+
+        let Some(frame_box) = scene.frame_box(frame_id) else {
+            return Ok(vec![]);
+        };
+
+        let membership: HashMap<Id, &Id> = scene.frame_membership();
+
+        Ok(scene
+            .sorted_by_z_index(scene.spatial_index.query(&frame_box))
+            .into_iter()
+            .filter(|id| membership.get(id) == Some(&frame_id))
+            .collect())
+    }
+
+    /* Changed entity and the connectors rerouted with it. */
+    fn emit_changed(&self, id: &Id, existed: bool) -> Result<(), LockError> {
+        if !self.events.listens(EventKind::EntityAdded) && !self.events.listens(EventKind::EntityModified) {
+            return Ok(());
+        }
+
+        let scene = self.read()?;
+        let connector_ids = scene.bindings.get(id).into_iter().flatten().filter(|connector_id| *connector_id != id);
+
+        for (changed_id, added) in std::iter::once((id, !existed)).chain(connector_ids.map(|connector_id| (connector_id, false))) {
+            let Some(Ok(json_entity)) = scene.entities.get(changed_id).map(JsonEntity::try_from) else {
+                continue;
+            };
+
+            self.events.emit(match added {
+                true => BoardEvent::EntityAdded(json_entity),
+                false => BoardEvent::EntityModified(json_entity),
+            });
+        }
 
         Ok(())
     }
+
+    fn replica(&self) -> Result<RwLockWriteGuard<Option<Replica>>, LockError> {
+        self.replica
+            .write()
+            .map_err(|_| LockError("Failed to acquire lock"))
+    }
+
+    fn read(&self) -> Result<RwLockReadGuard<Scene>, LockError> {
+        self.scene
+            .read()
+            .map_err(|_| LockError("Failed to acquire lock"))
+    }
+
+    fn write(&self) -> Result<RwLockWriteGuard<Scene>, LockError> {
+        self.scene
+            .write()
+            .map_err(|_| LockError("Failed to acquire lock"))
+    }
 }
 
-impl Renderable for ViewPort {
-    fn render(&self, renderer: &mut dyn Renderer) {
+impl Scene {
+    fn new() -> Self {
+        Self {
+            entities: HashMap::new(),
+            z_order: BTreeMap::new(),
+            z_indices: HashMap::new(),
+            next_z_index: 0,
+            removed_z_indices: HashMap::new(),
+            spatial_index: SpatialIndex::new(),
+            bindings: HashMap::new(),
+            rerouted: vec![],
+            view_box: None,
+            frames: vec![],
+        }
+    }
+
+    fn add(&mut self, entity: Entity<Id>) {
+        let id: Id = entity.id().clone();
+
+        if self.entities.contains_key(&id) {
+            self.modify(entity);
+            return;
+        }
+
+        let z_index: usize = match self.removed_z_indices.remove(&id) {
+            Some(z_index) if !self.z_order.contains_key(&z_index) => z_index,
+            _ => {
+                let z_index: usize = self.next_z_index;
+                self.next_z_index += 1;
+                z_index
+            }
+        };
+
+        self.z_order.insert(z_index, id.clone());
+        self.z_indices.insert(id.clone(), z_index);
+        if *id.element_type() == ElementType::Frame {
+            self.frames.push(id.clone());
+        }
+        self.index(&entity);
+        self.entities.insert(id.clone(), entity);
+        self.update_connectors(&id);
+    }
+
+    fn modify(&mut self, entity: Entity<Id>) {
+        if !self.entities.contains_key(entity.id()) {
+            self.add(entity);
+            return;
+        }
+
+        let id: Id = entity.id().clone();
+
+        self.index(&entity);
+        self.entities.insert(id.clone(), entity);
+        self.update_connectors(&id);
+    }
+
+    fn remove(&mut self, id: &Id) -> Option<Entity<Id>> {
+        let entity: Entity<Id> = self.entities.remove(id)?;
+
+        if let Some(z_index) = self.z_indices.remove(id) {
+            self.z_order.remove(&z_index);
+            self.removed_z_indices.insert(id.clone(), z_index);
+        }
+        self.spatial_index.remove(id);
+        self.frames.retain(|frame_id| frame_id != id);
+        /* Connectors bound to the removed entity stay bound, they are routed again if it comes back. */
+        self.unbind(id);
+
+        Some(entity)
+    }
+
+    /* Keep connector bindings and routes in sync with the changed entity. */
+    fn update_connectors(&mut self, id: &Id) {
+        if *id.element_type() == ElementType::Connector {
+            self.bind(id);
+            self.reroute(id);
+        }
+
+        let connector_ids: Vec<Id> = self.bindings
+            .get(id)
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+
+        for connector_id in &connector_ids {
+            let remembered: bool = connector_id == id || self.rerouted.iter().any(|before| before.id == *connector_id);
+
+            if !remembered {
+                if let Some(Ok(before)) = self.entities.get(connector_id).map(JsonEntity::try_from) {
+                    self.rerouted.push(before);
+                }
+            }
+
+            self.reroute(connector_id);
+        }
+    }
+
+    fn bind(&mut self, connector_id: &Id) {
+        self.unbind(connector_id);
+
+        let Some(model) = self.entities.get(connector_id).and_then(model_of::<ConnectorModel>) else {
+            return;
+        };
+
+        let bound_ids: Vec<Id> = model.bound_ids().cloned().collect();
+        for bound_id in bound_ids {
+            self.bindings.entry(bound_id).or_default().insert(connector_id.clone());
+        }
+    }
+
+    fn unbind(&mut self, connector_id: &Id) {
+        self.bindings.retain(|_, connector_ids| {
+            connector_ids.remove(connector_id);
+            !connector_ids.is_empty()
+        });
+    }
+
+    /* Connector that can't be rebuilt keeps its previous route. */
+    fn reroute(&mut self, connector_id: &Id) {
+        let Some(entity) = self.entities.get(connector_id) else {
+            return;
+        };
+        let Some(model) = model_of::<ConnectorModel>(entity) else {
+            return;
+        };
+
+        let bounds = |endpoint: &Endpoint| endpoint
+            .bound_id()
+            .and_then(|bound_id| self.spatial_index.bounding_box(bound_id));
+        let (start_bounds, end_bounds) = (bounds(&model.start), bounds(&model.end));
+
+        let Ok(rerouted) = Build::modified(entity, |model: &mut ConnectorModel| model.reroute(start_bounds, end_bounds)) else {
+            return;
+        };
+
+        self.index(&rerouted);
+        self.entities.insert(connector_id.clone(), rerouted);
+    }
+
+    fn index(&mut self, entity: &Entity<Id>) {
+        match BoundsRenderer::bounds_of(entity) {
+            Some(bounding_box) =>
+                self.spatial_index.insert(entity.id().clone(), bounding_box),
+            /* Entity without visual representation can't be hit or culled. */
+            None => {
+                self.spatial_index.remove(entity.id());
+            }
+        }
+    }
+
+    /* Put entities into the order. Entities missing in it stay on top in their current order. */
+    fn reorder(&mut self, ids: &[Id]) {
+        let mut ordered: Vec<Id> = ids.iter().filter(|id| self.entities.contains_key(*id)).cloned().collect();
+        let ordered_ids: HashSet<Id> = ordered.iter().cloned().collect();
+        ordered.extend(self.z_order.values().filter(|id| !ordered_ids.contains(*id)).cloned());
+
+        self.z_order.clear();
+        self.z_indices.clear();
+        /* Places are not comparable with the new ones. */
+        self.removed_z_indices.clear();
+
+        for (z_index, id) in ordered.into_iter().enumerate() {
+            self.z_order.insert(z_index, id.clone());
+            self.z_indices.insert(id, z_index);
+        }
+
+        self.next_z_index = self.z_order.len();
+    }
+
+    /* Area of the frame, None if the id is not a frame on the board. */
+    fn frame_box(&self, frame_id: &Id) -> Option<BoundingBox> {
+        self.entities
+            .get(frame_id)
+            .and_then(model_of::<FrameModel>)
+            .map(FrameModel::bounding_box)
+    }
+
+    /*
+     * Frame of every entity in a frame, the topmost frame containing the center of the entity.
+     * Frames don't belong to other frames. Each frame queries only the entities over it.
+     */
+    fn frame_membership(&self) -> HashMap<Id, &Id> {
+        let mut frames: Vec<(usize, &Id, BoundingBox)> = self.frames
+            .iter()
+            .filter_map(|frame_id| Some((*self.z_indices.get(frame_id)?, frame_id, self.frame_box(frame_id)?)))
+            .collect();
+        frames.sort_unstable_by_key(|(z_index, _, _)| std::cmp::Reverse(*z_index));
+
+        let mut membership: HashMap<Id, &Id> = HashMap::new();
+
+        for (_, frame_id, frame_box) in frames {
+            for id in self.spatial_index.query(&frame_box) {
+                if *id.element_type() == ElementType::Frame {
+                    continue;
+                }
+
+                let centered: bool = self.spatial_index
+                    .bounding_box(&id)
+                    .is_some_and(|bounding_box| {
+                        let (x, y) = bounding_box.center();
+                        frame_box.contains_point(x, y)
+                    });
+
+                if centered {
+                    membership.entry(id).or_insert(frame_id);
+                }
+            }
+        }
+
+        membership
+    }
+
+    /* Entity right below in the z-order, None for the bottom one. */
+    fn below(&self, id: &Id) -> Option<&Id> {
+        let z_index: usize = *self.z_indices.get(id)?;
+
+        self.z_order.range(..z_index).next_back().map(|(_, id)| id)
+    }
+
+    fn sorted_by_z_index(&self, ids: Vec<Id>) -> Vec<Id> {
+        let mut ids: Vec<(usize, Id)> = ids
+            .into_iter()
+            .filter_map(|id| Some((*self.z_indices.get(&id)?, id)))
+            .collect();
+
+        ids.sort_unstable_by_key(|(z_index, _)| *z_index);
+
+        ids.into_iter().map(|(_, id)| id).collect()
+    }
+}
+
+impl ViewPort {
+    pub fn render(&self, renderer: &mut dyn BoardRenderer) {
         /* Use try_read to not lock the current thread for rendering.  */
-        let Ok(entities) = self.entities.try_read() else {
+        let Ok(scene) = self.scene.try_read() else {
             return;
         };
 
-        for entity in entities.iter() {
-            if let Some(render) = entity.query::<Render<Id>>() {
-                (render.render)(entity, renderer);
+        let visible_ids: Vec<Id> = match &scene.view_box {
+            Some(view_box) => scene.sorted_by_z_index(scene.spatial_index.query(view_box)),
+            None => scene.z_order.values().cloned().collect(),
+        };
+
+        /* Frames are regions of the board, they stay behind other entities whatever their z-order is. */
+        let (frame_ids, visible_ids): (Vec<Id>, Vec<Id>) = visible_ids
+            .into_iter()
+            .partition(|id| *id.element_type() == ElementType::Frame);
+
+        for frame in frame_ids.iter().filter_map(|id| scene.entities.get(id)) {
+            board_renderer::render_entity(frame, renderer);
+        }
+
+        /* Content of frames is clipped by them. */
+        let membership: HashMap<Id, &Id> = scene.frame_membership();
+        let mut clip: Option<&Id> = None;
+
+        for id in &visible_ids {
+            let Some(entity) = scene.entities.get(id) else {
+                continue;
+            };
+
+            let frame_id: Option<&Id> = membership.get(id).copied();
+            if frame_id != clip {
+                renderer.clip(frame_id.and_then(|frame_id| scene.frame_box(frame_id)).as_ref());
+                clip = frame_id;
             }
+
+            board_renderer::render_entity(entity, renderer);
+        }
+
+        if clip.is_some() {
+            renderer.clip(None);
+        }
+    }
+
+    /* Frame and its content, clipped by the frame. Returns false if there is no such frame. */
+    pub fn render_frame(&self, frame_id: &Id, renderer: &mut dyn BoardRenderer) -> Result<bool, LockError> {
+        let contents: Vec<Id> = self.frame_contents(frame_id)?;
+        let scene = self.read()?;
+
+        let (Some(frame), Some(frame_box)) = (scene.entities.get(frame_id), scene.frame_box(frame_id)) else {
+            return Ok(false);
+        };
+
+        board_renderer::render_entity(frame, renderer);
+
+        renderer.clip(Some(&frame_box));
+        for entity in contents.iter().filter_map(|id| scene.entities.get(id)) {
+            board_renderer::render_entity(entity, renderer);
         }
+        renderer.clip(None);
+
+        Ok(true)
     }
 }
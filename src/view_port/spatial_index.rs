@@ -0,0 +1,191 @@
+use crate::bounding_box::BoundingBox;
+use crate::element::id::Id;
+use std::collections::HashMap;
+
+const NODE_CAPACITY: usize = 16;
+const MAX_DEPTH: usize = 12;
+const INITIAL_SIZE: f64 = 4096.0;
+
+/*
+ * Quadtree over entity bounding boxes.
+ * Items that don't fit completely into one of the node quadrants are kept in the node itself,
+ * so every item is stored exactly once. Root grows when an item is added outside of it.
+ */
+pub struct SpatialIndex {
+    bounding_boxes: HashMap<Id, BoundingBox>,
+    root: Node,
+}
+
+struct Node {
+    bounds: BoundingBox,
+    depth: usize,
+    items: Vec<(Id, BoundingBox)>,
+    quadrants: Option<Box<[Node; 4]>>,
+}
+
+impl SpatialIndex {
+    pub fn new() -> Self {
+        Self {
+            bounding_boxes: HashMap::new(),
+            root: Node::new(
+                BoundingBox::new(-INITIAL_SIZE / 2.0, -INITIAL_SIZE / 2.0, INITIAL_SIZE, INITIAL_SIZE),
+                0,
+            ),
+        }
+    }
+
+    pub fn bounding_box(&self, id: &Id) -> Option<BoundingBox> {
+        self.bounding_boxes.get(id).copied()
+    }
+
+    /* Bounding box of all indexed items. */
+    pub fn extent(&self) -> Option<BoundingBox> {
+        self.bounding_boxes
+            .values()
+            .copied()
+            .reduce(|extent, bounding_box| extent.union(&bounding_box))
+    }
+
+    /* Boxes that aren't finite are not indexed, the item is treated like one without bounds. */
+    pub fn insert(&mut self, id: Id, bounding_box: BoundingBox) {
+        self.remove(&id);
+
+        if !bounding_box.is_finite() {
+            return;
+        }
+
+        if !self.root.bounds.contains(&bounding_box) {
+            self.grow(&bounding_box);
+        }
+
+        self.root.insert(id.clone(), bounding_box);
+        self.bounding_boxes.insert(id, bounding_box);
+    }
+
+    pub fn remove(&mut self, id: &Id) -> Option<BoundingBox> {
+        let bounding_box: BoundingBox = self.bounding_boxes.remove(id)?;
+        self.root.remove(id, &bounding_box);
+
+        Some(bounding_box)
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /* Ids of items whose bounding box intersects the area. Order is unspecified. */
+    pub fn query(&self, area: &BoundingBox) -> Vec<Id> {
+        let mut found: Vec<Id> = vec![];
+        self.root.query(area, &mut found);
+
+        found
+    }
+
+    pub fn query_point(&self, x: f64, y: f64) -> Vec<Id> {
+        self.query(&BoundingBox::from_point(x, y))
+    }
+
+    /* Double the root until it covers the bounding box, then reinsert everything. */
+    fn grow(&mut self, bounding_box: &BoundingBox) {
+        let mut bounds: BoundingBox = self.root.bounds;
+
+        while !bounds.contains(bounding_box) {
+            let (center_x, center_y) = bounds.center();
+            let size: f64 = bounds.width().max(bounds.height()) * 2.0;
+            /* Boxes near the limits of f64 can't be covered, they are kept in the root. */
+            if !size.is_finite() {
+                break;
+            }
+
+            bounds = BoundingBox::new(center_x - size / 2.0, center_y - size / 2.0, size, size);
+        }
+
+        self.root = Node::new(bounds, 0);
+        for (id, bounding_box) in &self.bounding_boxes {
+            self.root.insert(id.clone(), *bounding_box);
+        }
+    }
+}
+
+impl Node {
+    fn new(bounds: BoundingBox, depth: usize) -> Self {
+        Self {
+            bounds,
+            depth,
+            items: vec![],
+            quadrants: None,
+        }
+    }
+
+    fn insert(&mut self, id: Id, bounding_box: BoundingBox) {
+        if let Some(quadrant) = self.quadrant_for(&bounding_box) {
+            quadrant.insert(id, bounding_box);
+            return;
+        }
+
+        self.items.push((id, bounding_box));
+
+        if self.quadrants.is_none() && self.items.len() > NODE_CAPACITY && self.depth < MAX_DEPTH {
+            self.split();
+        }
+    }
+
+    fn remove(&mut self, id: &Id, bounding_box: &BoundingBox) -> bool {
+        if let Some(quadrant) = self.quadrant_for(bounding_box) {
+            if quadrant.remove(id, bounding_box) {
+                return true;
+            }
+        }
+
+        let Some(position) = self.items.iter().position(|(item_id, _)| item_id == id) else {
+            return false;
+        };
+
+        self.items.swap_remove(position);
+        true
+    }
+
+    fn query(&self, area: &BoundingBox, found: &mut Vec<Id>) {
+        if !self.bounds.intersects(area) {
+            return;
+        }
+
+        found.extend(
+            self.items
+                .iter()
+                .filter(|(_, bounding_box)| bounding_box.intersects(area))
+                .map(|(id, _)| id.clone())
+        );
+
+        if let Some(quadrants) = &self.quadrants {
+            for quadrant in quadrants.iter() {
+                quadrant.query(area, found);
+            }
+        }
+    }
+
+    fn quadrant_for(&mut self, bounding_box: &BoundingBox) -> Option<&mut Node> {
+        self.quadrants
+            .as_mut()?
+            .iter_mut()
+            .find(|quadrant| quadrant.bounds.contains(bounding_box))
+    }
+
+    fn split(&mut self) {
+        let (center_x, center_y) = self.bounds.center();
+        let width: f64 = self.bounds.width() / 2.0;
+        let height: f64 = self.bounds.height() / 2.0;
+        let depth: usize = self.depth + 1;
+
+        self.quadrants = Some(Box::new([
+            Node::new(BoundingBox::new(self.bounds.left, self.bounds.top, width, height), depth),
+            Node::new(BoundingBox::new(center_x, self.bounds.top, width, height), depth),
+            Node::new(BoundingBox::new(self.bounds.left, center_y, width, height), depth),
+            Node::new(BoundingBox::new(center_x, center_y, width, height), depth),
+        ]));
+
+        for (id, bounding_box) in std::mem::take(&mut self.items) {
+            self.insert(id, bounding_box);
+        }
+    }
+}
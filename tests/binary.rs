@@ -0,0 +1,109 @@
+use serde_json::{json, Value};
+use whiteboard_rs::document::binary::{self, DecodeError};
+use whiteboard_rs::document::Document;
+use whiteboard_rs::Whiteboard;
+
+const BOARDS: [&str; 2] = [
+    include_str!("fixtures/v0/board.json"),
+    include_str!("fixtures/v1/board.json"),
+];
+
+fn round_trip(value: &Value) -> Value {
+    binary::decode(&binary::encode(value)).expect("Can't decode")
+}
+
+#[test]
+fn boards_round_trip_exactly() {
+    for board in BOARDS {
+        let value: Value = serde_json::from_str(board).expect("Fixture is valid json");
+
+        assert_eq!(round_trip(&value), value);
+    }
+}
+
+#[test]
+fn numbers_keep_their_kind_and_bits() {
+    let value: Value = json!({
+        "points": [
+            { "x": 0.5, "y": -12.25 },
+            { "x": 1.0, "y": -12.0 },
+            { "x": 0.1, "y": 1e-9 },
+            { "x": std::f64::consts::PI, "y": -0.0 },
+            { "x": 1, "y": -1 },
+            { "x": u64::MAX, "y": i64::MIN },
+            { "x": 1e300, "y": 9007199254740993u64 },
+        ],
+    });
+
+    let decoded: Value = round_trip(&value);
+
+    assert_eq!(decoded, value);
+    assert!(decoded["points"][3]["y"].as_f64().is_some_and(|y| y.is_sign_negative()));
+    assert!(decoded["points"][1]["x"].is_f64());
+    assert!(decoded["points"][4]["x"].is_i64());
+}
+
+#[test]
+fn point_arrays_are_smaller_than_json() {
+    let points: Vec<Value> = (0..1000)
+        .map(|index| json!({ "x": 100.0 + index as f64 * 0.5, "y": 300.0 + (index % 7) as f64 }))
+        .collect();
+    let value: Value = json!({ "points": points });
+
+    let json_size: usize = value.to_string().len();
+    let binary_size: usize = binary::encode(&value).len();
+
+    assert!(binary_size * 2 < json_size, "{binary_size} bytes against {json_size} bytes of json");
+}
+
+#[test]
+fn corrupted_bytes_are_rejected() {
+    let bytes: Vec<u8> = binary::encode(&serde_json::from_str(BOARDS[1]).expect("Fixture is valid json"));
+
+    assert_eq!(binary::decode(b"{}"), Err(DecodeError::Format));
+    assert_eq!(binary::decode(&bytes[..bytes.len() - 1]), Err(DecodeError::UnexpectedEnd));
+    assert!(binary::decode(&[bytes.as_slice(), &[0]].concat()).is_err());
+    assert!(Document::from_bytes(&bytes[..bytes.len() / 2]).is_err());
+}
+
+/* Arrays nested the given number of times around null. */
+fn nested(depth: usize) -> Vec<u8> {
+    let null: Vec<u8> = binary::encode(&Value::Null);
+    let (magic, value) = null.split_at(null.len() - 1);
+    let array: Vec<u8> = binary::encode(&json!([null]));
+    let array_start: &[u8] = &array[magic.len()..array.len() - 1];
+
+    [magic, &array_start.repeat(depth), value].concat()
+}
+
+#[test]
+fn deeply_nested_bytes_are_rejected() {
+    assert!(binary::decode(&nested(128)).is_ok());
+    assert_eq!(binary::decode(&nested(129)), Err(DecodeError::Depth));
+    assert_eq!(binary::decode(&nested(100_000)), Err(DecodeError::Depth));
+}
+
+#[test]
+fn documents_read_from_bytes_match_json() {
+    for board in BOARDS {
+        let document: Document = Document::from_json(board).expect("Can't read fixture");
+        let bytes: Vec<u8> = document.to_bytes().expect("Can't encode document");
+
+        let from_bytes: Document = Document::from_bytes(&bytes).expect("Can't decode document");
+
+        assert_eq!(from_bytes.to_json().ok(), document.to_json().ok());
+    }
+}
+
+#[test]
+fn board_loads_its_bytes() {
+    let mut whiteboard: Whiteboard = Whiteboard::new("writer");
+    assert!(whiteboard.load_json(BOARDS[1]).expect("Board accepts loading"));
+
+    let bytes: Vec<u8> = whiteboard.to_bytes();
+    let mut reader: Whiteboard = Whiteboard::new("reader");
+
+    assert!(reader.load_bytes(&bytes).expect("Board accepts loading"));
+    assert_eq!(reader.to_json(), whiteboard.to_json());
+    assert!(!reader.load_bytes(b"not a board").expect("Board accepts loading"));
+}
@@ -0,0 +1,125 @@
+use geometry::point::point_2d::Point2D;
+use serde_json::{json, Value};
+use standard_tool_plugin::tool::{Interaction, PointingDevice};
+use whiteboard_rs::document::Document;
+use whiteboard_rs::element::id::Id;
+use whiteboard_rs::element::json_entity::JsonEntity;
+use whiteboard_rs::element::ElementType;
+use whiteboard_rs::mode::BoardMode;
+use whiteboard_rs::tool::eraser_tool::EraserMode;
+use whiteboard_rs::Whiteboard;
+
+fn collaborator(owner_id: &str) -> Whiteboard {
+    let mut whiteboard: Whiteboard = Whiteboard::new(owner_id);
+    whiteboard.enable_collaboration();
+
+    whiteboard
+}
+
+fn draw_line(whiteboard: &mut Whiteboard) {
+    draw_line_at(whiteboard, 0.0);
+}
+
+fn draw_line_at(whiteboard: &mut Whiteboard, y: f64) {
+    whiteboard.activate_move_draw(ElementType::Line).expect("Can't activate line");
+    whiteboard.interact(Interaction::PointerDown(Point2D::new(0.0, y), PointingDevice::Mouse));
+    whiteboard.interact(Interaction::PointerMove(Point2D::new(100.0, y + 50.0), PointingDevice::Mouse));
+    whiteboard.interact(Interaction::PointerUp(Point2D::new(100.0, y + 50.0), PointingDevice::Mouse));
+    whiteboard.run_pending();
+}
+
+fn entity_count(whiteboard: &Whiteboard) -> usize {
+    Document::capture(whiteboard.view_port()).expect("Can't capture board").entities.len()
+}
+
+/* Ids in z-order. */
+fn order(whiteboard: &Whiteboard) -> Vec<Id> {
+    Document::capture(whiteboard.view_port())
+        .expect("Can't capture board")
+        .entities
+        .into_iter()
+        .map(|entity| entity.id)
+        .collect()
+}
+
+fn line(whiteboard: &Whiteboard) -> (Id, Value) {
+    let document: Document = Document::capture(whiteboard.view_port()).expect("Can't capture board");
+    let line: JsonEntity = document.entities.into_iter().next().expect("Line is on the board");
+
+    (line.id, line.model)
+}
+
+fn set(whiteboard: &mut Whiteboard, field: &str, value: f64) {
+    let (id, _) = line(whiteboard);
+    let patch: Value = json!([{ "op": "set", "path": ["model", field], "value": value }]);
+
+    assert!(whiteboard.patch_entity(&id, &patch.to_string()).expect("Board accepts patches"));
+}
+
+#[test]
+fn changes_not_allowed_are_relayed_but_not_shown() {
+    let mut alice: Whiteboard = collaborator("alice");
+    draw_line(&mut alice);
+
+    let mut bob: Whiteboard = collaborator("bob");
+    bob.set_owner_mode("alice", BoardMode::ReadOnly);
+    assert!(bob.apply_update(&alice.take_local_update()));
+    assert_eq!(entity_count(&bob), 0, "read-only owner can't add entities");
+
+    let mut carol: Whiteboard = collaborator("carol");
+    assert!(carol.apply_update(&bob.encode_update(&[]).expect("Empty state vector is valid")));
+    assert_eq!(entity_count(&carol), 1, "change is relayed although it is not shown");
+}
+
+#[test]
+fn edits_not_allowed_stay_out_of_the_entity() {
+    let mut alice: Whiteboard = collaborator("alice");
+    draw_line(&mut alice);
+    let drawn: Vec<u8> = alice.take_local_update();
+
+    let mut bob: Whiteboard = collaborator("bob");
+    assert!(bob.apply_update(&drawn));
+
+    let mut carol: Whiteboard = collaborator("carol");
+    carol.set_owner_mode("bob", BoardMode::ReadOnly);
+    assert!(carol.apply_update(&drawn));
+
+    set(&mut bob, "x2", 500.0);
+    assert!(carol.apply_update(&bob.take_local_update()));
+    assert_eq!(line(&carol).1["x2"], json!(100.0), "read-only edit is not shown");
+
+    set(&mut alice, "y2", 80.0);
+    assert!(carol.apply_update(&alice.take_local_update()));
+    assert_eq!(line(&carol).1["y2"], json!(80.0), "allowed edit is shown");
+    assert_eq!(line(&carol).1["x2"], json!(100.0), "read-only edit doesn't come with the allowed one");
+
+    let mut dave: Whiteboard = collaborator("dave");
+    dave.set_owner_mode("bob", BoardMode::ReadOnly);
+    set(&mut carol, "y1", 10.0);
+    assert!(dave.apply_update(&carol.encode_update(&[]).expect("Empty state vector is valid")));
+    assert_eq!(line(&dave).1["x2"], json!(100.0), "later local edits don't spread the read-only one");
+}
+
+#[test]
+fn undone_removal_keeps_its_place_on_peers() {
+    let mut alice: Whiteboard = collaborator("alice");
+    for y in [0.0, 200.0, 400.0] {
+        draw_line_at(&mut alice, y);
+    }
+
+    let mut bob: Whiteboard = collaborator("bob");
+    assert!(bob.apply_update(&alice.take_local_update()));
+
+    alice.activate_eraser(EraserMode::Stroke, 5.0).expect("Can't activate eraser");
+    alice.interact(Interaction::PointerDown(Point2D::new(50.0, 225.0), PointingDevice::Mouse));
+    alice.interact(Interaction::PointerMove(Point2D::new(52.0, 226.0), PointingDevice::Mouse));
+    alice.interact(Interaction::PointerUp(Point2D::new(52.0, 226.0), PointingDevice::Mouse));
+    alice.run_pending();
+    assert_eq!(entity_count(&alice), 2, "middle line is erased");
+
+    assert!(alice.undo().expect("Can't undo"));
+    assert!(bob.apply_update(&alice.take_local_update()));
+
+    assert_eq!(entity_count(&bob), 3);
+    assert!(order(&bob) == order(&alice), "line is back in the middle on both boards");
+}
@@ -0,0 +1,187 @@
+use serde_json::{json, Value};
+use whiteboard_rs::document::excalidraw::{self, Report};
+use whiteboard_rs::document::Document;
+use whiteboard_rs::element::ElementType;
+use whiteboard_rs::Whiteboard;
+
+const SCENE: &str = include_str!("fixtures/excalidraw/scene.excalidraw");
+
+fn id(index: u32, element_type: &str) -> Value {
+    json!({ "owner_id": "alice", "index": index, "element_type": element_type })
+}
+
+fn entity(index: u32, element_type: &str, model: Value) -> Value {
+    json!({ "id": id(index, element_type), "model": model })
+}
+
+fn exported(board: Value) -> (Vec<Value>, Report) {
+    let document: Document = Document::from_json(&board.to_string()).expect("Can't read board");
+    let (scene, report) = excalidraw::export(&document);
+    let scene: Value = serde_json::from_str(&scene).expect("Scene is valid json");
+
+    assert_eq!(scene["type"], json!("excalidraw"));
+
+    (scene["elements"].as_array().cloned().unwrap_or_default(), report)
+}
+
+fn element<'a>(elements: &'a [Value], id: &str) -> &'a Value {
+    elements.iter().find(|element| element["id"] == id).unwrap_or_else(|| panic!("{id} is exported"))
+}
+
+#[test]
+fn entities_export_with_style_and_bindings() {
+    let mut ellipse: Value = entity(1, "Ellipse", json!({ "x": 10.0, "y": 20.0, "width": -100.0, "height": 50.0 }));
+    ellipse["style"] = json!({ "stroke_color": "#1971c2", "fill_color": "#ffec99", "stroke_width": 3.0, "dash_array": [8.0, 11.0], "opacity": 0.5 });
+
+    let (elements, report) = exported(json!({
+        "entities": [
+            ellipse,
+            entity(2, "Line", json!({ "x1": 0.0, "y1": 0.0, "x2": 30.0, "y2": -40.0 })),
+            entity(3, "Connector", json!({
+                "start": { "Bound": { "id": id(1, "Ellipse") } },
+                "end": { "Free": { "x": 300.0, "y": 45.0 } },
+                "routing": "Straight",
+                "start_arrowhead": "None",
+                "end_arrowhead": "Triangle",
+                "label": "next",
+                "route": [[10.0, 45.0], [300.0, 45.0]],
+            })),
+            entity(4, "Connector", json!({
+                "start": { "Bound": { "id": id(8, "StickyNote") } },
+                "end": { "Free": { "x": 0.0, "y": 0.0 } },
+                "routing": "Straight",
+                "start_arrowhead": "None",
+                "end_arrowhead": "Arrow",
+                "route": [[500.0, 500.0], [0.0, 0.0]],
+            })),
+            entity(5, "Container", json!({ "children": [
+                entity(6, "Line", json!({ "x1": 0.0, "y1": 0.0, "x2": 10.0, "y2": 10.0 })),
+                entity(7, "Container", json!({ "children": [
+                    entity(9, "Ellipse", json!({ "x": 0.0, "y": 0.0, "width": 10.0, "height": 10.0 })),
+                ] })),
+            ] })),
+            entity(8, "StickyNote", json!({ "x": 0.0, "y": 0.0, "size": 100.0, "color": "Yellow", "text": "" })),
+            entity(10, "RegularPolygon", json!({ "x": 0.0, "y": 0.0, "width": 100.0, "height": 100.0, "sides": 6 })),
+            entity(11, "Text", json!({
+                "x": 0.0, "y": 200.0, "width": 100.0, "height": 19.2, "text": "Bold",
+                "font": { "family": "sans-serif", "size": 16.0, "weight": 700 }, "color": "#862e9c",
+            })),
+        ],
+    }));
+
+    let ellipse: &Value = element(&elements, "alice-1");
+    assert_eq!(ellipse["type"], json!("ellipse"));
+    assert_eq!((ellipse["x"].as_f64(), ellipse["width"].as_f64()), (Some(-90.0), Some(100.0)));
+    assert_eq!(ellipse["strokeStyle"], json!("dashed"));
+    assert_eq!(ellipse["backgroundColor"], json!("#ffec99"));
+    assert_eq!(ellipse["opacity"].as_f64(), Some(50.0));
+    assert_eq!(ellipse["boundElements"], json!([{ "id": "alice-3", "type": "arrow" }]));
+
+    let line: &Value = element(&elements, "alice-2");
+    assert_eq!(line["points"], json!([[0.0, 0.0], [30.0, -40.0]]));
+
+    let arrow: &Value = element(&elements, "alice-3");
+    assert_eq!(arrow["startBinding"]["elementId"], json!("alice-1"));
+    assert_eq!(arrow["endArrowhead"], json!("triangle"));
+    assert_eq!(arrow["boundElements"], json!([{ "id": "alice-3-label", "type": "text" }]));
+    assert_eq!(element(&elements, "alice-3-label")["containerId"], json!("alice-3"));
+    assert_eq!(element(&elements, "alice-4")["startBinding"], Value::Null, "sticky notes are not exported");
+
+    assert_eq!(element(&elements, "alice-6")["groupIds"], json!(["alice-5"]));
+    assert_eq!(element(&elements, "alice-9")["groupIds"], json!(["alice-7", "alice-5"]));
+    assert_eq!(element(&elements, "alice-10")["type"], json!("line"));
+    assert_eq!(element(&elements, "alice-11")["strokeColor"], json!("#862e9c"));
+
+    let notes = |notes: &[excalidraw::Note]| notes.iter().map(|note| note.id.clone()).collect::<Vec<String>>();
+    assert_eq!(report.converted, 8);
+    assert_eq!(notes(&report.skipped), ["alice-8"]);
+    assert_eq!(notes(&report.approximated), ["alice-10", "alice-11"]);
+}
+
+#[test]
+fn other_json_is_not_a_scene() {
+    assert!(excalidraw::import("{\"entities\": []}", "alice", &whiteboard_rs::text::measure::ApproximateTextMeasure).is_err());
+}
+
+fn board_entities(whiteboard: &Whiteboard) -> Vec<Value> {
+    let board: Value = serde_json::from_str(&whiteboard.to_json()).expect("Board json is valid");
+
+    board["entities"].as_array().cloned().unwrap_or_default()
+}
+
+fn count(entities: &[Value], element_type: ElementType) -> usize {
+    let element_type: Value = serde_json::to_value(element_type).expect("Can't serialize element type");
+
+    entities.iter().filter(|entity| entity["id"]["element_type"] == element_type).count()
+}
+
+#[test]
+fn scene_imports_as_one_step() {
+    let mut whiteboard: Whiteboard = Whiteboard::new("importer");
+
+    let report: String = whiteboard.import_excalidraw(SCENE).expect("Board accepts import").expect("Fixture is a scene");
+    let report: Value = serde_json::from_str(&report).expect("Report is valid json");
+    let reasons = |notes: &Value| -> Vec<(String, String)> {
+        notes
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|note| (note["id"].as_str().unwrap_or_default().to_string(), note["reason"].as_str().unwrap_or_default().to_string()))
+            .collect()
+    };
+
+    assert_eq!(report["converted"], json!(12));
+    assert_eq!(reasons(&report["skipped"]).iter().map(|(id, _)| id.as_str()).collect::<Vec<&str>>(), ["broken", "image", "embed"]);
+    assert_eq!(reasons(&report["approximated"]), [
+        ("ellipse".to_string(), "Rotation is dropped".to_string()),
+        ("diamond".to_string(), "Hatched fill is drawn solid".to_string()),
+        ("dot-arrow".to_string(), "Bends of the arrow are dropped".to_string()),
+        ("dot-arrow".to_string(), "Arrowhead dot is drawn as an arrow".to_string()),
+    ]);
+
+    let entities: Vec<Value> = board_entities(&whiteboard);
+    assert_eq!(entities.len(), 9, "grouped elements are inside one container");
+    assert_eq!(count(&entities, ElementType::Rectangle), 1);
+    assert_eq!(count(&entities, ElementType::Container), 1);
+    assert_eq!(count(&entities, ElementType::Polygon), 1);
+    assert_eq!(count(&entities, ElementType::Connector), 2);
+    assert_eq!(count(&entities, ElementType::Frame), 1);
+
+    let rectangle: &Value = entities.iter().find(|entity| entity["id"]["element_type"] == json!("Rectangle")).expect("Rectangle is imported");
+    assert_eq!(rectangle["style"]["stroke_color"], json!("#1971c2"));
+    assert_eq!(rectangle["style"]["fill_color"], json!("#a5d8ff"));
+    assert_eq!(rectangle["style"]["dash_array"], json!([8.0, 12.0]));
+    assert_eq!(rectangle["style"]["corner_radius"], json!(30.0));
+
+    let labelled: &Value = entities.iter().find(|entity| entity["model"]["label"] == json!("next")).expect("Label is kept");
+    assert_eq!(labelled["model"]["start"]["Bound"]["id"], rectangle["id"]);
+
+    let container: &Value = entities.iter().find(|entity| entity["id"]["element_type"] == json!("Container")).expect("Group is imported");
+    assert_eq!(container["model"]["children"].as_array().map(Vec::len), Some(2), "inner group and text");
+
+    assert!(whiteboard.undo().expect("Board accepts undo"));
+    assert!(board_entities(&whiteboard).is_empty());
+}
+
+#[test]
+fn exported_scene_imports_back() {
+    let mut whiteboard: Whiteboard = Whiteboard::new("importer");
+    whiteboard.import_excalidraw(SCENE).expect("Board accepts import").expect("Fixture is a scene");
+
+    let report: Value = serde_json::from_str(&whiteboard.export_excalidraw_report()).expect("Report is valid json");
+    assert_eq!(report["skipped"], json!([]));
+
+    let scene: Value = serde_json::from_str(&whiteboard.export_excalidraw()).expect("Scene is valid json");
+    for kind in ["rectangle", "ellipse", "diamond", "line", "arrow", "freedraw", "text", "frame"] {
+        assert!(scene["elements"].as_array().into_iter().flatten().any(|element| element["type"] == kind), "{kind} is exported");
+    }
+
+    let mut reader: Whiteboard = Whiteboard::new("reader");
+    let report: String = reader.import_excalidraw(&whiteboard.export_excalidraw()).expect("Board accepts import").expect("Export is a scene");
+    let report: Value = serde_json::from_str(&report).expect("Report is valid json");
+
+    assert_eq!(report["skipped"], json!([]));
+    assert_eq!(report["approximated"], json!([]));
+    assert_eq!(board_entities(&reader).len(), board_entities(&whiteboard).len());
+    assert_eq!(reader.import_excalidraw("[1, 2]").expect("Board accepts import"), None);
+}
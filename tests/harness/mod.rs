@@ -0,0 +1,94 @@
+use geometry::point::point_2d::Point2D;
+use standard_tool_plugin::tool::{Interaction, PointingDevice};
+use whiteboard_rs::document::Document;
+use whiteboard_rs::element::json_entity::JsonEntity;
+use whiteboard_rs::element::ElementType;
+use whiteboard_rs::renderer::renderer::recording_renderer::RecordingRenderer;
+use whiteboard_rs::Whiteboard;
+
+pub const OWNER_ID: &str = "harness";
+
+/* Whiteboard driven by scripted interactions, the way a host drives it with pointer and key events. */
+pub struct Harness {
+    pub whiteboard: Whiteboard,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        Self {
+            whiteboard: Whiteboard::new(OWNER_ID),
+        }
+    }
+
+    /* Interactions are delivered in order, pending work like adding drawn entities is run after the script. */
+    pub fn replay(&mut self, script: Vec<Interaction>) {
+        for interaction in script {
+            self.whiteboard.interact(interaction);
+        }
+
+        self.whiteboard.run_pending();
+    }
+
+    /* Modifier keys have no interaction of their own, the host sends them by name around the script. */
+    pub fn replay_holding(&mut self, key: &str, script: Vec<Interaction>) {
+        self.whiteboard.key_down(key);
+        self.replay(script);
+        self.whiteboard.key_up(key);
+    }
+
+    pub fn entities(&self) -> Vec<JsonEntity> {
+        Document::capture(self.whiteboard.view_port())
+            .expect("Can't capture board")
+            .entities
+    }
+
+    pub fn entities_of(&self, element_type: ElementType) -> Vec<JsonEntity> {
+        self.entities()
+            .into_iter()
+            .filter(|entity| *entity.id.element_type() == element_type)
+            .collect()
+    }
+
+    /* Entities as json, to compare boards before and after a script. */
+    pub fn snapshot(&self) -> String {
+        serde_json::to_string(&self.entities()).expect("Can't serialize entities")
+    }
+
+    pub fn render(&self) -> RecordingRenderer {
+        let mut recording_renderer: RecordingRenderer = RecordingRenderer::new();
+        self.whiteboard.render(&mut recording_renderer);
+
+        recording_renderer
+    }
+}
+
+pub fn down(x: f64, y: f64) -> Interaction {
+    Interaction::PointerDown(Point2D::new(x, y), PointingDevice::Mouse)
+}
+
+pub fn moved(x: f64, y: f64) -> Interaction {
+    Interaction::PointerMove(Point2D::new(x, y), PointingDevice::Mouse)
+}
+
+pub fn up(x: f64, y: f64) -> Interaction {
+    Interaction::PointerUp(Point2D::new(x, y), PointingDevice::Mouse)
+}
+
+/* Press, move in a few steps and release. */
+pub fn drag(from: (f64, f64), to: (f64, f64)) -> Vec<Interaction> {
+    let steps: usize = 4;
+    let mut script: Vec<Interaction> = vec![down(from.0, from.1)];
+
+    for step in 1..=steps {
+        let progress: f64 = step as f64 / steps as f64;
+        script.push(moved(from.0 + (to.0 - from.0) * progress, from.1 + (to.1 - from.1) * progress));
+    }
+
+    script.push(up(to.0, to.1));
+
+    script
+}
+
+pub fn click(at: (f64, f64)) -> Vec<Interaction> {
+    vec![down(at.0, at.1), up(at.0, at.1)]
+}
@@ -0,0 +1,240 @@
+use entity_model_feature::entity::Entity;
+use geometry::point::point_2d::Point2D;
+use serde_json::{json, Value};
+use standard_entity_plugin::model::path_model::PathModel;
+use whiteboard_rs::document::Document;
+use whiteboard_rs::element::free_hand;
+use whiteboard_rs::element::id::Id;
+use whiteboard_rs::element::json_entity::JsonEntity;
+use whiteboard_rs::element::patch::{Operation, Patch};
+use whiteboard_rs::element::transform::Transform;
+use whiteboard_rs::element::{Build, ElementType};
+use whiteboard_rs::events::Events;
+use whiteboard_rs::history::Change;
+use whiteboard_rs::text::measure::ApproximateTextMeasure;
+use whiteboard_rs::view_port::ViewPort;
+use whiteboard_rs::Whiteboard;
+
+fn entity(model: Value) -> JsonEntity {
+    serde_json::from_value(json!({
+        "id": { "owner_id": "alice", "index": 1, "element_type": "Line" },
+        "model": model,
+    }))
+    .expect("Entity json is valid")
+}
+
+fn stroke(points: usize) -> Value {
+    let points: Vec<Value> = (0..points).map(|index| json!({ "x": index as f64, "y": (index % 5) as f64 })).collect();
+
+    json!({ "points": points, "closed": false })
+}
+
+fn apply(patch: &Patch, json_entity: &JsonEntity) -> JsonEntity {
+    patch.apply(json_entity, &ApproximateTextMeasure).expect("Patch fits the entity")
+}
+
+#[test]
+fn diff_applies_back_exactly() {
+    let before: JsonEntity = entity(json!({ "x1": 0.0, "y1": 0.0, "x2": 10.0, "y2": 10.0 }));
+    let mut after: JsonEntity = entity(json!({ "x1": 0.0, "y1": 5.0, "x2": 10.0, "label": "new" }));
+    after.style = Some(serde_json::from_value(json!({ "stroke_color": "#e53935" })).expect("Style json is valid"));
+
+    let patch: Patch = Patch::diff(&before, &after);
+    let patched: JsonEntity = apply(&patch, &before);
+
+    assert_eq!(patch.operations().len(), 4, "y1, y2, label and style change");
+    assert_eq!(patched.model, after.model);
+    assert!(patched.style == after.style);
+    assert_eq!(apply(&Patch::diff(&after, &before), &after).model, before.model);
+}
+
+#[test]
+fn appended_points_are_one_splice() {
+    let before: JsonEntity = entity(stroke(5000));
+    let after: JsonEntity = entity(stroke(5010));
+
+    let patch: Patch = Patch::diff(&before, &after);
+
+    assert!(matches!(
+        patch.operations(),
+        [Operation::Splice { index: 5000, delete: 0, insert, .. }] if insert.len() == 10
+    ));
+    assert_eq!(apply(&patch, &before).model, after.model);
+}
+
+#[test]
+fn moved_point_is_set_in_place() {
+    let before: JsonEntity = entity(stroke(5000));
+    let mut after: JsonEntity = before.clone();
+    after.model["points"][1234]["x"] = json!(-1.0);
+
+    let patch: Patch = Patch::diff(&before, &after);
+    let json: String = serde_json::to_string(&patch).expect("Can't serialize patch");
+
+    assert_eq!(json, r#"[{"op":"set","path":["model","points",1234,"x"],"value":-1.0}]"#);
+    assert_eq!(apply(&serde_json::from_str(&json).expect("Can't read patch"), &before).model, after.model);
+}
+
+#[test]
+fn transform_moves_the_whole_entity() {
+    let before: JsonEntity = entity(json!({ "x1": 0.0, "y1": 0.0, "x2": 10.0, "y2": 10.0 }));
+    let patch: Patch = Patch::transform(Transform {
+        scale_x: 1.0,
+        scale_y: 1.0,
+        translate_x: 5.0,
+        translate_y: -5.0,
+    });
+
+    assert_eq!(apply(&patch, &before).model, json!({ "x1": 5.0, "y1": -5.0, "x2": 15.0, "y2": 5.0 }));
+}
+
+#[test]
+fn moved_stroke_is_recorded_as_transforms() {
+    let points: Vec<Point2D> = (0..5000).map(|index| Point2D::new(index as f64 * 0.5, (index % 7) as f64)).collect();
+    let stroke: Entity<Id> = Build::default_with("alice", ElementType::FreeHand, None, |model: &mut PathModel| {
+        model.path = free_hand::polyline(&points);
+    })
+    .expect("Can't build stroke");
+    let id: Id = stroke.id().clone();
+
+    let mut view_port: ViewPort = ViewPort::new(Events::new());
+    view_port.add_entity(stroke).expect("Can't add stroke");
+    let model = |view_port: &ViewPort| -> Value {
+        view_port
+            .with_entity(&id, JsonEntity::try_from)
+            .expect("Can't lock view port")
+            .expect("Stroke is on the board")
+            .expect("Can't serialize stroke")
+            .model
+    };
+    let before: Value = model(&view_port);
+
+    let moved: Patch = Patch::transform(Transform { scale_x: 1.0, scale_y: 1.0, translate_x: 40.0, translate_y: -25.0 });
+    let change: Change = Change::patch(&mut view_port, &id, &moved, &ApproximateTextMeasure)
+        .expect("Can't patch stroke")
+        .expect("Stroke is on the board");
+
+    let Change::Modify { redo, undo, .. } = &change else {
+        panic!("Patch is recorded as a modification");
+    };
+    assert_eq!(redo.operations().len(), 1, "redo keeps the supplied patch");
+    assert_eq!(undo.operations().len(), 1, "undo moves back instead of setting every point");
+    assert_ne!(model(&view_port), before);
+
+    change.inverse().apply(&mut view_port, &ApproximateTextMeasure).expect("Can't undo patch");
+    assert_eq!(model(&view_port), before);
+}
+
+#[test]
+fn patches_that_do_not_fit_are_rejected() {
+    let before: JsonEntity = entity(stroke(3));
+
+    for patch in [
+        json!([{ "op": "set", "path": ["model", "points", 7, "x"], "value": 1 }]),
+        json!([{ "op": "splice", "path": ["model", "points"], "index": 2, "delete": 5, "insert": [] }]),
+        json!([{ "op": "remove", "path": ["model", "points", 0] }]),
+        json!([{ "op": "set", "path": ["id"], "value": 1 }]),
+    ] {
+        let patch: Patch = serde_json::from_value(patch).expect("Patch json is valid");
+
+        assert!(patch.apply(&before, &ApproximateTextMeasure).is_err());
+    }
+}
+
+#[test]
+fn patched_entity_is_undone() {
+    let mut whiteboard: Whiteboard = Whiteboard::new("writer");
+    assert!(whiteboard.load_json(include_str!("fixtures/v1/board.json")).expect("Board accepts loading"));
+
+    let id: Id = serde_json::from_value(json!({ "owner_id": "alice", "index": 1710000000003u64, "element_type": "Line" }))
+        .expect("Id json is valid");
+    let model = |whiteboard: &Whiteboard| -> Value {
+        Document::capture(whiteboard.view_port())
+            .expect("Can't capture board")
+            .entities
+            .into_iter()
+            .find(|entity| entity.id == id)
+            .map(|entity| entity.model)
+            .expect("Line is on the board")
+    };
+    let before: Value = model(&whiteboard);
+
+    assert!(whiteboard.patch_entity(&id, r#"[{"op": "set", "path": ["model", "x2"], "value": 500.0}]"#).expect("Board accepts patches"));
+    assert_eq!(model(&whiteboard)["x2"], json!(500.0));
+    assert!(!whiteboard.patch_entity(&id, r#"[{"op": "remove", "path": ["model", "missing", "x"]}]"#).expect("Board accepts patches"));
+
+    assert!(whiteboard.undo().expect("Board accepts undo"));
+    assert_eq!(model(&whiteboard), before);
+}
+
+#[test]
+fn rerouted_connector_is_undone_with_the_move() {
+    let id = |index: u32, element_type: &str| json!({ "owner_id": "alice", "index": index, "element_type": element_type });
+    let board: Value = json!({
+        "entities": [
+            { "id": id(1, "Ellipse"), "model": { "x": 0.0, "y": 0.0, "width": 100.0, "height": 50.0 } },
+            { "id": id(2, "Connector"), "model": {
+                "start": { "Bound": { "id": id(1, "Ellipse") } },
+                "end": { "Free": { "x": 300.0, "y": 25.0 } },
+                "routing": "Straight",
+                "start_arrowhead": "None",
+                "end_arrowhead": "Arrow",
+                "route": [[100.0, 25.0], [300.0, 25.0]],
+            } },
+        ],
+    });
+
+    let mut whiteboard: Whiteboard = Whiteboard::new("writer");
+    assert!(whiteboard.load_json(&board.to_string()).expect("Board accepts loading"));
+
+    let ellipse: Id = serde_json::from_value(id(1, "Ellipse")).expect("Id json is valid");
+    let route = |whiteboard: &Whiteboard| -> Value {
+        Document::capture(whiteboard.view_port())
+            .expect("Can't capture board")
+            .entities
+            .into_iter()
+            .find(|entity| *entity.id.element_type() == ElementType::Connector)
+            .map(|entity| entity.model["route"].clone())
+            .expect("Connector is on the board")
+    };
+    let before: Value = route(&whiteboard);
+
+    let patch: Value = json!([{ "op": "transform", "transform": { "scale_x": 1.0, "scale_y": 1.0, "translate_x": 0.0, "translate_y": 200.0 } }]);
+    assert!(whiteboard.patch_entity(&ellipse, &patch.to_string()).expect("Board accepts patches"));
+    let moved: Value = route(&whiteboard);
+    assert_ne!(moved, before, "connector follows the ellipse");
+
+    assert!(whiteboard.undo().expect("Board accepts undo"));
+    assert_eq!(route(&whiteboard), before);
+    assert!(!whiteboard.undo().expect("Board accepts undo"), "reroute is in the step of the move");
+
+    assert!(whiteboard.redo().expect("Board accepts redo"));
+    assert_eq!(route(&whiteboard), moved);
+}
+
+#[test]
+fn huge_coordinates_keep_the_board_working() {
+    let id: Value = json!({ "owner_id": "alice", "index": 1, "element_type": "Ellipse" });
+    let board: Value = json!({ "entities": [{ "id": id, "model": { "x": 0.0, "y": 0.0, "width": 100.0, "height": 50.0 } }] });
+
+    let mut whiteboard: Whiteboard = Whiteboard::new("writer");
+    assert!(whiteboard.load_json(&board.to_string()).expect("Board accepts loading"));
+    let ellipse: Id = serde_json::from_value(id).expect("Id json is valid");
+
+    let huge: Value = json!([
+        { "op": "set", "path": ["model", "x"], "value": -1e308 },
+        { "op": "set", "path": ["model", "width"], "value": 1.5e308 },
+    ]);
+    assert!(whiteboard.patch_entity(&ellipse, &huge.to_string()).expect("Board accepts patches"));
+    assert!(whiteboard.view_port().entities_at(0.0, 25.0).expect("Can't lock view port") == [ellipse.clone()]);
+
+    /* Right edge overflows, the box isn't finite. */
+    let overflowing: Value = json!([
+        { "op": "set", "path": ["model", "x"], "value": 1e308 },
+        { "op": "set", "path": ["model", "width"], "value": 1e308 },
+    ]);
+    assert!(whiteboard.patch_entity(&ellipse, &overflowing.to_string()).expect("Board accepts patches"));
+    assert!(whiteboard.view_port().bounding_box(&ellipse).expect("Can't lock view port").is_none());
+    assert!(whiteboard.view_port().entities_at(0.0, 25.0).expect("Can't lock view port").is_empty());
+}
+
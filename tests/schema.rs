@@ -0,0 +1,148 @@
+use serde_json::{json, Map, Value};
+use whiteboard_rs::document::Document;
+use whiteboard_rs::element::json_entity::JsonEntity;
+use whiteboard_rs::schema::{self, Migration};
+use whiteboard_rs::Whiteboard;
+
+/* Boards saved by each schema version, kept as they were written. Add one for every new version. */
+const FIXTURES: [(u32, &str); 2] = [
+    (0, include_str!("fixtures/v0/board.json")),
+    (1, include_str!("fixtures/v1/board.json")),
+];
+
+fn fixture_value(json: &str) -> Value {
+    serde_json::from_str(json).expect("Fixture is valid json")
+}
+
+/* Boards saved before frames existed have none. */
+fn frame_count(json: &str) -> usize {
+    fixture_value(json)["frames"].as_array().map_or(0, Vec::len)
+}
+
+#[test]
+fn every_version_has_a_migration_and_a_fixture() {
+    for (index, migration) in schema::MIGRATIONS.iter().enumerate() {
+        assert_eq!(migration.from as usize, index, "migrations are in version order");
+    }
+
+    assert_eq!(schema::MIGRATIONS.len() as u32, schema::VERSION);
+    assert_eq!(FIXTURES.last().map(|(version, _)| *version), Some(schema::VERSION));
+}
+
+#[test]
+fn fixtures_upgrade_to_the_current_version() {
+    for (version, json) in FIXTURES {
+        let document: Document = Document::from_json(json).expect("Can't read fixture");
+        let entity_count: usize = fixture_value(json)["entities"].as_array().map_or(0, Vec::len);
+
+        assert_eq!(document.version, schema::VERSION, "v{version}: board is upgraded");
+        assert_eq!(document.entities.len(), entity_count, "v{version}: no entity is lost");
+        assert!(
+            document.entities.iter().all(|entity| entity.version == schema::VERSION),
+            "v{version}: entities are upgraded",
+        );
+        assert_eq!(document.frames.len(), frame_count(json), "v{version}: frame order is kept");
+    }
+}
+
+#[test]
+fn current_boards_are_not_changed_by_upgrade() {
+    let (_, json) = FIXTURES[FIXTURES.len() - 1];
+    let upgraded: Value = schema::upgrade_document(fixture_value(json)).expect("Can't upgrade board");
+
+    assert_eq!(upgraded, fixture_value(json));
+}
+
+#[test]
+fn newer_boards_are_rejected() {
+    let mut board: Value = fixture_value(FIXTURES[FIXTURES.len() - 1].1);
+    board["version"] = json!(schema::VERSION + 1);
+
+    assert!(Document::from_json(&board.to_string()).is_err());
+}
+
+#[test]
+fn entity_json_is_upgraded() {
+    let entity: Value = fixture_value(FIXTURES[0].1)["entities"][1].clone();
+    let upgraded: Value = schema::upgrade_entity(entity).expect("Can't upgrade entity");
+
+    assert_eq!(upgraded["version"], json!(schema::VERSION));
+}
+
+#[test]
+fn entities_read_on_their_own_are_upgraded() {
+    let mut entity: Value = fixture_value(FIXTURES[0].1)["entities"][1].clone();
+    let json_entity: JsonEntity = serde_json::from_value(entity.clone()).expect("Can't read entity");
+
+    assert_eq!(json_entity.version, schema::VERSION, "entity without version is read as unversioned");
+
+    entity["version"] = json!(schema::VERSION + 1);
+    assert!(serde_json::from_value::<JsonEntity>(entity).is_err());
+}
+
+#[test]
+fn unversioned_text_becomes_a_text_box() {
+    let (_, json) = FIXTURES[0];
+    let document: Value = schema::upgrade_document(fixture_value(json)).expect("Can't upgrade board");
+
+    let plain: &Value = &document["entities"][0]["model"];
+    assert_eq!(plain["text"], json!("Saved before versioning"));
+    assert_eq!(plain["font"], json!({ "family": "sans-serif", "size": 16.0, "weight": 400 }));
+    assert_eq!(plain["color"], json!("#000000"));
+    assert_eq!(plain["lines"].as_array().map(Vec::len), Some(1));
+
+    let styled: &Value = &document["entities"][1]["model"];
+    assert_eq!(styled["font"], json!({ "family": "serif", "size": 20.0, "weight": 400 }));
+    assert_eq!(styled["color"], json!("#1e88e5"));
+    assert!(styled["lines"].as_array().map_or(0, Vec::len) > 1, "text is wrapped to the box width");
+    assert_eq!(styled.get("font_size"), None);
+}
+
+fn mark(entity: &mut Map<String, Value>) -> serde_json::Result<()> {
+    let count: u64 = entity.get("marks").and_then(Value::as_u64).unwrap_or(0);
+    entity.insert("marks".to_string(), json!(count + 1));
+
+    Ok(())
+}
+
+#[test]
+fn migrations_run_from_the_saved_version() {
+    let migrations: [Migration; 2] = [
+        Migration { from: 0, migrate: mark },
+        Migration { from: 1, migrate: mark },
+    ];
+
+    let board: Value = json!({
+        "version": 1,
+        "entities": [
+            { "id": 1, "model": {} },
+            { "id": 2, "model": {}, "version": 0 },
+            { "id": 3, "model": {}, "version": 2 },
+            { "id": 4, "model": { "children": [{ "id": 5, "model": {} }] } },
+        ],
+    });
+
+    let upgraded: Value = schema::upgrade_document_with(board, &migrations).expect("Can't upgrade board");
+    let entities: &Value = &upgraded["entities"];
+
+    assert_eq!(upgraded["version"], json!(2));
+    assert_eq!(entities[0]["marks"], json!(1), "entity without version has the board version");
+    assert_eq!(entities[1]["marks"], json!(2), "older entity runs every migration");
+    assert_eq!(entities[2].get("marks"), None, "current entity is left as it is");
+    assert_eq!(entities[3]["model"]["children"][0]["marks"], json!(1), "children are migrated");
+    assert!(entities.as_array().into_iter().flatten().all(|entity| entity["version"] == json!(2)));
+}
+
+#[test]
+fn fixtures_load_into_the_board() {
+    for (version, json) in FIXTURES {
+        let mut whiteboard: Whiteboard = Whiteboard::new("reader");
+
+        assert!(whiteboard.load_json(json).expect("Board accepts loading"), "v{version}: board is loaded");
+        assert_eq!(whiteboard.get_frames().len(), frame_count(json), "v{version}: frames are restored");
+
+        let saved: Value = fixture_value(&whiteboard.to_json());
+        assert_eq!(saved["version"], json!(schema::VERSION), "v{version}: board is saved with the current version");
+        assert_eq!(saved["entities"].as_array().map(Vec::len), fixture_value(json)["entities"].as_array().map(Vec::len));
+    }
+}
@@ -0,0 +1,117 @@
+use futures::executor::block_on;
+use geometry::point::point_2d::Point2D;
+use serde_json::{json, Value};
+use standard_tool_plugin::tool::{Interaction, PointingDevice};
+use std::rc::Rc;
+use whiteboard_rs::document::Document;
+use whiteboard_rs::element::ElementType;
+use whiteboard_rs::storage::autosave::Autosave;
+use whiteboard_rs::storage::memory_storage::MemoryStorage;
+use whiteboard_rs::storage::SnapshotInfo;
+use whiteboard_rs::Whiteboard;
+
+fn draw_line(whiteboard: &mut Whiteboard, y: f64) {
+    whiteboard.activate_move_draw(ElementType::Line).expect("Can't activate line");
+    whiteboard.interact(Interaction::PointerDown(Point2D::new(0.0, y), PointingDevice::Mouse));
+    whiteboard.interact(Interaction::PointerMove(Point2D::new(100.0, y), PointingDevice::Mouse));
+    whiteboard.interact(Interaction::PointerUp(Point2D::new(100.0, y), PointingDevice::Mouse));
+    whiteboard.run_pending();
+}
+
+fn entity_count(whiteboard: &Whiteboard) -> usize {
+    Document::capture(whiteboard.view_port()).expect("Can't capture board").entities.len()
+}
+
+fn revisions(autosave: &Autosave) -> Vec<u32> {
+    block_on(autosave.revisions())
+        .expect("Memory storage lists revisions")
+        .iter()
+        .map(|info: &SnapshotInfo| info.revision)
+        .collect()
+}
+
+#[test]
+fn changes_are_saved_keeping_latest_revisions() {
+    let mut whiteboard: Whiteboard = Whiteboard::new("saver");
+    whiteboard.enable_memory_autosave(0, 2);
+    let autosave: Autosave = whiteboard.autosave().expect("Autosave is enabled");
+
+    draw_line(&mut whiteboard, 0.0);
+    assert_eq!(revisions(&autosave), [1]);
+
+    draw_line(&mut whiteboard, 50.0);
+    draw_line(&mut whiteboard, 100.0);
+    assert_eq!(revisions(&autosave), [3, 2], "newest first, older ones are pruned");
+
+    whiteboard.disable_autosave();
+    draw_line(&mut whiteboard, 150.0);
+    assert_eq!(revisions(&autosave), [3, 2]);
+}
+
+#[test]
+fn remote_changes_are_saved() {
+    let mut writer: Whiteboard = Whiteboard::new("writer");
+    writer.enable_collaboration();
+    draw_line(&mut writer, 0.0);
+
+    let mut reader: Whiteboard = Whiteboard::new("reader");
+    reader.enable_collaboration();
+    reader.enable_memory_autosave(0, 5);
+    let autosave: Autosave = reader.autosave().expect("Autosave is enabled");
+
+    assert!(reader.apply_update(&writer.encode_update(&[]).expect("Collaboration is enabled")));
+    reader.run_pending();
+
+    assert_eq!(revisions(&autosave), [1]);
+}
+
+#[test]
+fn unchanged_board_is_not_saved_again() {
+    let mut whiteboard: Whiteboard = Whiteboard::new("saver");
+    let autosave: Autosave = Autosave::new(Rc::new(MemoryStorage::new()), whiteboard.view_port().clone(), 0, 5);
+
+    draw_line(&mut whiteboard, 0.0);
+
+    assert_eq!(block_on(autosave.save()).expect("Memory storage saves"), Some(1));
+    assert_eq!(block_on(autosave.save()).expect("Memory storage saves"), None);
+    assert_eq!(revisions(&autosave), [1]);
+}
+
+#[test]
+fn revisions_are_restored() {
+    let mut whiteboard: Whiteboard = Whiteboard::new("saver");
+    whiteboard.enable_memory_autosave(0, 5);
+    let autosave: Autosave = whiteboard.autosave().expect("Autosave is enabled");
+
+    draw_line(&mut whiteboard, 0.0);
+    draw_line(&mut whiteboard, 50.0);
+    draw_line(&mut whiteboard, 100.0);
+    assert_eq!(entity_count(&whiteboard), 3);
+
+    assert_eq!(block_on(autosave.restore(Some(1))).expect("Memory storage restores"), Some(1));
+    assert_eq!(entity_count(&whiteboard), 1);
+
+    assert_eq!(block_on(autosave.restore(Some(9))).expect("Memory storage restores"), None);
+    assert_eq!(entity_count(&whiteboard), 1, "missing revision leaves the board as it is");
+
+    assert_eq!(block_on(autosave.restore(None)).expect("Memory storage restores"), Some(3));
+    assert_eq!(entity_count(&whiteboard), 3);
+}
+
+#[test]
+fn entities_without_bounds_are_saved() {
+    let empty_text: Value = json!({
+        "entities": [{
+            "id": { "owner_id": "saver", "index": 1, "element_type": "Text" },
+            "model": { "x": 0.0, "y": 0.0, "width": 200.0, "height": 20.0, "text": "", "lines": [] },
+            "version": 1
+        }],
+        "version": 1
+    });
+
+    let mut whiteboard: Whiteboard = Whiteboard::new("saver");
+    assert!(whiteboard.load_json(&empty_text.to_string()).expect("Board accepts loading"));
+    assert!(whiteboard.view_port().extent().expect("Can't lock view port").is_none(), "text box without lines has no bounds");
+
+    assert_eq!(entity_count(&whiteboard), 1);
+}
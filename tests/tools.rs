@@ -0,0 +1,316 @@
+mod harness;
+
+use harness::{click, down, drag, moved, up, Harness};
+use standard_entity_plugin::model::path_model::PathModel;
+use standard_tool_plugin::tool::Interaction;
+use std::cell::RefCell;
+use std::rc::Rc;
+use whiteboard_rs::bounding_box::BoundingBox;
+use whiteboard_rs::element::free_hand::smoothing::Smoothing;
+use whiteboard_rs::element::free_hand::{self, Segment};
+use whiteboard_rs::element::id::Id;
+use whiteboard_rs::element::json_entity::JsonEntity;
+use whiteboard_rs::element::ElementType;
+use whiteboard_rs::mode::BoardMode;
+use whiteboard_rs::renderer::renderer::recording_renderer::{DrawCall, RecordingRenderer};
+use whiteboard_rs::tool::eraser_tool::EraserMode;
+
+const ELEMENT_TYPES: [(ElementType, &str); 12] = [
+    (ElementType::Rectangle, "rectangle"),
+    (ElementType::Polygon, "polygon"),
+    (ElementType::FreeHand, "free hand"),
+    (ElementType::Text, "text"),
+    (ElementType::Container, "container"),
+    (ElementType::Connector, "connector"),
+    (ElementType::Ellipse, "ellipse"),
+    (ElementType::Line, "line"),
+    (ElementType::RegularPolygon, "regular polygon"),
+    (ElementType::Image, "image"),
+    (ElementType::StickyNote, "sticky note"),
+    (ElementType::Frame, "frame"),
+];
+
+const START: (f64, f64) = (100.0, 100.0);
+const END: (f64, f64) = (260.0, 200.0);
+
+/* Text boxes left empty are removed when editing finishes, so drawn ones get some text. */
+fn fill_text(harness: &mut Harness, element_type: ElementType) {
+    if element_type == ElementType::Text {
+        harness.whiteboard.text_input("Scripted").expect("Board accepts text");
+        harness.whiteboard.finish_text_editing();
+    }
+}
+
+fn move_draw(element_type: ElementType) -> Harness {
+    let mut harness: Harness = Harness::new();
+    harness.whiteboard.activate_move_draw(element_type).expect("Can't activate move draw");
+    harness.replay(drag(START, END));
+    fill_text(&mut harness, element_type);
+
+    harness
+}
+
+/* Click at the start, move to the end and click again. */
+fn click_draw_script() -> Vec<Interaction> {
+    let mut script: Vec<Interaction> = click(START);
+    script.push(moved(END.0, END.1));
+    script.extend(click(END));
+
+    script
+}
+
+fn drawn_calls(harness: &Harness) -> usize {
+    harness
+        .render()
+        .calls()
+        .iter()
+        .filter(|call| !matches!(call, DrawCall::Clear | DrawCall::Clip(_)))
+        .count()
+}
+
+#[test]
+fn move_draw_adds_one_entity_of_every_type() {
+    for (element_type, name) in ELEMENT_TYPES {
+        let harness: Harness = move_draw(element_type);
+
+        assert_eq!(harness.entities().len(), 1, "{name}: one entity is drawn");
+        assert_eq!(harness.entities_of(element_type).len(), 1, "{name}: entity has the tool type");
+        assert!(drawn_calls(&harness) > 0, "{name}: drawn entity is rendered");
+    }
+}
+
+#[test]
+fn move_draw_is_undone() {
+    for (element_type, name) in ELEMENT_TYPES {
+        let mut harness: Harness = move_draw(element_type);
+
+        while harness.whiteboard.undo().expect("Can't undo") {}
+
+        assert!(harness.entities().is_empty(), "{name}: board is empty after undo");
+    }
+}
+
+#[test]
+fn shapes_follow_the_drag() {
+    for element_type in [ElementType::Ellipse, ElementType::RegularPolygon] {
+        let harness: Harness = move_draw(element_type);
+        let id: Id = harness.entities()[0].id.clone();
+
+        let bounding_box: BoundingBox = harness
+            .whiteboard
+            .view_port()
+            .bounding_box(&id)
+            .expect("Can't lock view port")
+            .expect("Drawn shape has bounds");
+
+        assert!(bounding_box.contains_point(START.0 + 1.0, START.1 + 1.0));
+        assert!(bounding_box.contains_point(END.0 - 1.0, END.1 - 1.0));
+    }
+}
+
+#[test]
+fn shift_keeps_shapes_square() {
+    let mut harness: Harness = Harness::new();
+    harness.whiteboard.activate_move_draw(ElementType::Ellipse).expect("Can't activate move draw");
+    harness.replay_holding("Shift", drag(START, END));
+
+    let id: Id = harness.entities()[0].id.clone();
+    let bounding_box: BoundingBox = harness
+        .whiteboard
+        .view_port()
+        .bounding_box(&id)
+        .expect("Can't lock view port")
+        .expect("Drawn shape has bounds");
+
+    /* Drag is wider than high, the shape is as high as the drag is wide. */
+    assert!(bounding_box.contains_point(START.0 + 1.0, START.1 + (END.0 - START.0) - 1.0));
+}
+
+#[test]
+fn click_draw_adds_entities_of_the_tool_type() {
+    for (element_type, name) in ELEMENT_TYPES {
+        let mut harness: Harness = Harness::new();
+        harness.whiteboard.activate_click_draw(element_type).expect("Can't activate click draw");
+        harness.replay(click_draw_script());
+        fill_text(&mut harness, element_type);
+
+        let entities: Vec<JsonEntity> = harness.entities();
+
+        /* Connectors are drawn by dragging, clicks are ignored. */
+        if element_type == ElementType::Connector {
+            assert!(entities.is_empty(), "{name}: click doesn't add a connector");
+            continue;
+        }
+
+        assert!(!entities.is_empty(), "{name}: click draws an entity");
+        assert!(
+            entities.iter().all(|entity| *entity.id.element_type() == element_type),
+            "{name}: only entities of the tool type are drawn",
+        );
+    }
+}
+
+fn moved_by(from: &BoundingBox, to: &BoundingBox, x: f64, y: f64) -> bool {
+    let offsets: [(f64, f64); 4] = [(to.left - from.left, x), (to.top - from.top, y), (to.right - from.right, x), (to.bottom - from.bottom, y)];
+
+    offsets.iter().all(|(offset, expected)| (offset - expected).abs() < 1e-6)
+}
+
+#[test]
+fn select_drag_keeps_entities() {
+    for (element_type, name) in ELEMENT_TYPES {
+        let mut harness: Harness = move_draw(element_type);
+        let before: String = harness.snapshot();
+
+        harness.whiteboard.activate_select_tool();
+        harness.replay(drag((START.0 - 20.0, START.1 - 20.0), (END.0 + 20.0, END.1 + 20.0)));
+
+        assert_eq!(harness.snapshot(), before, "{name}: selecting doesn't change the board");
+        assert!(drawn_calls(&harness) > 0, "{name}: selected entity is still rendered");
+
+        let id: Id = harness.entities()[0].id.clone();
+        let bounding_box = |harness: &Harness| -> BoundingBox {
+            harness.whiteboard.view_port().bounding_box(&id).expect("Can't lock view port").expect("Entity has bounds")
+        };
+        let drawn: BoundingBox = bounding_box(&harness);
+        let center: (f64, f64) = ((drawn.left + drawn.right) / 2.0, (drawn.top + drawn.bottom) / 2.0);
+
+        harness.replay(drag(center, (center.0 + 40.0, center.1 + 30.0)));
+
+        assert!(moved_by(&drawn, &bounding_box(&harness), 40.0, 30.0), "{name}: entity follows the drag");
+
+        assert!(harness.whiteboard.undo().expect("Can't undo"), "{name}: move is undoable");
+        assert!(moved_by(&drawn, &bounding_box(&harness), 0.0, 0.0), "{name}: undo puts the entity back");
+    }
+}
+
+#[test]
+fn read_only_board_ignores_scripts() {
+    let mut harness: Harness = Harness::new();
+    harness.whiteboard.activate_move_draw(ElementType::Rectangle).expect("Can't activate move draw");
+    harness.whiteboard.set_mode(BoardMode::ReadOnly);
+    harness.replay(drag(START, END));
+
+    assert!(harness.entities().is_empty());
+}
+
+#[test]
+fn read_only_board_selects_without_moving() {
+    let mut harness: Harness = move_draw(ElementType::Rectangle);
+    let before: String = harness.snapshot();
+    let center: (f64, f64) = ((START.0 + END.0) / 2.0, (START.1 + END.1) / 2.0);
+
+    harness.whiteboard.set_mode(BoardMode::ReadOnly);
+    harness.whiteboard.activate_select_tool();
+    harness.replay(drag(center, (center.0 + 40.0, center.1 + 30.0)));
+
+    assert_eq!(harness.snapshot(), before);
+    assert!(harness.whiteboard.text_input("Refused").is_err());
+}
+
+#[test]
+fn figures_carry_the_entity_style() {
+    let mut harness: Harness = Harness::new();
+    harness.whiteboard.set_current_style(r#"{"stroke_color": "#d32f2f"}"#);
+    harness.whiteboard.activate_move_draw(ElementType::Ellipse).expect("Can't activate move draw");
+    harness.replay(drag(START, END));
+
+    let recording_renderer: RecordingRenderer = harness.render();
+    let styled: bool = recording_renderer.calls().iter().any(|call| matches!(
+        call,
+        DrawCall::Figure { entity_style: Some(style), .. } if style.stroke_color == "#d32f2f"
+    ));
+
+    assert!(recording_renderer.figures().count() > 0);
+    assert!(styled, "ellipse is drawn with the current style");
+
+    let other: Harness = move_draw(ElementType::Ellipse);
+    assert!(other.entities().iter().all(|entity| entity.style.is_none()), "other boards keep their own style");
+}
+
+#[test]
+fn recognized_shape_keeps_the_stroke_style() {
+    let mut harness: Harness = Harness::new();
+    let offered: Rc<RefCell<Option<Id>>> = Rc::new(RefCell::new(None));
+    let offer: Rc<RefCell<Option<Id>>> = offered.clone();
+
+    harness.whiteboard.enable_shape_recognition_with(0.0, move |stroke_id, _, _| *offer.borrow_mut() = Some(stroke_id.clone()));
+    harness.whiteboard.set_current_style(r#"{"stroke_color": "#d32f2f", "stroke_width": 4.0}"#);
+    harness.whiteboard.activate_move_draw(ElementType::FreeHand).expect("Can't activate move draw");
+
+    let mut script: Vec<Interaction> = vec![down(START.0, START.1)];
+    script.extend((1..=20).map(|step| moved(START.0 + step as f64 * 8.0, START.1 + step as f64 * 5.0)));
+    script.push(up(END.0, END.1));
+    harness.replay(script);
+
+    let stroke_id: Id = offered.borrow().clone().expect("Stroke is recognized");
+    harness.whiteboard.set_current_style(r#"{"stroke_color": "#1971c2"}"#);
+    assert!(harness.whiteboard.accept_recognized_shape(&stroke_id).expect("Board accepts recognition"));
+
+    let entities: Vec<JsonEntity> = harness.entities();
+    let style = entities[0].style.as_ref().expect("Shape is styled");
+
+    assert_eq!(entities.len(), 1, "stroke is replaced");
+    assert_ne!(*entities[0].id.element_type(), ElementType::FreeHand);
+    assert_eq!((style.stroke_color.as_str(), style.stroke_width), ("#d32f2f", 4.0), "shape has the style of the stroke");
+}
+
+fn stroke_points(json_entity: &JsonEntity) -> Vec<(f64, f64)> {
+    let path_model: PathModel = serde_json::from_value(json_entity.model.clone()).expect("Stroke is a path");
+
+    free_hand::points(&path_model).iter().map(|point| (point.x, point.y)).collect()
+}
+
+#[test]
+fn pixel_eraser_cuts_strokes_in_place() {
+    let mut harness: Harness = move_draw(ElementType::FreeHand);
+    let stroke: JsonEntity = harness.entities()[0].clone();
+    let vertices: Vec<(f64, f64)> = stroke_points(&stroke);
+
+    harness.whiteboard.activate_move_draw(ElementType::Rectangle).expect("Can't activate move draw");
+    harness.replay(drag((300.0, 300.0), (400.0, 400.0)));
+
+    let eraser_x: f64 = (START.0 + END.0) / 2.0;
+    harness.whiteboard.activate_eraser(EraserMode::Pixel, 5.0).expect("Can't activate eraser");
+    harness.replay(drag((eraser_x, 0.0), (eraser_x, 300.0)));
+
+    let pieces: Vec<Vec<(f64, f64)>> = harness.entities_of(ElementType::FreeHand).iter().map(stroke_points).collect();
+    let points: Vec<(f64, f64)> = pieces.concat();
+    let outside: Vec<&(f64, f64)> = vertices.iter().filter(|(x, _)| (x - eraser_x).abs() > 5.0).collect();
+
+    assert_eq!(pieces.len(), 2, "stroke is cut in two");
+    assert!(outside.iter().all(|vertex| points.contains(vertex)), "vertices outside of the eraser are kept");
+    assert_eq!(points.len(), outside.len() + 2, "only the cut points are added");
+
+    assert!(harness.whiteboard.undo().expect("Can't undo"));
+    let entities: Vec<JsonEntity> = harness.entities();
+
+    assert_eq!(entities.len(), 2);
+    assert!(entities[0].id == stroke.id, "stroke is back below the rectangle");
+    assert_eq!(stroke_points(&entities[0]), vertices);
+}
+
+#[test]
+fn pixel_eraser_keeps_curves() {
+    let mut harness: Harness = Harness::new();
+    harness.whiteboard.set_stroke_options(Smoothing::CatmullRom, 0.0);
+    harness.whiteboard.activate_move_draw(ElementType::FreeHand).expect("Can't activate move draw");
+    harness.replay(drag(START, END));
+
+    let eraser_x: f64 = (START.0 + END.0) / 2.0;
+    harness.whiteboard.activate_eraser(EraserMode::Pixel, 5.0).expect("Can't activate eraser");
+    harness.replay(drag((eraser_x, 0.0), (eraser_x, 300.0)));
+
+    let pieces: Vec<Vec<Segment>> = harness
+        .entities_of(ElementType::FreeHand)
+        .iter()
+        .map(|piece| free_hand::segments(&serde_json::from_value(piece.model.clone()).expect("Piece is a path")))
+        .collect();
+
+    assert_eq!(pieces.len(), 2, "stroke is cut in two");
+    assert!(
+        pieces.iter().flatten().all(|segment| matches!(segment, Segment::Curve { .. })),
+        "pieces are curves like the stroke",
+    );
+}
+